@@ -0,0 +1,198 @@
+use static_assertions::assert_impl_all;
+use std::{convert::TryInto, ops::Deref};
+use zbus_names::{BusName, InterfaceName};
+use zvariant::{ObjectPath, OwnedValue, Value};
+
+use crate::{
+    blocking::{Connection, Proxy, ProxyBuilder, SignalIterator},
+    dynamic_proxy::{
+        call_body, check_property_value, find_interface, find_method, find_property, find_signal,
+        is_no_reply, method_or_err, out_args, property_arg, readable_property, reply_values,
+        signal_or_err, signal_values,
+    },
+    quick_xml::{Interface, Method, Property, Signal},
+    CacheProperties, Error, Message, Result,
+};
+
+/// A blocking wrapper of [`crate::DynamicProxy`].
+///
+/// This API is mostly the same as [`crate::DynamicProxy`], except that all its methods block to
+/// completion.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::blocking::{Connection, DynamicProxy};
+/// use zvariant::Value;
+///
+/// let connection = Connection::session()?;
+/// let proxy = DynamicProxy::new(
+///     &connection,
+///     "org.freedesktop.DBus",
+///     "/org/freedesktop/DBus",
+///     "org.freedesktop.DBus",
+/// )?;
+///
+/// let reply = proxy.call("GetNameOwner", &[Value::from("org.freedesktop.DBus")])?;
+/// let owner: &str = reply[0].downcast_ref().unwrap();
+/// println!("Owner: {owner}");
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynamicProxy<'a> {
+    proxy: Proxy<'a>,
+    interface: Interface<'static>,
+}
+
+assert_impl_all!(DynamicProxy<'_>: Send, Sync, Unpin);
+
+impl<'a> DynamicProxy<'a> {
+    /// Create a new `DynamicProxy` for the given destination/path/interface.
+    ///
+    /// See [`crate::DynamicProxy::new`] for details.
+    pub fn new<D, P, I>(
+        conn: &Connection,
+        destination: D,
+        path: P,
+        interface: I,
+    ) -> Result<DynamicProxy<'a>>
+    where
+        D: TryInto<BusName<'a>>,
+        P: TryInto<ObjectPath<'a>>,
+        I: TryInto<InterfaceName<'a>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+    {
+        let proxy = ProxyBuilder::new_bare(conn)
+            .destination(destination)?
+            .path(path)?
+            .interface(interface)?
+            .cache_properties(CacheProperties::No)
+            .build()?;
+
+        Self::from_proxy(proxy)
+    }
+
+    /// Create a new `DynamicProxy` from an existing [`Proxy`].
+    ///
+    /// See [`crate::DynamicProxy::from_proxy`] for details.
+    pub fn from_proxy(proxy: Proxy<'a>) -> Result<DynamicProxy<'a>> {
+        let xml = proxy.introspect()?;
+        let interface = find_interface(&xml, proxy.interface())?;
+
+        Ok(Self { proxy, interface })
+    }
+
+    /// The introspected description of the interface.
+    pub fn introspection(&self) -> &Interface<'static> {
+        &self.interface
+    }
+
+    /// The methods of the interface.
+    pub fn methods(&self) -> &[Method<'static>] {
+        self.interface.methods()
+    }
+
+    /// The signals of the interface.
+    pub fn signals(&self) -> &[Signal<'static>] {
+        self.interface.signals()
+    }
+
+    /// The properties of the interface.
+    pub fn properties(&self) -> &[Property<'_>] {
+        self.interface.properties()
+    }
+
+    /// Get the description of the method `name`, if the interface has one.
+    pub fn method(&self, name: &str) -> Option<&Method<'static>> {
+        find_method(&self.interface, name)
+    }
+
+    /// Get the description of the signal `name`, if the interface has one.
+    pub fn signal(&self, name: &str) -> Option<&Signal<'static>> {
+        find_signal(&self.interface, name)
+    }
+
+    /// Get the description of the property `name`, if the interface has one.
+    pub fn property(&self, name: &str) -> Option<&Property<'_>> {
+        find_property(&self.interface, name)
+    }
+
+    /// Call the method `method_name` with the given arguments.
+    ///
+    /// See [`crate::DynamicProxy::call`] for details.
+    pub fn call(&self, method_name: &str, args: &[Value<'_>]) -> Result<Vec<OwnedValue>> {
+        let method = method_or_err(&self.interface, method_name)?;
+        let body = call_body(method, args)?;
+
+        if is_no_reply(method) {
+            match &body {
+                Some(body) => self.proxy.call_noreply(method_name, body)?,
+                None => self.proxy.call_noreply(method_name, &())?,
+            }
+
+            return Ok(vec![]);
+        }
+
+        let reply = match &body {
+            Some(body) => self.proxy.call_method(method_name, body)?,
+            None => self.proxy.call_method(method_name, &())?,
+        };
+
+        reply_values(method_name, &reply, out_args(method))
+    }
+
+    /// Get the value of the property `property_name`.
+    ///
+    /// See [`crate::DynamicProxy::get_property`] for details.
+    pub fn get_property(&self, property_name: &str) -> Result<OwnedValue> {
+        let property = readable_property(&self.interface, property_name)?;
+        let value = self.proxy.get_property::<OwnedValue>(property_name)?;
+        check_property_value(property, value)
+    }
+
+    /// Set the value of the property `property_name`.
+    ///
+    /// See [`crate::DynamicProxy::set_property`] for details.
+    pub fn set_property(&self, property_name: &str, value: Value<'_>) -> Result<()> {
+        let value = property_arg(&self.interface, property_name, value)?;
+        self.proxy
+            .set_property(property_name, value)
+            .map_err(Into::into)
+    }
+
+    /// Create an iterator for the signal `signal_name`.
+    ///
+    /// Use [`DynamicProxy::signal_args`] to extract the arguments of the received signals.
+    pub fn receive_signal<'n>(&self, signal_name: &'n str) -> Result<SignalIterator<'n>> {
+        signal_or_err(&self.interface, signal_name)?;
+        self.proxy.receive_signal(signal_name)
+    }
+
+    /// Extract the arguments of a signal message received through this proxy.
+    ///
+    /// See [`crate::DynamicProxy::signal_args`] for details.
+    pub fn signal_args(&self, msg: &Message) -> Result<Vec<OwnedValue>> {
+        signal_values(&self.interface, msg)
+    }
+
+    /// Get a reference to the underlying [`Proxy`].
+    pub fn inner(&self) -> &Proxy<'a> {
+        &self.proxy
+    }
+
+    /// Get the underlying [`Proxy`], consuming `self`.
+    pub fn into_inner(self) -> Proxy<'a> {
+        self.proxy
+    }
+}
+
+impl<'a> Deref for DynamicProxy<'a> {
+    type Target = Proxy<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.proxy
+    }
+}
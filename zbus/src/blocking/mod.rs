@@ -28,4 +28,8 @@ mod proxy;
 pub use proxy::*;
 mod proxy_builder;
 pub use proxy_builder::*;
+#[cfg(feature = "quick-xml")]
+mod dynamic_proxy;
+#[cfg(feature = "quick-xml")]
+pub use dynamic_proxy::*;
 pub mod fdo;
@@ -150,6 +150,17 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().introspect())
     }
 
+    /// Check the interface against the introspection data of the associated object.
+    ///
+    /// See [`crate::Proxy::verify_against_introspection`] for details.
+    #[cfg(feature = "quick-xml")]
+    pub fn verify_against_introspection(
+        &self,
+        expected: &str,
+    ) -> Result<Vec<crate::quick_xml::Mismatch>> {
+        block_on(self.inner().verify_against_introspection(expected))
+    }
+
     /// Get the cached value of the property `property_name`.
     ///
     /// This returns `None` if the property is not in the cache.  This could be because the cache
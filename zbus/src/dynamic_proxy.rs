@@ -0,0 +1,639 @@
+use static_assertions::assert_impl_all;
+use std::{convert::TryInto, ops::Deref};
+use zbus_names::{BusName, InterfaceName};
+use zvariant::{ObjectPath, OwnedValue, Structure, StructureBuilder, Value};
+
+use crate::{
+    fdo,
+    quick_xml::{Arg, ArgDirection, Interface, Method, Node, Property, Signal},
+    CacheProperties, Connection, Error, Message, Proxy, ProxyBuilder, Result, SignalStream,
+};
+
+/// A client-side proxy whose interface is only known at runtime.
+///
+/// Unlike the proxies generated by [`dbus_proxy`], a `DynamicProxy` doesn't need a Rust trait
+/// describing the interface. Instead, it introspects the remote object on creation and uses the
+/// resulting description to expose the methods, signals and properties of the interface as
+/// runtime metadata. This is useful for generic tools (debuggers, bridges to scripting languages,
+/// etc.) that need to talk to arbitrary services.
+///
+/// All calls are type-checked against the introspected signatures: arguments that don't match
+/// are rejected with [`fdo::Error::InvalidArgs`] before anything is sent on the bus, and replies
+/// are checked before being returned as a list of [`OwnedValue`]s.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use zbus::{Connection, DynamicProxy};
+/// # use zvariant::Value;
+/// #
+/// # async_io::block_on(async {
+/// let connection = Connection::session().await?;
+/// let proxy = DynamicProxy::new(
+///     &connection,
+///     "org.freedesktop.DBus",
+///     "/org/freedesktop/DBus",
+///     "org.freedesktop.DBus",
+/// )
+/// .await?;
+///
+/// for method in proxy.methods() {
+///     println!("{}", method.name());
+/// }
+///
+/// let reply = proxy
+///     .call("GetNameOwner", &[Value::from("org.freedesktop.DBus")])
+///     .await?;
+/// let owner: &str = reply[0].downcast_ref().unwrap();
+/// println!("Owner: {owner}");
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+///
+/// [`dbus_proxy`]: attr.dbus_proxy.html
+#[derive(Clone, Debug)]
+pub struct DynamicProxy<'a> {
+    proxy: Proxy<'a>,
+    interface: Interface<'static>,
+}
+
+assert_impl_all!(DynamicProxy<'_>: Send, Sync, Unpin);
+
+impl<'a> DynamicProxy<'a> {
+    /// Create a new `DynamicProxy` for the given destination/path/interface.
+    ///
+    /// The remote object is introspected to retrieve the description of `interface`. If the object
+    /// doesn't implement it, [`Error::InterfaceNotFound`] is returned.
+    pub async fn new<D, P, I>(
+        conn: &Connection,
+        destination: D,
+        path: P,
+        interface: I,
+    ) -> Result<DynamicProxy<'a>>
+    where
+        D: TryInto<BusName<'a>>,
+        P: TryInto<ObjectPath<'a>>,
+        I: TryInto<InterfaceName<'a>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+    {
+        let proxy = ProxyBuilder::new_bare(conn)
+            .destination(destination)?
+            .path(path)?
+            .interface(interface)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+
+        Self::from_proxy(proxy).await
+    }
+
+    /// Create a new `DynamicProxy` from an existing [`Proxy`].
+    ///
+    /// The remote object is introspected to retrieve the description of the interface of `proxy`.
+    /// If the object doesn't implement it, [`Error::InterfaceNotFound`] is returned.
+    pub async fn from_proxy(proxy: Proxy<'a>) -> Result<DynamicProxy<'a>> {
+        let xml = proxy.introspect().await?;
+        let interface = find_interface(&xml, proxy.interface())?;
+
+        Ok(Self { proxy, interface })
+    }
+
+    /// The introspected description of the interface.
+    pub fn introspection(&self) -> &Interface<'static> {
+        &self.interface
+    }
+
+    /// The methods of the interface.
+    pub fn methods(&self) -> &[Method<'static>] {
+        self.interface.methods()
+    }
+
+    /// The signals of the interface.
+    pub fn signals(&self) -> &[Signal<'static>] {
+        self.interface.signals()
+    }
+
+    /// The properties of the interface.
+    pub fn properties(&self) -> &[Property<'_>] {
+        self.interface.properties()
+    }
+
+    /// Get the description of the method `name`, if the interface has one.
+    pub fn method(&self, name: &str) -> Option<&Method<'static>> {
+        find_method(&self.interface, name)
+    }
+
+    /// Get the description of the signal `name`, if the interface has one.
+    pub fn signal(&self, name: &str) -> Option<&Signal<'static>> {
+        find_signal(&self.interface, name)
+    }
+
+    /// Get the description of the property `name`, if the interface has one.
+    pub fn property(&self, name: &str) -> Option<&Property<'_>> {
+        find_property(&self.interface, name)
+    }
+
+    /// Call the method `method_name` with the given arguments.
+    ///
+    /// The arguments are checked against the introspected input arguments of the method. Arguments
+    /// of type `v` may be passed either as a [`Value::Value`] or as the bare value, which then gets
+    /// wrapped automatically.
+    ///
+    /// On success, the output arguments of the method are returned, after checking them against the
+    /// introspected signature. Methods annotated with `org.freedesktop.DBus.Method.NoReply` are
+    /// called without waiting for a reply, and an empty list is returned.
+    pub async fn call(&self, method_name: &str, args: &[Value<'_>]) -> Result<Vec<OwnedValue>> {
+        let method = method_or_err(&self.interface, method_name)?;
+        let body = call_body(method, args)?;
+
+        if is_no_reply(method) {
+            match &body {
+                Some(body) => self.proxy.call_noreply(method_name, body).await?,
+                None => self.proxy.call_noreply(method_name, &()).await?,
+            }
+
+            return Ok(vec![]);
+        }
+
+        let reply = match &body {
+            Some(body) => self.proxy.call_method(method_name, body).await?,
+            None => self.proxy.call_method(method_name, &()).await?,
+        };
+
+        reply_values(method_name, &reply, out_args(method))
+    }
+
+    /// Get the value of the property `property_name`.
+    ///
+    /// The property must be readable and the returned value is checked against its introspected
+    /// type.
+    pub async fn get_property(&self, property_name: &str) -> Result<OwnedValue> {
+        let property = readable_property(&self.interface, property_name)?;
+        let value = self.proxy.get_property::<OwnedValue>(property_name).await?;
+        check_property_value(property, value)
+    }
+
+    /// Set the value of the property `property_name`.
+    ///
+    /// The property must be writable and `value` must match its introspected type. If the property
+    /// is of type `v`, `value` is wrapped as needed.
+    pub async fn set_property(&self, property_name: &str, value: Value<'_>) -> Result<()> {
+        let value = property_arg(&self.interface, property_name, value)?;
+        self.proxy
+            .set_property(property_name, value)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Create a stream for the signal `signal_name`.
+    ///
+    /// Use [`DynamicProxy::signal_args`] to extract the arguments of the received signals.
+    pub async fn receive_signal<'n>(&self, signal_name: &'n str) -> Result<SignalStream<'n>> {
+        signal_or_err(&self.interface, signal_name)?;
+        self.proxy.receive_signal(signal_name).await
+    }
+
+    /// Extract the arguments of a signal message received through this proxy.
+    ///
+    /// The arguments are checked against the introspected signature of the signal.
+    pub fn signal_args(&self, msg: &Message) -> Result<Vec<OwnedValue>> {
+        signal_values(&self.interface, msg)
+    }
+
+    /// Get a reference to the underlying [`Proxy`].
+    pub fn inner(&self) -> &Proxy<'a> {
+        &self.proxy
+    }
+
+    /// Get the underlying [`Proxy`], consuming `self`.
+    pub fn into_inner(self) -> Proxy<'a> {
+        self.proxy
+    }
+}
+
+impl<'a> Deref for DynamicProxy<'a> {
+    type Target = Proxy<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.proxy
+    }
+}
+
+// The helpers below are shared with `blocking::DynamicProxy`.
+
+pub(crate) fn find_interface(xml: &str, name: &InterfaceName<'_>) -> Result<Interface<'static>> {
+    let node: Node<'static> = Node::from_reader(xml.as_bytes())?;
+
+    node.interfaces()
+        .iter()
+        .find(|i| i.name() == *name)
+        .cloned()
+        .ok_or(Error::InterfaceNotFound)
+}
+
+pub(crate) fn find_method<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Option<&'i Method<'static>> {
+    interface.methods().iter().find(|m| m.name() == name)
+}
+
+pub(crate) fn find_signal<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Option<&'i Signal<'static>> {
+    interface.signals().iter().find(|s| s.name() == name)
+}
+
+pub(crate) fn find_property<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Option<&'i Property<'i>> {
+    interface.properties().iter().find(|p| p.name() == name)
+}
+
+pub(crate) fn method_or_err<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Result<&'i Method<'static>> {
+    find_method(interface, name).ok_or_else(|| {
+        fdo::Error::UnknownMethod(format!(
+            "Unknown method `{name}` on interface `{}`",
+            interface.name()
+        ))
+        .into()
+    })
+}
+
+pub(crate) fn signal_or_err<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Result<&'i Signal<'static>> {
+    find_signal(interface, name).ok_or_else(|| {
+        Error::Failure(format!(
+            "Unknown signal `{name}` on interface `{}`",
+            interface.name()
+        ))
+    })
+}
+
+fn property_or_err<'i>(interface: &'i Interface<'static>, name: &str) -> Result<&'i Property<'i>> {
+    find_property(interface, name).ok_or_else(|| {
+        fdo::Error::UnknownProperty(format!(
+            "Unknown property `{name}` on interface `{}`",
+            interface.name()
+        ))
+        .into()
+    })
+}
+
+pub(crate) fn readable_property<'i>(
+    interface: &'i Interface<'static>,
+    name: &str,
+) -> Result<&'i Property<'i>> {
+    let property = property_or_err(interface, name)?;
+    if !property.access().read() {
+        return Err(fdo::Error::AccessDenied(format!("Property `{name}` is not readable")).into());
+    }
+
+    Ok(property)
+}
+
+pub(crate) fn check_property_value(
+    property: &Property<'_>,
+    value: OwnedValue,
+) -> Result<OwnedValue> {
+    let value = as_type(&value, property.ty())
+        .map(OwnedValue::from)
+        .ok_or_else(|| {
+            Error::Failure(format!(
+                "Property `{}` has type `{}`, got a value of type `{}`",
+                property.name(),
+                property.ty(),
+                value.value_signature(),
+            ))
+        })?;
+
+    Ok(value)
+}
+
+pub(crate) fn property_arg<'v>(
+    interface: &Interface<'static>,
+    name: &str,
+    value: Value<'v>,
+) -> Result<Value<'v>> {
+    let property = property_or_err(interface, name)?;
+    if !property.access().write() {
+        return Err(
+            fdo::Error::PropertyReadOnly(format!("Property `{name}` is not writable")).into(),
+        );
+    }
+
+    as_type(&value, property.ty()).ok_or_else(|| {
+        fdo::Error::InvalidArgs(format!(
+            "Property `{name}` has type `{}`, got a value of type `{}`",
+            property.ty(),
+            value.value_signature(),
+        ))
+        .into()
+    })
+}
+
+pub(crate) fn is_no_reply(method: &Method<'_>) -> bool {
+    method
+        .annotations()
+        .iter()
+        .any(|a| a.name() == "org.freedesktop.DBus.Method.NoReply" && a.value() == "true")
+}
+
+fn in_args<'m>(method: &'m Method<'_>) -> impl Iterator<Item = &'m Arg> {
+    method
+        .args()
+        .iter()
+        .filter(|a| a.direction() != Some(ArgDirection::Out))
+}
+
+pub(crate) fn out_args<'m>(method: &'m Method<'_>) -> impl Iterator<Item = &'m Arg> {
+    method
+        .args()
+        .iter()
+        .filter(|a| a.direction() == Some(ArgDirection::Out))
+}
+
+/// Check `args` against the input arguments of `method` and build the message body out of them.
+///
+/// Returns `None` if the method takes no arguments.
+pub(crate) fn call_body(
+    method: &Method<'_>,
+    args: &[Value<'_>],
+) -> Result<Option<Structure<'static>>> {
+    let name = method.name();
+    let expected: Vec<_> = in_args(method).collect();
+    if expected.len() != args.len() {
+        return Err(fdo::Error::InvalidArgs(format!(
+            "Method `{name}` expects {} argument(s), got {}",
+            expected.len(),
+            args.len(),
+        ))
+        .into());
+    }
+    if args.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = StructureBuilder::new();
+    for (i, (arg, value)) in expected.into_iter().zip(args).enumerate() {
+        let value = as_type(value, arg.ty()).ok_or_else(|| {
+            fdo::Error::InvalidArgs(format!(
+                "Argument {i}{} of method `{name}` has type `{}`, got a value of type `{}`",
+                arg.name().map(|n| format!(" (`{n}`)")).unwrap_or_default(),
+                arg.ty(),
+                value.value_signature(),
+            ))
+        })?;
+        builder = builder.append_field(value.to_owned().into());
+    }
+
+    Ok(Some(builder.build()))
+}
+
+/// Extract the values from `msg`, checking they match the types of `args`.
+pub(crate) fn reply_values<'m>(
+    member: &str,
+    msg: &Message,
+    args: impl Iterator<Item = &'m Arg>,
+) -> Result<Vec<OwnedValue>> {
+    let args: Vec<_> = args.collect();
+    let expected: String = args.iter().map(|a| a.ty()).collect();
+    let signature = match msg.body_signature() {
+        Ok(signature) => signature.to_string(),
+        Err(Error::NoBodySignature) => String::new(),
+        Err(e) => return Err(e),
+    };
+    if signature != expected {
+        return Err(Error::Failure(format!(
+            "Expected `{member}` to carry arguments of type `{expected}`, got `{signature}`"
+        )));
+    }
+    if signature.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let body: Structure<'_> = msg.body()?;
+    // A body made of a single structure is deserialized as that structure, not as its fields.
+    if args.len() == 1 && expected.starts_with(zvariant::STRUCT_SIG_START_CHAR) {
+        return Ok(vec![Value::from(body).into()]);
+    }
+
+    Ok(body
+        .into_fields()
+        .into_iter()
+        .map(OwnedValue::from)
+        .collect())
+}
+
+pub(crate) fn signal_values(
+    interface: &Interface<'static>,
+    msg: &Message,
+) -> Result<Vec<OwnedValue>> {
+    let member = msg.member().ok_or(Error::MissingField)?;
+    let signal = signal_or_err(interface, &member)?;
+
+    reply_values(&member, msg, signal.args().iter())
+}
+
+/// Return `value` as a value of type `ty`, if it is one.
+///
+/// Values passed for an argument of type `v` are wrapped in a [`Value::Value`] if needed.
+fn as_type<'v>(value: &Value<'v>, ty: &str) -> Option<Value<'v>> {
+    if value.value_signature() == ty {
+        Some(value.clone())
+    } else if ty == "v" {
+        Some(Value::Value(Box::new(value.clone())))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dbus_interface, utils::block_on, ConnectionBuilder, SignalContext};
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    struct Calculator {
+        total: u32,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.zbus.DynamicProxyTest")]
+    impl Calculator {
+        fn add(&self, a: u32, b: u32) -> u32 {
+            a + b
+        }
+
+        fn describe(&self, value: Value<'_>) -> (String, u8) {
+            (value.value_signature().to_string(), 42)
+        }
+
+        fn reset(&mut self) {
+            self.total = 0;
+        }
+
+        #[dbus_interface(property)]
+        fn total(&self) -> u32 {
+            self.total
+        }
+
+        #[dbus_interface(property)]
+        fn set_total(&mut self, total: u32) {
+            self.total = total;
+        }
+
+        #[dbus_interface(property)]
+        fn name(&self) -> &str {
+            "calculator"
+        }
+
+        #[dbus_interface(signal)]
+        async fn overflow(ctxt: &SignalContext<'_>, value: u32) -> Result<()>;
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn dynamic_proxy() {
+        block_on(test_dynamic_proxy()).unwrap();
+    }
+
+    async fn test_dynamic_proxy() -> Result<()> {
+        let service = ConnectionBuilder::session()?
+            .serve_at("/calculator", Calculator { total: 1 })?
+            .build()
+            .await?;
+        let conn = Connection::session().await?;
+        let proxy = DynamicProxy::new(
+            &conn,
+            service.unique_name().unwrap(),
+            "/calculator",
+            "org.freedesktop.zbus.DynamicProxyTest",
+        )
+        .await?;
+
+        let methods: Vec<_> = proxy.methods().iter().map(|m| m.name()).collect();
+        assert_eq!(methods, ["Add", "Describe", "Reset"]);
+        assert!(proxy.signal("Overflow").is_some());
+        assert!(proxy.property("Total").unwrap().access().write());
+
+        // Method calls.
+        let reply = proxy
+            .call("Add", &[Value::from(2u32), Value::from(3u32)])
+            .await?;
+        assert_eq!(reply, [OwnedValue::from(5u32)]);
+        let reply = proxy.call("Describe", &[Value::from(1.5f64)]).await?;
+        assert_eq!(
+            reply,
+            [OwnedValue::from(Value::from("d")), OwnedValue::from(42u8)]
+        );
+        assert!(proxy.call("Reset", &[]).await?.is_empty());
+
+        // Arguments are checked before sending.
+        let err = proxy
+            .call("Add", &[Value::from(2u32), Value::from("3")])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FDO(e) if matches!(*e, fdo::Error::InvalidArgs(_))));
+        let err = proxy.call("Add", &[]).await.unwrap_err();
+        assert!(matches!(err, Error::FDO(e) if matches!(*e, fdo::Error::InvalidArgs(_))));
+        let err = proxy.call("Subtract", &[]).await.unwrap_err();
+        assert!(matches!(err, Error::FDO(e) if matches!(*e, fdo::Error::UnknownMethod(_))));
+
+        // Properties.
+        assert_eq!(proxy.get_property("Total").await?, OwnedValue::from(0u32));
+        proxy.set_property("Total", Value::from(7u32)).await?;
+        assert_eq!(proxy.get_property("Total").await?, OwnedValue::from(7u32));
+        let err = proxy
+            .set_property("Total", Value::from("7"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FDO(e) if matches!(*e, fdo::Error::InvalidArgs(_))));
+        let err = proxy
+            .set_property("Name", Value::from("x"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FDO(e) if matches!(*e, fdo::Error::PropertyReadOnly(_))));
+
+        // Signals.
+        let mut stream = proxy.receive_signal("Overflow").await?;
+        let ctxt = SignalContext::new(&service, "/calculator")?;
+        Calculator::overflow(&ctxt, 99).await?;
+        let msg = stream.next().await.unwrap();
+        assert_eq!(proxy.signal_args(&msg)?, [OwnedValue::from(99u32)]);
+        assert!(proxy.receive_signal("Underflow").await.is_err());
+
+        // Unknown interfaces are reported.
+        let err = DynamicProxy::new(
+            &conn,
+            service.unique_name().unwrap(),
+            "/calculator",
+            "org.freedesktop.zbus.DoesNotExist",
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err, Error::InterfaceNotFound);
+
+        Ok(())
+    }
+
+    #[test]
+    fn struct_args() -> Result<()> {
+        let xml = r#"
+<node>
+  <interface name="org.freedesktop.zbus.DynamicProxyTest">
+    <signal name="Moved">
+      <arg type="(su)"/>
+    </signal>
+    <signal name="Renamed">
+      <arg type="(s)"/>
+      <arg type="u"/>
+    </signal>
+  </interface>
+</node>
+"#;
+        let interface = find_interface(
+            xml,
+            &InterfaceName::from_static_str("org.freedesktop.zbus.DynamicProxyTest")?,
+        )?;
+
+        // A single structure argument isn't mistaken for its fields.
+        let msg = crate::MessageBuilder::signal(
+            "/calculator",
+            "org.freedesktop.zbus.DynamicProxyTest",
+            "Moved",
+        )?
+        .build(&(("x", 1u32),))?;
+        assert_eq!(
+            signal_values(&interface, &msg)?,
+            [OwnedValue::from(Value::from(("x", 1u32)))]
+        );
+
+        let msg = crate::MessageBuilder::signal(
+            "/calculator",
+            "org.freedesktop.zbus.DynamicProxyTest",
+            "Renamed",
+        )?
+        .build(&(("x",), 1u32))?;
+        assert_eq!(
+            signal_values(&interface, &msg)?,
+            [
+                OwnedValue::from(Value::from(Structure::from(("x",)))),
+                OwnedValue::from(1u32)
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -75,6 +75,10 @@ mod proxy;
 pub use proxy::*;
 mod proxy_builder;
 pub use proxy_builder::*;
+#[cfg(feature = "quick-xml")]
+mod dynamic_proxy;
+#[cfg(feature = "quick-xml")]
+pub use dynamic_proxy::*;
 mod signal_context;
 pub use signal_context::*;
 mod interface;
@@ -120,6 +124,22 @@ pub mod export {
     pub use static_assertions;
 }
 
+// Keeps the given items only if the `quick-xml` feature is enabled. Used by macros, since they
+// can't check the features of zbus themselves.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "quick-xml")]
+macro_rules! __if_quick_xml {
+    ($($item:item)*) => { $($item)* };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "quick-xml"))]
+macro_rules! __if_quick_xml {
+    ($($item:item)*) => {};
+}
+
 pub use zbus_names as names;
 pub use zvariant;
 
@@ -1,3 +1,5 @@
+#[cfg(feature = "gvariant")]
+use std::collections::BTreeMap;
 use std::{
     convert::{Into, TryFrom, TryInto},
     fmt,
@@ -13,12 +15,16 @@ use std::{
 use enumflags2::BitFlags;
 use static_assertions::assert_impl_all;
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
+#[cfg(feature = "gvariant")]
+use zvariant::{Structure, Value};
 
 #[cfg(unix)]
 use crate::OwnedFd;
+#[cfg(feature = "gvariant")]
+use crate::GVARIANT_PROTOCOL_VERSION;
 use crate::{
     utils::padding_for_8_bytes,
-    zvariant::{DynamicType, EncodingContext, ObjectPath, Signature, Type},
+    zvariant::{DynamicType, EncodingContext, EncodingFormat, ObjectPath, Signature, Type},
     EndianSig, Error, MessageField, MessageFieldCode, MessageFields, MessageFlags, MessageHeader,
     MessagePrimaryHeader, MessageType, QuickMessageFields, Result, MAX_MESSAGE_SIZE,
     MIN_MESSAGE_SIZE, NATIVE_ENDIAN_SIG,
@@ -39,6 +45,54 @@ macro_rules! dbus_context {
     };
 }
 
+/// The header of a message in its GVariant representation: `(yyyyuta{tv})`.
+#[cfg(feature = "gvariant")]
+type GVariantHeader<'h> = (u8, u8, u8, u8, u32, u64, BTreeMap<u64, Value<'h>>);
+
+/// Split the GVariant representation of a message into the header, the body and its signature.
+#[cfg(feature = "gvariant")]
+fn split_gvariant(bytes: &[u8]) -> Result<(&[u8], &[u8], &str)> {
+    // The header is not the last member of the outer structure so its end is recorded in a framing
+    // offset at the end of the data, with the size of the offset depending on the size of the data.
+    let offset_size = match bytes.len() {
+        0..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8,
+    };
+    let data_len = bytes
+        .len()
+        .checked_sub(offset_size)
+        .ok_or(Error::InvalidField)?;
+    let header_end = bytes[data_len..]
+        .iter()
+        .rev()
+        .fold(0usize, |offset, b| (offset << 8) | *b as usize);
+    let body_start = header_end + padding_for_8_bytes(header_end);
+    if body_start > data_len {
+        return Err(Error::InvalidField);
+    }
+
+    // The body is a variant, so its signature comes after the last nul byte.
+    let body = &bytes[body_start..data_len];
+    let sig_start = body
+        .iter()
+        .rposition(|b| *b == b'\0')
+        .ok_or(Error::InvalidField)?;
+    let signature = std::str::from_utf8(&body[sig_start + 1..]).map_err(|_| Error::InvalidField)?;
+
+    Ok((&bytes[..header_end], &body[..sig_start], signature))
+}
+
+fn body_format_for_protocol_version(_version: u8) -> EncodingFormat {
+    #[cfg(feature = "gvariant")]
+    if _version == GVARIANT_PROTOCOL_VERSION {
+        return EncodingFormat::GVariant;
+    }
+
+    EncodingFormat::DBus
+}
+
 /// A builder for [`Message`]
 #[derive(Debug)]
 pub struct MessageBuilder<'a> {
@@ -114,6 +168,28 @@ impl<'a> MessageBuilder<'a> {
         Ok(self)
     }
 
+    /// Set the encoding format of the message body.
+    ///
+    /// By default, the body is encoded in the D-Bus format. Setting this to
+    /// [`EncodingFormat::GVariant`] encodes the body in the GVariant format instead and marks the
+    /// message with major protocol version `2`, as done by the GVariant-based D-Bus protocol. The
+    /// header is always encoded in the D-Bus format.
+    ///
+    /// **Note:** D-Bus brokers only understand protocol version `1` so messages with
+    /// GVariant-encoded bodies are only useful for peers that know about them, or for conversion
+    /// into the GVariant message representation through [`Message::to_gvariant`].
+    #[cfg(feature = "gvariant")]
+    pub fn body_format(mut self, format: EncodingFormat) -> Self {
+        let version = if format == EncodingFormat::GVariant {
+            GVARIANT_PROTOCOL_VERSION
+        } else {
+            1
+        };
+        self.header.primary_mut().set_protocol_version(version);
+
+        self
+    }
+
     /// Set the unique name of the sending connection.
     pub fn sender<'s: 'a, S>(mut self, sender: S) -> Result<Self>
     where
@@ -212,7 +288,8 @@ impl<'a> MessageBuilder<'a> {
     where
         B: serde::ser::Serialize + DynamicType,
     {
-        let ctxt = dbus_context!(0);
+        let format = body_format_for_protocol_version(self.header.primary().protocol_version());
+        let ctxt = EncodingContext::<byteorder::NativeEndian>::new(format, 0);
 
         // Note: this iterates the body twice, but we prefer efficient handling of large messages
         // to efficient handling of ones that are complex to serialize.
@@ -244,7 +321,8 @@ impl<'a> MessageBuilder<'a> {
     }
 
     /// Create a new message from a raw slice of bytes to populate the body with, rather than by
-    /// serializing a value. The message body will be the exact bytes.
+    /// serializing a value. The message body will be the exact bytes, which must be encoded in the
+    /// body encoding format of the message.
     ///
     /// # Safety
     ///
@@ -536,6 +614,130 @@ impl Message {
         })
     }
 
+    /// Create a message from its GVariant representation.
+    ///
+    /// This is the inverse of [`Message::to_gvariant`]. The resulting message has a
+    /// GVariant-encoded body.
+    #[cfg(feature = "gvariant")]
+    pub fn from_gvariant(bytes: &[u8]) -> Result<Self> {
+        let ctxt = EncodingContext::<byteorder::NativeEndian>::new_gvariant(0);
+        let (header_bytes, body_bytes, body_sig) = split_gvariant(bytes)?;
+        let (endian_sig, msg_type, flags, version, _, serial, fields): GVariantHeader<'_> =
+            zvariant::from_slice(header_bytes, ctxt)?;
+        if EndianSig::try_from(endian_sig)? != NATIVE_ENDIAN_SIG {
+            return Err(Error::IncorrectEndian);
+        }
+        if version != GVARIANT_PROTOCOL_VERSION {
+            return Err(Error::Unsupported);
+        }
+
+        let mut builder =
+            MessageBuilder::new(MessageType::from(msg_type)).body_format(EncodingFormat::GVariant);
+        let flags = BitFlags::<MessageFlags>::from_bits(flags).map_err(|_| Error::InvalidField)?;
+        builder.header.primary_mut().set_flags(flags);
+        for (code, value) in fields {
+            let code = u8::try_from(code)
+                .map(MessageFieldCode::from)
+                .unwrap_or(MessageFieldCode::Invalid);
+            let field = match code {
+                MessageFieldCode::Path => MessageField::Path(ObjectPath::try_from(value)?),
+                MessageFieldCode::Interface => {
+                    MessageField::Interface(InterfaceName::try_from(value)?)
+                }
+                MessageFieldCode::Member => MessageField::Member(MemberName::try_from(value)?),
+                MessageFieldCode::ErrorName => MessageField::ErrorName(ErrorName::try_from(value)?),
+                MessageFieldCode::ReplySerial => {
+                    let serial = u64::try_from(value)?;
+                    MessageField::ReplySerial(serial.try_into().map_err(|_| Error::InvalidField)?)
+                }
+                MessageFieldCode::Destination => {
+                    MessageField::Destination(BusName::try_from(value)?)
+                }
+                MessageFieldCode::Sender => MessageField::Sender(UniqueName::try_from(value)?),
+                MessageFieldCode::UnixFDs => MessageField::UnixFDs(u32::try_from(value)?),
+                // The signature is implied by the type of the body and unknown fields must be
+                // ignored.
+                MessageFieldCode::Signature | MessageFieldCode::Invalid => continue,
+            };
+            builder.header.fields_mut().replace(field);
+        }
+
+        // Empty structures are not valid D-Bus signatures so we've to special-case empty bodies.
+        let mut msg = if body_sig == "()" {
+            builder.build(&())?
+        } else {
+            let body_sig = Signature::try_from(body_sig)?;
+            let body: Structure<'_> =
+                zvariant::from_slice_for_dynamic_signature(body_bytes, ctxt, &body_sig)?;
+            builder.build(&body)?
+        };
+        if serial != 0 {
+            let serial = serial.try_into().map_err(|_| Error::InvalidField)?;
+            msg.modify_primary_header(|primary| {
+                primary.serial_num_or_init(|| serial);
+
+                Ok(())
+            })?;
+        }
+
+        Ok(msg)
+    }
+
+    /// Serialize the message to its GVariant representation.
+    ///
+    /// The message is represented as a GVariant of type `((yyyyuta{tv})v)`, the format used by
+    /// GLib for GVariant-based D-Bus messages: a header structure (endianness, message type,
+    /// flags, major protocol version, a reserved field, serial number and the header fields keyed
+    /// by their codes) followed by the body, wrapped in a variant of structure type. The body is
+    /// converted to the GVariant format if needed.
+    ///
+    /// **Note:** File descriptors are represented by their indices and are not part of the
+    /// representation.
+    #[cfg(feature = "gvariant")]
+    pub fn to_gvariant(&self) -> Result<Vec<u8>> {
+        let header = self.header()?;
+        let primary = header.primary();
+        let mut fields = BTreeMap::new();
+        for field in header.fields().get() {
+            let value = match field {
+                MessageField::Path(path) => Value::from(path.as_ref()),
+                MessageField::Interface(iface) => Value::from(iface.as_str()),
+                MessageField::Member(member) => Value::from(member.as_str()),
+                MessageField::ErrorName(name) => Value::from(name.as_str()),
+                MessageField::ReplySerial(serial) => Value::from(u64::from(*serial)),
+                MessageField::Destination(dest) => Value::from(dest.as_str()),
+                MessageField::Sender(sender) => Value::from(sender.as_str()),
+                MessageField::UnixFDs(n_fds) => Value::from(*n_fds),
+                // The signature is implied by the type of the body.
+                MessageField::Signature(_) | MessageField::Invalid => continue,
+            };
+            fields.insert(field.code() as u64, value);
+        }
+        let gv_header: GVariantHeader<'_> = (
+            primary.endian_sig() as u8,
+            primary.msg_type() as u8,
+            primary.flags().bits(),
+            GVARIANT_PROTOCOL_VERSION,
+            0,
+            primary.serial_num().copied().unwrap_or_default().into(),
+            fields,
+        );
+        let body = if self.body_signature().is_ok() {
+            self.body::<Structure<'_>>()?
+        } else {
+            Structure::default()
+        };
+
+        let ctxt = EncodingContext::<byteorder::NativeEndian>::new_gvariant(0);
+        let value = (gv_header, Value::from(body));
+        #[cfg(unix)]
+        let (bytes, _) = zvariant::to_bytes_fds(ctxt, &value)?;
+        #[cfg(not(unix))]
+        let bytes = zvariant::to_bytes(ctxt, &value)?;
+
+        Ok(bytes)
+    }
+
     /// Take ownership of the associated file descriptors in the message.
     ///
     /// When a message is received over a AF_UNIX socket, it may contain associated FDs. To prevent
@@ -613,6 +815,18 @@ impl Message {
         self.primary_header.msg_type()
     }
 
+    /// The encoding format of the message body.
+    ///
+    /// This is always [`EncodingFormat::DBus`] unless the message was explicitly built with a
+    /// GVariant-encoded body.
+    pub fn body_format(&self) -> EncodingFormat {
+        body_format_for_protocol_version(self.primary_header.protocol_version())
+    }
+
+    fn body_context(&self) -> EncodingContext<byteorder::NativeEndian> {
+        EncodingContext::new(self.body_format(), 0)
+    }
+
     /// The object to send a call to, or the object a signal is emitted from.
     pub fn path(&self) -> Option<ObjectPath<'_>> {
         self.quick_fields.path(self)
@@ -634,6 +848,8 @@ impl Message {
     }
 
     /// Deserialize the body (without checking signature matching).
+    ///
+    /// See [`Message::body`] for details on errors.
     pub fn body_unchecked<'d, 'm: 'd, B>(&'m self) -> Result<B>
     where
         B: serde::de::Deserialize<'d> + Type,
@@ -644,19 +860,25 @@ impl Message {
                 zvariant::from_slice_fds(
                     &self.bytes[self.body_offset..],
                     Some(&self.fds()),
-                    dbus_context!(0),
+                    self.body_context(),
                 )
             }
             #[cfg(not(unix))]
             {
-                zvariant::from_slice(&self.bytes[self.body_offset..], dbus_context!(0))
+                zvariant::from_slice(&self.bytes[self.body_offset..], self.body_context())
             }
         }
-        .map_err(Error::from)
+        .map_err(|e| Error::from(e.prefix_path("body")))
     }
 
     /// Deserialize the body using the contained signature.
     ///
+    /// # Errors
+    ///
+    /// If an element of the body fails to deserialize, the returned [`Error::Variant`] carries its
+    /// location, as a path starting with `body` and the offset of the element from the start of the
+    /// body. See [`zvariant::ErrorContext`] for details.
+    ///
     /// # Example
     ///
     /// ```
@@ -693,7 +915,7 @@ impl Message {
                 zvariant::from_slice_fds_for_dynamic_signature(
                     &self.bytes[self.body_offset..],
                     Some(&self.fds()),
-                    dbus_context!(0),
+                    self.body_context(),
                     &body_sig,
                 )
             }
@@ -701,12 +923,12 @@ impl Message {
             {
                 zvariant::from_slice_for_dynamic_signature(
                     &self.bytes[self.body_offset..],
-                    dbus_context!(0),
+                    self.body_context(),
                     &body_sig,
                 )
             }
         }
-        .map_err(Error::from)
+        .map_err(|e| Error::from(e.prefix_path("body")))
     }
 
     #[cfg(unix)]
@@ -904,4 +1126,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "gvariant")]
+    #[test]
+    fn gvariant() -> Result<(), Error> {
+        use zvariant::EncodingFormat;
+
+        let body = (7i32, "hello", vec!["a", "b"]);
+        let m = MessageBuilder::signal("/org/zbus/test", "org.zbus.Test", "Test")?
+            .sender(":1.42")?
+            .body_format(EncodingFormat::GVariant)
+            .build(&body)?;
+        assert_eq!(m.body_format(), EncodingFormat::GVariant);
+        assert_eq!(m.primary_header().protocol_version(), 2);
+        assert_eq!(m.body_signature()?, "isas");
+        let received: (i32, &str, Vec<&str>) = m.body()?;
+        assert_eq!(received, body);
+
+        let gv = m.to_gvariant()?;
+        let m = Message::from_gvariant(&gv)?;
+        assert_eq!(m.body_format(), EncodingFormat::GVariant);
+        assert_eq!(m.to_string(), "Signal Test from :1.42");
+        let received: (i32, &str, Vec<&str>) = m.body()?;
+        assert_eq!(received, body);
+        assert_eq!(m.to_gvariant()?, gv);
+
+        // D-Bus encoded bodies get converted.
+        let m = Message::method(
+            None::<()>,
+            Some("org.zbus.Test"),
+            "/",
+            None::<()>,
+            "Do",
+            &("foo", 42u8),
+        )?;
+        let m = Message::from_gvariant(&m.to_gvariant()?)?;
+        assert_eq!(m.body::<(&str, u8)>()?, ("foo", 42));
+
+        // As do empty ones.
+        let m = Message::method(None::<()>, None::<()>, "/", None::<()>, "Do", &())?;
+        let m = Message::from_gvariant(&m.to_gvariant()?)?;
+        assert!(matches!(m.body_signature(), Err(Error::NoBodySignature)));
+
+        Ok(())
+    }
 }
@@ -14,6 +14,9 @@ use crate::{Error, MessageField, MessageFieldCode, MessageFields};
 pub(crate) const PRIMARY_HEADER_SIZE: usize = 12;
 pub(crate) const MIN_MESSAGE_SIZE: usize = PRIMARY_HEADER_SIZE + 4;
 pub(crate) const MAX_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
+/// The major protocol version of messages with GVariant-encoded bodies.
+#[cfg(feature = "gvariant")]
+pub(crate) const GVARIANT_PROTOCOL_VERSION: u8 = 2;
 
 /// D-Bus code for endianness.
 #[repr(u8)]
@@ -209,14 +212,16 @@ impl MessagePrimaryHeader {
 
     /// The major version of the protocol the message is compliant to.
     ///
-    /// Currently only `1` is valid.
+    /// Currently only `1` is valid, unless the `gvariant` feature is enabled, in which case `2` is
+    /// used for messages with GVariant-encoded bodies.
     pub fn protocol_version(&self) -> u8 {
         self.protocol_version
     }
 
     /// Set the major version of the protocol the message is compliant to.
     ///
-    /// Currently only `1` is valid.
+    /// Currently only `1` is valid, unless the `gvariant` feature is enabled, in which case `2` is
+    /// used for messages with GVariant-encoded bodies.
     pub fn set_protocol_version(&mut self, version: u8) {
         self.protocol_version = version;
     }
@@ -631,6 +631,47 @@ impl<'a> Proxy<'a> {
         proxy.introspect().await
     }
 
+    /// Check the interface against the introspection data of the associated object.
+    ///
+    /// `expected` is the introspection XML of the interface (an `<interface>` element, or a
+    /// `<node>` containing it), typically the one returned by the `introspection_xml()` function of
+    /// proxies generated with [`dbus_proxy`](macro@crate::dbus_proxy), whose
+    /// `verify_introspection()` method calls this with it. The differences are returned,
+    /// as described by [`Interface::mismatches`](crate::quick_xml::Interface::mismatches). This is
+    /// useful in tests, to catch proxies that drifted from the service they're for.
+    ///
+    /// Returns [`Error::InterfaceNotFound`] if `expected` doesn't describe the interface of the
+    /// proxy.
+    #[cfg(feature = "quick-xml")]
+    pub async fn verify_against_introspection(
+        &self,
+        expected: &str,
+    ) -> Result<Vec<crate::quick_xml::Mismatch>> {
+        use crate::quick_xml::{Mismatch, Node};
+
+        let expected = if expected.trim_start().starts_with("<interface") {
+            format!("<node>{expected}</node>")
+        } else {
+            expected.to_string()
+        };
+        let expected = Node::try_from(expected.as_str())?;
+        let interface = self.interface();
+        let expected = expected
+            .interfaces()
+            .iter()
+            .find(|i| i.name() == *interface)
+            .ok_or(Error::InterfaceNotFound)?;
+
+        let actual = self.introspect().await?;
+        let actual = Node::try_from(actual.as_str())?;
+        let mismatches = match actual.interfaces().iter().find(|i| i.name() == *interface) {
+            Some(actual) => expected.mismatches(actual),
+            None => vec![Mismatch::MissingInterface(interface.to_string())],
+        };
+
+        Ok(mismatches)
+    }
+
     fn properties_proxy(&self) -> PropertiesProxy<'_> {
         PropertiesProxy::builder(&self.inner.inner_without_borrows.conn)
             // Safe because already checked earlier
@@ -6,6 +6,12 @@
 //! This optional `quick_xml` module provides facilities to parse the XML data into more convenient Rust
 //! structures. The XML string may be parsed to a tree with [`Node.from_reader()`].
 //!
+//! Trees can also be built programmatically, starting with [`Node::new()`], and checked with
+//! [`Node::validate()`]. Documentation is available through the `org.freedesktop.DBus.DocString`
+//! annotation or GLib's `<doc:doc>` elements, both of which are preserved when writing the XML.
+//! Unknown child elements of nodes and interfaces (e.g from other XML namespaces) are preserved
+//! too, as raw XML.
+//!
 //! This module has a more type-safe API and uses a maintained XML parser.
 //! It will eventually replace the [xml](xml/index.html) module.
 //! See also:
@@ -16,20 +22,28 @@
 //! [Introspection format]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 //! [`org.freedesktop.DBus.Introspectable`]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces-introspectable
 
-use quick_xml::{de::Deserializer, se::to_writer};
-use serde::{Deserialize, Serialize};
+use quick_xml::{de::Deserializer, events::Event, se::to_writer, Reader};
+use serde::{
+    de::{self, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Serialize, Serializer,
+};
 use static_assertions::assert_impl_all;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt,
     io::{BufReader, Read, Write},
     result::Result,
 };
 
 use crate::{
     names::{InterfaceName, MemberName},
+    zvariant::{ObjectPath, Signature},
     Error,
 };
 
+const DOC_STRING_ANNOTATION: &str = "org.freedesktop.DBus.DocString";
+const DOC_NAMESPACE: &str = "http://www.freedesktop.org/dbus/1.0/doc.dtd";
+
 /// Annotations are generic key/value pairs of metadata.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Annotation {
@@ -42,6 +56,14 @@ pub struct Annotation {
 assert_impl_all!(Annotation: Send, Sync, Unpin);
 
 impl Annotation {
+    /// Create a new annotation.
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
     /// Return the annotation name/key.
     pub fn name(&self) -> &str {
         &self.name
@@ -53,6 +75,176 @@ impl Annotation {
     }
 }
 
+/// GLib-style documentation of an element (a `<doc:doc>` element).
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Doc {
+    #[serde(
+        rename(deserialize = "summary", serialize = "doc:summary"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    summary: Option<String>,
+    #[serde(
+        rename(deserialize = "description", serialize = "doc:description"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    description: Option<DocDescription>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+struct DocDescription {
+    #[serde(rename(deserialize = "para", serialize = "doc:para"), default)]
+    paras: Vec<DocPara>,
+}
+
+// A `<doc:para>`, with its inline markup (e.g `<doc:ref>`) flattened to text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct DocPara(String);
+
+impl Serialize for DocPara {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for DocPara {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Inline {
+            #[serde(rename = "$value", default)]
+            text: String,
+        }
+
+        struct ParaVisitor;
+
+        impl<'de> Visitor<'de> for ParaVisitor {
+            type Value = DocPara;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a documentation paragraph")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<DocPara, E> {
+                Ok(DocPara(v.to_string()))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<DocPara, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                // The text is trimmed by the parser, so the pieces are joined with spaces.
+                let mut pieces = vec![];
+                while let Some(key) = map.next_key::<String>()? {
+                    if key.starts_with('@') {
+                        map.next_value::<IgnoredAny>()?;
+                    } else if key == "$text" || key == "$value" {
+                        pieces.push(map.next_value::<String>()?);
+                    } else {
+                        pieces.push(map.next_value::<Inline>()?.text);
+                    }
+                }
+
+                Ok(DocPara(pieces.join(" ")))
+            }
+        }
+
+        deserializer.deserialize_map(ParaVisitor)
+    }
+}
+
+assert_impl_all!(Doc: Send, Sync, Unpin);
+
+impl Doc {
+    /// Create a new documentation element, with an optional summary and description paragraphs.
+    pub fn new(summary: Option<String>, paragraphs: Vec<String>) -> Self {
+        let description = if paragraphs.is_empty() {
+            None
+        } else {
+            Some(DocDescription {
+                paras: paragraphs.into_iter().map(DocPara).collect(),
+            })
+        };
+
+        Self {
+            summary,
+            description,
+        }
+    }
+
+    /// Return the summary, if any.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Return the paragraphs of the description.
+    ///
+    /// Inline markup, such as `<doc:ref>`, is flattened to its text.
+    pub fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        self.description
+            .iter()
+            .flat_map(|d| d.paras.iter().map(|p| p.0.as_str()))
+    }
+
+    /// Return the whole text, with the summary and paragraphs separated by blank lines.
+    pub fn text(&self) -> String {
+        let paras = self.paragraphs().map(str::trim);
+
+        self.summary
+            .iter()
+            .map(|s| s.trim())
+            .chain(paras)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+// The documentation of an element: the `org.freedesktop.DBus.DocString` annotation if present,
+// otherwise the `<doc:doc>` element.
+fn doc(annotations: &[Annotation], doc: &Option<Doc>) -> Option<String> {
+    annotations
+        .iter()
+        .find(|a| a.name == DOC_STRING_ANNOTATION)
+        .map(|a| a.value.clone())
+        .or_else(|| doc.as_ref().map(Doc::text))
+}
+
+// The documentation setters and getters of an element with `doc` and `annotations` fields.
+macro_rules! impl_doc {
+    ($ty:ty) => {
+        impl $ty {
+            /// Set the documentation, as a `org.freedesktop.DBus.DocString` annotation.
+            pub fn with_doc<D: Into<String>>(mut self, doc: D) -> Self {
+                self.annotations.push(Annotation::new(DOC_STRING_ANNOTATION, doc));
+                self
+            }
+
+            /// Set the `<doc:doc>` element.
+            pub fn with_doc_element(mut self, doc: Doc) -> Self {
+                self.doc = Some(doc);
+                self
+            }
+
+            /// Return the documentation, from the `org.freedesktop.DBus.DocString` annotation or
+            /// the `<doc:doc>` element.
+            pub fn doc(&self) -> Option<String> {
+                doc(&self.annotations, &self.doc)
+            }
+
+            /// Return the `<doc:doc>` element, if any.
+            pub fn doc_element(&self) -> Option<&Doc> {
+                self.doc.as_ref()
+            }
+        }
+    };
+}
+
 /// A direction of an argument
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ArgDirection {
@@ -65,12 +257,18 @@ pub enum ArgDirection {
 /// An argument
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Arg {
-    #[serde(rename = "@name")]
+    #[serde(rename = "@name", skip_serializing_if = "Option::is_none")]
     name: Option<String>,
     #[serde(rename = "@type")]
     r#type: String,
-    #[serde(rename = "@direction")]
+    #[serde(rename = "@direction", skip_serializing_if = "Option::is_none")]
     direction: Option<ArgDirection>,
+    #[serde(
+        rename(deserialize = "doc", serialize = "doc:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -78,6 +276,29 @@ pub struct Arg {
 assert_impl_all!(Arg: Send, Sync, Unpin);
 
 impl Arg {
+    /// Create a new argument of type `ty`.
+    pub fn new(ty: Signature<'_>, direction: Option<ArgDirection>) -> Self {
+        Self {
+            name: None,
+            r#type: ty.to_string(),
+            direction,
+            doc: None,
+            annotations: vec![],
+        }
+    }
+
+    /// Set the argument name.
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Add an annotation.
+    pub fn with_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
     /// Return the argument name, if any.
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
@@ -99,11 +320,19 @@ impl Arg {
     }
 }
 
+impl_doc!(Arg);
+
 /// A method
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Method<'a> {
     #[serde(rename = "@name", borrow)]
     name: MemberName<'a>,
+    #[serde(
+        rename(deserialize = "doc", serialize = "doc:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "arg", default)]
     args: Vec<Arg>,
     #[serde(rename = "annotation", default)]
@@ -113,6 +342,28 @@ pub struct Method<'a> {
 assert_impl_all!(Method<'_>: Send, Sync, Unpin);
 
 impl<'a> Method<'a> {
+    /// Create a new method.
+    pub fn new(name: MemberName<'a>) -> Self {
+        Self {
+            name,
+            doc: None,
+            args: vec![],
+            annotations: vec![],
+        }
+    }
+
+    /// Add an argument.
+    pub fn with_arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Add an annotation.
+    pub fn with_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
     /// Return the method name.
     pub fn name(&self) -> MemberName<'_> {
         self.name.as_ref()
@@ -129,11 +380,19 @@ impl<'a> Method<'a> {
     }
 }
 
+impl_doc!(Method<'_>);
+
 /// A signal
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Signal<'a> {
     #[serde(rename = "@name", borrow)]
     name: MemberName<'a>,
+    #[serde(
+        rename(deserialize = "doc", serialize = "doc:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
 
     #[serde(rename = "arg", default)]
     args: Vec<Arg>,
@@ -144,6 +403,28 @@ pub struct Signal<'a> {
 assert_impl_all!(Signal<'_>: Send, Sync, Unpin);
 
 impl<'a> Signal<'a> {
+    /// Create a new signal.
+    pub fn new(name: MemberName<'a>) -> Self {
+        Self {
+            name,
+            doc: None,
+            args: vec![],
+            annotations: vec![],
+        }
+    }
+
+    /// Add an argument.
+    pub fn with_arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Add an annotation.
+    pub fn with_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
     /// Return the signal name.
     pub fn name(&self) -> MemberName<'_> {
         self.name.as_ref()
@@ -160,6 +441,8 @@ impl<'a> Signal<'a> {
     }
 }
 
+impl_doc!(Signal<'_>);
+
 /// The possible property access types
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PropertyAccess {
@@ -172,6 +455,14 @@ pub enum PropertyAccess {
 }
 
 impl PropertyAccess {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PropertyAccess::Read => "read",
+            PropertyAccess::Write => "write",
+            PropertyAccess::ReadWrite => "readwrite",
+        }
+    }
+
     pub fn read(&self) -> bool {
         matches!(self, PropertyAccess::Read | PropertyAccess::ReadWrite)
     }
@@ -191,6 +482,12 @@ pub struct Property<'a> {
     r#type: String,
     #[serde(rename = "@access")]
     access: PropertyAccess,
+    #[serde(
+        rename(deserialize = "doc", serialize = "doc:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
 
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
@@ -199,6 +496,23 @@ pub struct Property<'a> {
 assert_impl_all!(Property<'_>: Send, Sync, Unpin);
 
 impl<'a> Property<'a> {
+    /// Create a new property of type `ty`.
+    pub fn new(name: MemberName<'a>, ty: Signature<'_>, access: PropertyAccess) -> Self {
+        Self {
+            name,
+            r#type: ty.to_string(),
+            access,
+            doc: None,
+            annotations: vec![],
+        }
+    }
+
+    /// Add an annotation.
+    pub fn with_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
     /// Returns the property name.
     pub fn name(&self) -> MemberName<'_> {
         self.name.as_ref()
@@ -220,11 +534,19 @@ impl<'a> Property<'a> {
     }
 }
 
+impl_doc!(Property<'_>);
+
 /// An interface
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Interface<'a> {
     #[serde(rename = "@name", borrow)]
     name: InterfaceName<'a>,
+    #[serde(
+        rename(deserialize = "doc", serialize = "doc:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
 
     #[serde(rename = "method", default)]
     methods: Vec<Method<'a>>,
@@ -234,11 +556,51 @@ pub struct Interface<'a> {
     signals: Vec<Signal<'a>>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+
+    #[serde(skip)]
+    unknown_elements: Vec<String>,
 }
 
 assert_impl_all!(Interface<'_>: Send, Sync, Unpin);
 
 impl<'a> Interface<'a> {
+    /// Create a new interface.
+    pub fn new(name: InterfaceName<'a>) -> Self {
+        Self {
+            name,
+            doc: None,
+            methods: vec![],
+            properties: vec![],
+            signals: vec![],
+            annotations: vec![],
+            unknown_elements: vec![],
+        }
+    }
+
+    /// Add a method.
+    pub fn with_method(mut self, method: Method<'a>) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// Add a signal.
+    pub fn with_signal(mut self, signal: Signal<'a>) -> Self {
+        self.signals.push(signal);
+        self
+    }
+
+    /// Add a property.
+    pub fn with_property(mut self, property: Property<'a>) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Add an annotation.
+    pub fn with_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
     /// Returns the interface name.
     pub fn name(&self) -> InterfaceName<'_> {
         self.name.as_ref()
@@ -263,32 +625,338 @@ impl<'a> Interface<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Return the raw XML of the child elements unknown to this module, which are preserved when
+    /// writing the document.
+    pub fn unknown_elements(&self) -> &[String] {
+        &self.unknown_elements
+    }
+
+    /// Compare this (expected) description of the interface against the `actual` one.
+    ///
+    /// All the methods, signals and properties of `self` must be present in `actual`, with the same
+    /// signatures, and properties must allow (at least) the same access. Members of `actual` that
+    /// are not in `self` are ignored, since a proxy may only cover a part of the interface.
+    pub fn mismatches(&self, actual: &Interface<'_>) -> Vec<Mismatch> {
+        fn check(
+            kind: MemberKind,
+            name: &MemberName<'_>,
+            expected: String,
+            actual: Option<String>,
+        ) -> Option<Mismatch> {
+            let name = name.to_string();
+            match actual {
+                None => Some(Mismatch::MissingMember { kind, name }),
+                Some(actual) if actual != expected => Some(Mismatch::Signature {
+                    kind,
+                    name,
+                    expected,
+                    actual,
+                }),
+                Some(_) => None,
+            }
+        }
+        let mut mismatches = vec![];
+
+        for method in &self.methods {
+            let actual = actual
+                .methods
+                .iter()
+                .find(|m| m.name == method.name)
+                .map(method_signature);
+            mismatches.extend(check(
+                MemberKind::Method,
+                &method.name,
+                method_signature(method),
+                actual,
+            ));
+        }
+        for signal in &self.signals {
+            let actual = actual
+                .signals
+                .iter()
+                .find(|s| s.name == signal.name)
+                .map(|s| args_signature(&s.args, None));
+            mismatches.extend(check(
+                MemberKind::Signal,
+                &signal.name,
+                args_signature(&signal.args, None),
+                actual,
+            ));
+        }
+        for property in &self.properties {
+            let actual_property = actual.properties.iter().find(|p| p.name == property.name);
+            mismatches.extend(check(
+                MemberKind::Property,
+                &property.name,
+                property.r#type.clone(),
+                actual_property.map(|p| p.r#type.clone()),
+            ));
+            if let Some(actual_property) = actual_property {
+                let (expected, actual) = (property.access, actual_property.access);
+                if (expected.read() && !actual.read()) || (expected.write() && !actual.write()) {
+                    mismatches.push(Mismatch::Access {
+                        name: property.name.to_string(),
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        mismatches
+    }
+}
+
+impl_doc!(Interface<'_>);
+
+// The signature of the arguments in the given direction (all of them if `None`).
+fn args_signature(args: &[Arg], direction: Option<ArgDirection>) -> String {
+    args.iter()
+        .filter(|arg| {
+            direction.is_none() || arg.direction.unwrap_or(ArgDirection::In) == direction.unwrap()
+        })
+        .map(|arg| arg.r#type.as_str())
+        .collect()
+}
+
+// The signature of a method, as `<in> -> <out>`.
+fn method_signature(method: &Method<'_>) -> String {
+    format!(
+        "{} -> {}",
+        args_signature(&method.args, Some(ArgDirection::In)),
+        args_signature(&method.args, Some(ArgDirection::Out)),
+    )
+}
+
+/// The kind of an interface member.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemberKind {
+    /// A method.
+    Method,
+    /// A signal.
+    Signal,
+    /// A property.
+    Property,
+}
+
+impl fmt::Display for MemberKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemberKind::Method => f.write_str("method"),
+            MemberKind::Signal => f.write_str("signal"),
+            MemberKind::Property => f.write_str("property"),
+        }
+    }
+}
+
+/// A difference between the expected description of an interface and the actual one.
+///
+/// See [`Interface::mismatches`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Mismatch {
+    /// The interface is not implemented by the object.
+    MissingInterface(String),
+    /// A member is missing from the interface.
+    MissingMember { kind: MemberKind, name: String },
+    /// A member has a different signature. The signature of methods is given as `<in> -> <out>`.
+    Signature {
+        kind: MemberKind,
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    /// A property doesn't allow the expected access.
+    Access {
+        name: String,
+        expected: PropertyAccess,
+        actual: PropertyAccess,
+    },
+}
+
+assert_impl_all!(Mismatch: Send, Sync, Unpin);
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::MissingInterface(name) => write!(f, "missing interface `{name}`"),
+            Mismatch::MissingMember { kind, name } => write!(f, "missing {kind} `{name}`"),
+            Mismatch::Signature {
+                kind,
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{kind} `{name}`: expected signature `{expected}`, found `{actual}`"
+            ),
+            Mismatch::Access {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "property `{name}`: expected `{}` access, found `{}`",
+                expected.as_str(),
+                actual.as_str(),
+            ),
+        }
+    }
 }
 
 /// An introspection tree node (typically the root of the XML document).
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename = "node")]
 pub struct Node<'a> {
-    #[serde(rename = "@name")]
+    #[serde(rename = "@name", skip_serializing_if = "Option::is_none")]
     name: Option<String>,
+    // Only needed when `<doc:doc>` elements are used.
+    #[serde(
+        rename(deserialize = "@doc", serialize = "@xmlns:doc"),
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc_namespace: Option<String>,
 
     #[serde(rename = "interface", default, borrow)]
     interfaces: Vec<Interface<'a>>,
     #[serde(rename = "node", default, borrow)]
     nodes: Vec<Node<'a>>,
+
+    #[serde(skip)]
+    unknown_elements: Vec<String>,
 }
 
 assert_impl_all!(Node<'_>: Send, Sync, Unpin);
 
 impl<'a> Node<'a> {
+    /// Create a new, empty node.
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            doc_namespace: None,
+            interfaces: vec![],
+            nodes: vec![],
+            unknown_elements: vec![],
+        }
+    }
+
+    /// Set the node name: an object path for the root node, or a path element for the children.
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Add an interface.
+    pub fn with_interface(mut self, interface: Interface<'a>) -> Self {
+        if has_doc_elements(&interface) {
+            self.doc_namespace = Some(DOC_NAMESPACE.to_string());
+        }
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Add a child node.
+    pub fn with_node(mut self, node: Node<'a>) -> Self {
+        if node.doc_namespace.is_some() {
+            self.doc_namespace = Some(DOC_NAMESPACE.to_string());
+        }
+        self.nodes.push(node);
+        self
+    }
+
+    /// Check the validity of the names and signatures in the tree.
+    ///
+    /// The names of interfaces and members are always valid, since they're checked on parsing. This
+    /// checks the node names, the type signatures (which must be single complete types), the
+    /// annotation names, the direction of signal arguments and the uniqueness of interface and
+    /// member names. The first error found is returned, with its location in the tree.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let location = match &self.name {
+            Some(name) => format!("node `{name}`"),
+            None => "node".to_string(),
+        };
+        if let Some(name) = &self.name {
+            // The root node may have an absolute path.
+            let path = if name.starts_with('/') {
+                name.clone()
+            } else {
+                format!("/{name}")
+            };
+            if ObjectPath::try_from(path).is_err() {
+                return Err(ValidationError::new(&location, "invalid node name"));
+            }
+        }
+
+        for (i, iface) in self.interfaces.iter().enumerate() {
+            let location = format!("{location} > interface `{}`", iface.name);
+            if self.interfaces[..i].iter().any(|i| i.name == iface.name) {
+                return Err(ValidationError::new(&location, "duplicate interface"));
+            }
+            validate_annotations(&location, &iface.annotations)?;
+
+            for (i, method) in iface.methods.iter().enumerate() {
+                let location = format!("{location} > method `{}`", method.name);
+                if iface.methods[..i].iter().any(|m| m.name == method.name) {
+                    return Err(ValidationError::new(&location, "duplicate method"));
+                }
+                validate_annotations(&location, &method.annotations)?;
+                validate_args(&location, &method.args, false)?;
+            }
+            for (i, signal) in iface.signals.iter().enumerate() {
+                let location = format!("{location} > signal `{}`", signal.name);
+                if iface.signals[..i].iter().any(|s| s.name == signal.name) {
+                    return Err(ValidationError::new(&location, "duplicate signal"));
+                }
+                validate_annotations(&location, &signal.annotations)?;
+                validate_args(&location, &signal.args, true)?;
+            }
+            for (i, property) in iface.properties.iter().enumerate() {
+                let location = format!("{location} > property `{}`", property.name);
+                if iface.properties[..i]
+                    .iter()
+                    .any(|p| p.name == property.name)
+                {
+                    return Err(ValidationError::new(&location, "duplicate property"));
+                }
+                validate_annotations(&location, &property.annotations)?;
+                validate_type(&location, &property.r#type)?;
+            }
+        }
+
+        for node in &self.nodes {
+            if node.name.as_deref().map_or(true, |n| n.starts_with('/')) {
+                let name = node.name.as_deref().unwrap_or_default();
+                return Err(ValidationError::new(
+                    &format!("{location} > node `{name}`"),
+                    "child nodes must have a relative name",
+                ));
+            }
+            node.validate().map_err(|e| ValidationError {
+                location: format!("{location} > {}", e.location),
+                message: e.message,
+            })?;
+        }
+
+        Ok(())
+    }
+
     /// Parse the introspection XML document from reader.
-    pub fn from_reader<R: Read>(reader: R) -> Result<Node<'a>, Error> {
-        let mut deserializer = Deserializer::from_reader(BufReader::new(reader));
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Node<'a>, Error> {
+        // The whole document is needed to extract the unknown elements.
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        let mut deserializer = Deserializer::from_reader(BufReader::new(xml.as_bytes()));
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        let mut node = Node::deserialize(&mut deserializer)?;
+        node.set_unknown_elements(&mut read_unknown_elements(&xml)?.into_iter());
+
+        Ok(node)
     }
 
     /// Write the XML document to writer.
-    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), Error> {
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> Result<(), Error> {
         // Need this wrapper until this is resolved: https://github.com/tafia/quick-xml/issues/499
         struct Writer<T>(T);
 
@@ -301,7 +969,20 @@ impl<'a> Node<'a> {
             }
         }
 
-        to_writer(Writer(writer), &self)?;
+        let mut unknown = vec![];
+        self.collect_unknown_elements(&mut unknown);
+        if unknown
+            .iter()
+            .all(|(node, ifaces)| node.is_empty() && ifaces.iter().all(Vec::is_empty))
+        {
+            to_writer(Writer(writer), &self)?;
+
+            return Ok(());
+        }
+
+        let mut xml = String::new();
+        to_writer(&mut xml, &self)?;
+        writer.write_all(insert_unknown_elements(&xml, unknown)?.as_bytes())?;
 
         Ok(())
     }
@@ -320,8 +1001,147 @@ impl<'a> Node<'a> {
     pub fn interfaces(&self) -> &[Interface<'a>] {
         &self.interfaces
     }
+
+    /// Returns the raw XML of the child elements unknown to this module, which are preserved when
+    /// writing the document.
+    pub fn unknown_elements(&self) -> &[String] {
+        &self.unknown_elements
+    }
+
+    // Collects the unknown elements of the tree, in the order of `read_unknown_elements`.
+    fn collect_unknown_elements(&self, unknown: &mut UnknownElements) {
+        let ifaces = self
+            .interfaces
+            .iter()
+            .map(|i| i.unknown_elements.clone())
+            .collect();
+        unknown.push((self.unknown_elements.clone(), ifaces));
+        for node in &self.nodes {
+            node.collect_unknown_elements(unknown);
+        }
+    }
+
+    fn set_unknown_elements<I>(&mut self, unknown: &mut I)
+    where
+        I: Iterator<Item = (Vec<String>, Vec<Vec<String>>)>,
+    {
+        if let Some((elements, ifaces)) = unknown.next() {
+            self.unknown_elements = elements;
+            for (iface, elements) in self.interfaces.iter_mut().zip(ifaces) {
+                iface.unknown_elements = elements;
+            }
+        }
+        for node in &mut self.nodes {
+            node.set_unknown_elements(unknown);
+        }
+    }
+}
+
+impl Default for Node<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn has_doc_elements(iface: &Interface<'_>) -> bool {
+    let args_docs = |args: &[Arg]| args.iter().any(|a| a.doc.is_some());
+
+    iface.doc.is_some()
+        || iface
+            .methods
+            .iter()
+            .any(|m| m.doc.is_some() || args_docs(&m.args))
+        || iface
+            .signals
+            .iter()
+            .any(|s| s.doc.is_some() || args_docs(&s.args))
+        || iface.properties.iter().any(|p| p.doc.is_some())
+}
+
+fn validate_annotations(location: &str, annotations: &[Annotation]) -> Result<(), ValidationError> {
+    for annotation in annotations {
+        // Annotation names follow the same rules as interface names.
+        if InterfaceName::try_from(annotation.name.as_str()).is_err() {
+            return Err(ValidationError::new(
+                location,
+                &format!("invalid annotation name `{}`", annotation.name),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_args(location: &str, args: &[Arg], signal: bool) -> Result<(), ValidationError> {
+    for (i, arg) in args.iter().enumerate() {
+        let location = match &arg.name {
+            Some(name) => format!("{location} > arg {i} (`{name}`)"),
+            None => format!("{location} > arg {i}"),
+        };
+        if signal && arg.direction == Some(ArgDirection::In) {
+            return Err(ValidationError::new(
+                &location,
+                "signal arguments can't have an `in` direction",
+            ));
+        }
+        validate_annotations(&location, &arg.annotations)?;
+        validate_type(&location, &arg.r#type)?;
+    }
+
+    Ok(())
+}
+
+fn validate_type(location: &str, ty: &str) -> Result<(), ValidationError> {
+    let valid = Signature::try_from(ty)
+        .map(|s| s.complete_types().count() == 1)
+        .unwrap_or(false);
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            location,
+            &format!("invalid type signature `{ty}`"),
+        ))
+    }
 }
 
+/// An error found by [`Node::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    location: String,
+    message: String,
+}
+
+assert_impl_all!(ValidationError: Send, Sync, Unpin);
+
+impl ValidationError {
+    fn new(location: &str, message: &str) -> Self {
+        Self {
+            location: location.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// The location of the error in the tree, e.g ``node `/org/example` > interface
+    /// `org.example.Manager` > method `Reload` > arg 0 (`flags`)``.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// The error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
 impl<'a> TryFrom<&'a str> for Node<'a> {
     type Error = Error;
 
@@ -329,16 +1149,177 @@ impl<'a> TryFrom<&'a str> for Node<'a> {
     fn try_from(s: &'a str) -> Result<Node<'a>, Error> {
         let mut deserializer = Deserializer::from_str(s);
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        let mut node = Node::deserialize(&mut deserializer)?;
+        node.set_unknown_elements(&mut read_unknown_elements(s)?.into_iter());
+
+        Ok(node)
+    }
+}
+
+// The unknown elements of each node of a tree (in document order) and of each of its interfaces.
+type UnknownElements = Vec<(Vec<String>, Vec<Vec<String>>)>;
+
+// The known child elements of nodes and interfaces, which serde takes care of.
+const NODE_ELEMENTS: &[&[u8]] = &[b"node", b"interface"];
+const INTERFACE_ELEMENTS: &[&[u8]] = &[b"method", b"signal", b"property", b"annotation", b"doc"];
+
+#[derive(Clone, Copy)]
+enum Parent {
+    Node(usize),
+    Interface(usize, usize),
+    Other,
+}
+
+// Extracts the raw XML of the unknown child elements of nodes and interfaces, which serde ignores.
+fn read_unknown_elements(xml: &str) -> Result<UnknownElements, Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut unknown: UnknownElements = vec![];
+    let mut parents = vec![];
+    loop {
+        let start = reader.buffer_position();
+        let (element, empty) = match reader.read_event().map_err(de_error)? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(_) => {
+                parents.pop();
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        let name = element.local_name();
+        let parent = match (parents.last().copied(), name.as_ref()) {
+            (None, b"node") | (Some(Parent::Node(_)), b"node") => {
+                unknown.push((vec![], vec![]));
+                Parent::Node(unknown.len() - 1)
+            }
+            (Some(Parent::Node(n)), b"interface") => {
+                unknown[n].1.push(vec![]);
+                Parent::Interface(n, unknown[n].1.len() - 1)
+            }
+            (Some(Parent::Node(n)), name) if !NODE_ELEMENTS.contains(&name) => {
+                let end = skip_element(&mut reader, &element, empty)?;
+                unknown[n].0.push(xml[start..end].to_string());
+                continue;
+            }
+            (Some(Parent::Interface(n, i)), name) if !INTERFACE_ELEMENTS.contains(&name) => {
+                let end = skip_element(&mut reader, &element, empty)?;
+                unknown[n].1[i].push(xml[start..end].to_string());
+                continue;
+            }
+            _ => Parent::Other,
+        };
+        if !empty {
+            parents.push(parent);
+        }
     }
+
+    Ok(unknown)
+}
+
+// Skips to the end of `element`, returning the position after it.
+fn skip_element(
+    reader: &mut Reader<&[u8]>,
+    element: &quick_xml::events::BytesStart<'_>,
+    empty: bool,
+) -> Result<usize, Error> {
+    if !empty {
+        reader.read_to_end(element.name()).map_err(de_error)?;
+    }
+
+    Ok(reader.buffer_position())
+}
+
+// Returns `xml`, with the unknown elements inserted at the end of their parents.
+fn insert_unknown_elements(xml: &str, unknown: UnknownElements) -> Result<String, Error> {
+    let mut out = String::with_capacity(xml.len());
+    let mut reader = Reader::from_str(xml);
+    let mut parents = vec![];
+    // The number of interfaces met so far, for each node.
+    let mut n_interfaces: Vec<usize> = vec![];
+    let mut written = 0;
+    loop {
+        let start = reader.buffer_position();
+        let event = reader.read_event().map_err(de_error)?;
+        let (parent, empty) = match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let parent = match (parents.last().copied(), e.local_name().as_ref()) {
+                    (None, b"node") | (Some(Parent::Node(_)), b"node") => {
+                        n_interfaces.push(0);
+                        Parent::Node(n_interfaces.len() - 1)
+                    }
+                    (Some(Parent::Node(n)), b"interface") => {
+                        n_interfaces[n] += 1;
+                        Parent::Interface(n, n_interfaces[n] - 1)
+                    }
+                    _ => Parent::Other,
+                };
+                (parent, matches!(event, Event::Empty(_)))
+            }
+            Event::End(_) => match parents.pop() {
+                Some(parent) => (parent, false),
+                None => continue,
+            },
+            Event::Eof => break,
+            _ => continue,
+        };
+        if let Event::Start(_) = event {
+            parents.push(parent);
+            continue;
+        }
+
+        let elements = match parent {
+            Parent::Node(n) => &unknown[n].0,
+            Parent::Interface(n, i) => &unknown[n].1[i],
+            Parent::Other => continue,
+        };
+        if elements.is_empty() {
+            continue;
+        }
+        let end = reader.buffer_position();
+        out.push_str(&xml[written..start]);
+        if empty {
+            // Expand `<name .../>` to `<name ...>...</name>`.
+            let tag = xml[start..end].trim_end_matches("/>").trim_end();
+            let name = tag[1..].split_whitespace().next().unwrap_or_default();
+            out.push_str(tag);
+            out.push('>');
+            for element in elements {
+                out.push_str(element);
+            }
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        } else {
+            for element in elements {
+                out.push_str(element);
+            }
+            out.push_str(&xml[start..end]);
+        }
+        written = end;
+    }
+    out.push_str(&xml[written..]);
+
+    Ok(out)
+}
+
+fn de_error(e: quick_xml::Error) -> Error {
+    Error::QuickXml(e.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{convert::TryInto, error::Error};
+    use std::{
+        convert::{TryFrom, TryInto},
+        error::Error,
+    };
     use test_log::test;
 
-    use super::{ArgDirection, Node};
+    use super::{
+        Arg, ArgDirection, Doc, Interface, MemberKind, Method, Mismatch, Node, Property,
+        PropertyAccess,
+    };
+    use zvariant::Signature;
 
     static EXAMPLE: &str = r##"
 <!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
@@ -390,4 +1371,253 @@ mod tests {
         node.to_writer(&mut writer).unwrap();
         Ok(())
     }
+
+    #[test]
+    fn mismatches() -> Result<(), Box<dyn Error>> {
+        let actual = Node::from_reader(EXAMPLE.as_bytes())?;
+        let actual = &actual.interfaces()[0];
+        let expected = Node::from_reader(
+            r#"
+<node>
+  <interface name="com.example.SampleInterface0">
+    <method name="Frobate">
+      <arg name="foo" type="i" direction="in"/>
+      <arg type="s" direction="out"/>
+      <arg type="a{us}" direction="out"/>
+    </method>
+    <method name="Bazify">
+      <arg name="bar" type="(iiu)" direction="in"/>
+      <arg type="s" direction="out"/>
+    </method>
+    <method name="Reset"/>
+    <signal name="Changed">
+      <arg name="new_value" type="b"/>
+    </signal>
+    <property name="Bar" type="y" access="readwrite"/>
+  </interface>
+</node>"#
+                .as_bytes(),
+        )?;
+        let expected = &expected.interfaces()[0];
+        assert_eq!(
+            expected.mismatches(actual),
+            vec![
+                Mismatch::Signature {
+                    kind: MemberKind::Method,
+                    name: "Bazify".into(),
+                    expected: "(iiu) -> s".into(),
+                    actual: "(iiu) -> v".into(),
+                },
+                Mismatch::MissingMember {
+                    kind: MemberKind::Method,
+                    name: "Reset".into(),
+                },
+            ]
+        );
+        // Members not in the expected interface are fine.
+        assert!(actual.mismatches(actual).is_empty());
+
+        let expected = Node::from_reader(
+            r#"
+<node>
+  <interface name="com.example.SampleInterface0">
+    <signal name="Changed">
+      <arg name="new_value" type="s"/>
+    </signal>
+    <property name="Bar" type="y" access="read"/>
+  </interface>
+</node>"#
+                .as_bytes(),
+        )?;
+        let mismatches = expected.interfaces()[0].mismatches(actual);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "signal `Changed`: expected signature `s`, found `b`"
+        );
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            actual.mismatches(&expected.interfaces()[0]),
+            vec![
+                // Only the signals and properties of `expected` are there.
+                Mismatch::MissingMember {
+                    kind: MemberKind::Method,
+                    name: "Frobate".into(),
+                },
+                Mismatch::MissingMember {
+                    kind: MemberKind::Method,
+                    name: "Bazify".into(),
+                },
+                Mismatch::MissingMember {
+                    kind: MemberKind::Method,
+                    name: "Mogrify".into(),
+                },
+                Mismatch::Signature {
+                    kind: MemberKind::Signal,
+                    name: "Changed".into(),
+                    expected: "b".into(),
+                    actual: "s".into(),
+                },
+                Mismatch::Access {
+                    name: "Bar".into(),
+                    expected: PropertyAccess::ReadWrite,
+                    actual: PropertyAccess::Read,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn docs() -> Result<(), Box<dyn Error>> {
+        let xml = r#"
+<node name="/org/example" xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">
+  <interface name="org.example.Foo">
+    <doc:doc>
+      <doc:summary>The Foo interface</doc:summary>
+      <doc:description>
+        <doc:para>See <doc:ref type="method" to="Frobate">Frobate</doc:ref> first.</doc:para>
+        <doc:para>Second paragraph</doc:para>
+      </doc:description>
+    </doc:doc>
+    <method name="Frobate">
+      <annotation name="org.freedesktop.DBus.DocString" value="Frobate it"/>
+      <arg name="foo" type="i" direction="in">
+        <doc:doc><doc:summary>The foo</doc:summary></doc:doc>
+      </arg>
+    </method>
+  </interface>
+</node>"#;
+        let node = Node::from_reader(xml.as_bytes())?;
+        let iface = &node.interfaces()[0];
+        let text = "The Foo interface\n\nSee Frobate first.\n\nSecond paragraph";
+        assert_eq!(iface.doc().unwrap(), text);
+        assert_eq!(iface.methods()[0].doc().unwrap(), "Frobate it");
+        assert_eq!(iface.methods()[0].args()[0].doc().unwrap(), "The foo");
+
+        // Round-trip.
+        let mut writer = Vec::with_capacity(128);
+        node.to_writer(&mut writer)?;
+        let node = Node::from_reader(writer.as_slice())?;
+        let iface = &node.interfaces()[0];
+        assert_eq!(iface.doc().unwrap(), text);
+        assert_eq!(iface.methods()[0].doc().unwrap(), "Frobate it");
+        assert_eq!(iface.methods()[0].args()[0].doc().unwrap(), "The foo");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_elements() -> Result<(), Box<dyn Error>> {
+        let xml = r#"
+<node name="/org/example" xmlns:foo="http://example.org/foo">
+  <foo:bar x="1"><foo:baz/></foo:bar>
+  <interface name="org.example.Foo">
+    <method name="Frobate"/>
+    <foo:qux/>
+  </interface>
+  <interface name="org.example.Bar"/>
+  <node name="child">
+    <interface name="org.example.Baz">
+      <foo:quux>text</foo:quux>
+    </interface>
+  </node>
+</node>"#;
+        let check = |node: &Node<'_>| {
+            assert_eq!(
+                node.unknown_elements(),
+                [r#"<foo:bar x="1"><foo:baz/></foo:bar>"#]
+            );
+            assert_eq!(node.interfaces()[0].unknown_elements(), ["<foo:qux/>"]);
+            assert!(node.interfaces()[1].unknown_elements().is_empty());
+            let child = &node.nodes()[0];
+            assert!(child.unknown_elements().is_empty());
+            assert_eq!(
+                child.interfaces()[0].unknown_elements(),
+                ["<foo:quux>text</foo:quux>"]
+            );
+        };
+        let node = Node::from_reader(xml.as_bytes())?;
+        check(&node);
+        assert_eq!(node.interfaces()[0].methods()[0].name(), "Frobate");
+
+        // Round-trip.
+        let mut writer = Vec::with_capacity(256);
+        node.to_writer(&mut writer)?;
+        let xml = String::from_utf8(writer)?;
+        let node = Node::try_from(xml.as_str())?;
+        check(&node);
+        assert_eq!(node.interfaces()[0].methods()[0].name(), "Frobate");
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_and_validate() -> Result<(), Box<dyn Error>> {
+        let node = Node::new()
+            .with_name("/org/example")
+            .with_interface(
+                Interface::new("org.example.Bar".try_into()?)
+                    .with_doc("The Bar interface")
+                    .with_method(
+                        Method::new("GetAll".try_into()?)
+                            .with_arg(
+                                Arg::new(Signature::try_from("a{sv}")?, Some(ArgDirection::Out))
+                                    .with_name("properties"),
+                            )
+                            .with_doc_element(Doc::new(Some("Get everything".into()), vec![])),
+                    )
+                    .with_property(Property::new(
+                        "Count".try_into()?,
+                        Signature::try_from("u")?,
+                        PropertyAccess::Read,
+                    )),
+            )
+            .with_node(Node::new().with_name("child"));
+        node.validate()?;
+        let mut writer = Vec::with_capacity(128);
+        node.to_writer(&mut writer)?;
+        let xml = String::from_utf8(writer)?;
+        assert!(xml.starts_with(
+            r#"<node name="/org/example" xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">"#
+        ));
+        let node = Node::from_reader(xml.as_bytes())?;
+        let iface = &node.interfaces()[0];
+        assert_eq!(iface.doc().unwrap(), "The Bar interface");
+        assert_eq!(iface.methods()[0].doc().unwrap(), "Get everything");
+        assert_eq!(iface.methods()[0].args()[0].name(), Some("properties"));
+        assert_eq!(node.nodes()[0].name(), Some("child"));
+
+        for (xml, error) in [
+            (
+                r#"<node name="/a"><interface name="a.B"><method name="M"><arg type="a(" name="x"/></method></interface></node>"#,
+                "node `/a` > interface `a.B` > method `M` > arg 0 (`x`): invalid type signature `a(`",
+            ),
+            (
+                r#"<node><interface name="a.B"><signal name="S"><arg type="s" direction="in"/></signal></interface></node>"#,
+                "node > interface `a.B` > signal `S` > arg 0: signal arguments can't have an `in` direction",
+            ),
+            (
+                r#"<node><interface name="a.B"><property name="P" type="ss" access="read"/></interface></node>"#,
+                "node > interface `a.B` > property `P`: invalid type signature `ss`",
+            ),
+            (
+                r#"<node><interface name="a.B"><method name="M"/><method name="M"/></interface></node>"#,
+                "node > interface `a.B` > method `M`: duplicate method",
+            ),
+            (
+                r#"<node name="/a"><node name="/b"/></node>"#,
+                "node `/a` > node `/b`: child nodes must have a relative name",
+            ),
+            (
+                r#"<node><node name="b"><interface name="a.B"><annotation name="bad" value="x"/></interface></node></node>"#,
+                "node > node `b` > interface `a.B`: invalid annotation name `bad`",
+            ),
+        ] {
+            let node = Node::from_reader(xml.as_bytes())?;
+            assert_eq!(node.validate().unwrap_err().to_string(), error);
+        }
+
+        Ok(())
+    }
 }
@@ -493,8 +493,10 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
                 .filter(|a| a.direction().unwrap() == zbus::quick_xml::ArgDirection::Out);
 
             if method.name() == "TestSingleStructRet" {
-                assert_eq!(args.len(), 1);
-                assert_eq!(out_args.next().unwrap().ty(), "(is)");
+                // The returned structure is described as one out argument per field.
+                assert_eq!(args.len(), 2);
+                assert_eq!(out_args.next().unwrap().ty(), "i");
+                assert_eq!(out_args.next().unwrap().ty(), "s");
                 assert!(out_args.next().is_none());
             } else {
                 assert_eq!(args.len(), 2);
@@ -505,6 +507,19 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
             }
         }
     }
+    #[cfg(feature = "quick-xml")]
+    {
+        let mismatches = proxy.verify_introspection().await?;
+        debug!("Introspection mismatches: {:?}", mismatches);
+        // The proxy's `RefType` property is served as `RefProp` by the interface.
+        assert_eq!(
+            mismatches,
+            [zbus::quick_xml::Mismatch::MissingMember {
+                kind: zbus::quick_xml::MemberKind::Property,
+                name: "RefType".to_string(),
+            }]
+        );
+    }
     // build-time check to see if macro is doing the right thing.
     let _ = proxy.test_single_struct_ret().await?.foo;
     let _ = proxy.test_multi_ret().await?.1;
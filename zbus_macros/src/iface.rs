@@ -1,11 +1,11 @@
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use std::collections::BTreeMap;
 use syn::{
-    self, parse_quote, punctuated::Punctuated, spanned::Spanned, AngleBracketedGenericArguments,
-    AttributeArgs, Error, FnArg, GenericArgument, ImplItem, ItemImpl, Lit::Str, Meta,
-    Meta::NameValue, MetaList, MetaNameValue, NestedMeta, PatType, PathArguments, ReturnType,
-    Signature, Token, Type, TypePath,
+    self, ext::IdentExt, parse_quote, punctuated::Punctuated, spanned::Spanned,
+    AngleBracketedGenericArguments, Attribute, AttributeArgs, Error, FnArg, GenericArgument,
+    ImplItem, ItemImpl, Lit::Str, Meta::NameValue, MetaNameValue, PatType, PathArguments,
+    ReturnType, Signature, Token, Type, TypePath,
 };
 use zvariant_utils::{case, def_attrs};
 
@@ -24,7 +24,9 @@ def_attrs! {
         name str,
         signal none,
         property none,
-        out_args [str]
+        out_args [str],
+        out_annotations [str],
+        annotations [str]
     };
 }
 
@@ -38,7 +40,9 @@ mod arg_attrs {
             object_server none,
             connection none,
             header none,
-            signal_context none
+            signal_context none,
+            name str,
+            annotations [str]
         };
     }
 }
@@ -51,6 +55,8 @@ struct Property<'a> {
     write: bool,
     ty: Option<&'a Type>,
     doc_comments: TokenStream,
+    doc_string: TokenStream,
+    annotations: Vec<(String, String)>,
 }
 
 impl<'a> Property<'a> {
@@ -60,6 +66,8 @@ impl<'a> Property<'a> {
             write: false,
             ty: None,
             doc_comments: quote!(),
+            doc_string: quote!(),
+            annotations: vec![],
         }
     }
 }
@@ -75,7 +83,7 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
     let mut get_all = quote!();
     let mut call_dispatch = quote!();
     let mut call_mut_dispatch = quote!();
-    let mut introspect = quote!();
+    let mut introspect = introspect_doc_string(doc_lines(&input.attrs));
     let mut generated_signals = quote!();
 
     // the impl Type
@@ -124,20 +132,10 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
             .attrs
             .retain(|attr| !attr.path.is_ident("dbus_interface"));
 
-        let docs = get_doc_attrs(&method.attrs)
-            .iter()
-            .filter_map(|attr| {
-                if let Ok(NameValue(MetaNameValue { lit: Str(s), .. })) = attr.parse_meta() {
-                    Some(s.value())
-                } else {
-                    // non #[doc = "..."] attributes are not our concern
-                    // we leave them for rustc to handle
-                    None
-                }
-            })
-            .collect();
-
-        let doc_comments = to_xml_docs(docs);
+        let docs = doc_lines(&method.attrs);
+        let doc_comments = to_xml_docs(docs.clone());
+        let doc_string = introspect_doc_string(docs);
+        let annotations = parse_annotations(attrs.annotations.as_deref(), &ident)?;
         let is_property = attrs.property;
         let is_signal = attrs.signal;
         let out_args = attrs.out_args.as_deref();
@@ -183,9 +181,14 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
             None
         };
 
-        let mut intro_args = quote!();
-        intro_args.extend(introspect_input_args(&typed_inputs, is_signal));
-        let is_result_output = introspect_add_output_args(&mut intro_args, output, out_args)?;
+        let mut intro_args = introspect_input_args(&typed_inputs, is_signal)?;
+        let is_result_output = introspect_add_output_args(
+            &mut intro_args,
+            output,
+            out_args,
+            attrs.out_annotations.as_deref(),
+        )?;
+        intro_args.extend(introspect_annotations(&annotations));
 
         let (args_from_msg, args_names) = get_args_from_inputs(&typed_inputs, &zbus)?;
 
@@ -216,7 +219,7 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
 
         if is_signal {
             introspect.extend(doc_comments);
-            introspect.extend(introspect_signal(&member_name, &intro_args));
+            introspect.extend(introspect_signal(&member_name, &doc_string, &intro_args));
             let signal_context = signal_context_arg.unwrap().pat;
 
             method.block = parse_quote!({
@@ -238,6 +241,8 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
 
             let p = p.or_insert_with(Property::new);
             p.doc_comments.extend(doc_comments);
+            p.doc_string.extend(doc_string);
+            p.annotations.extend(annotations);
             if has_inputs {
                 p.write = true;
 
@@ -422,7 +427,7 @@ pub fn expand(args: AttributeArgs, mut input: ItemImpl) -> syn::Result<TokenStre
             }
         } else {
             introspect.extend(doc_comments);
-            introspect.extend(introspect_method(&member_name, &intro_args));
+            introspect.extend(introspect_method(&member_name, &doc_string, &intro_args));
 
             let m = quote! {
                 #member_name => {
@@ -672,91 +677,149 @@ fn get_args_from_inputs(
 fn clean_input_args(inputs: &mut Punctuated<FnArg, Token![,]>) {
     for input in inputs {
         if let FnArg::Typed(t) = input {
-            t.attrs.retain(|attr| !attr.path.is_ident("zbus"));
+            t.attrs
+                .retain(|attr| !attr.path.is_ident("zbus") && !attr.path.is_ident("doc"));
         }
     }
 }
 
-fn introspect_signal(name: &str, args: &TokenStream) -> TokenStream {
+fn introspect_signal(name: &str, doc_string: &TokenStream, args: &TokenStream) -> TokenStream {
     quote!(
         ::std::writeln!(writer, "{:indent$}<signal name=\"{}\">", "", #name, indent = level).unwrap();
         {
             let level = level + 2;
+            #doc_string
             #args
         }
         ::std::writeln!(writer, "{:indent$}</signal>", "", indent = level).unwrap();
     )
 }
 
-fn introspect_method(name: &str, args: &TokenStream) -> TokenStream {
+fn introspect_method(name: &str, doc_string: &TokenStream, args: &TokenStream) -> TokenStream {
     quote!(
         ::std::writeln!(writer, "{:indent$}<method name=\"{}\">", "", #name, indent = level).unwrap();
         {
             let level = level + 2;
+            #doc_string
             #args
         }
         ::std::writeln!(writer, "{:indent$}</method>", "", indent = level).unwrap();
     )
 }
 
-fn introspect_input_args(
-    inputs: &[PatType],
-    is_signal: bool,
-) -> impl Iterator<Item = TokenStream> + '_ {
-    inputs
-        .iter()
-        .filter_map(move |pat_type @ PatType { ty, attrs, .. }| {
-            let is_special_arg = attrs.iter().any(|attr| {
-                if !attr.path.is_ident("zbus") {
-                    return false;
-                }
-
-                let meta = match attr.parse_meta() {
-                    ::std::result::Result::Ok(meta) => meta,
-                    ::std::result::Result::Err(_) => return false,
-                };
-
-                let nested = match meta {
-                    Meta::List(MetaList { nested, .. }) => nested,
-                    _ => return false,
-                };
+fn introspect_input_args(inputs: &[PatType], is_signal: bool) -> syn::Result<TokenStream> {
+    let mut args = quote!();
 
-                let res = nested.iter().any(|nested_meta| {
-                    matches!(
-                        nested_meta,
-                        NestedMeta::Meta(Meta::Path(path))
-                        if path.is_ident("object_server") || path.is_ident("connection") || path.is_ident("header") || path.is_ident("signal_context")
-                    )
-                });
+    for pat_type in inputs {
+        let attrs = ArgAttributes::parse(&pat_type.attrs)?;
+        if attrs.object_server || attrs.connection || attrs.header || attrs.signal_context {
+            continue;
+        }
 
-                res
-            });
-            if is_special_arg {
-                return None;
-            }
+        let arg_name = match attrs.name {
+            Some(name) => name,
+            None => pat_ident(pat_type).unwrap().unraw().to_string(),
+        };
+        let ty = &pat_type.ty;
+        let dir = if is_signal { None } else { Some("in") };
+        let mut annotations = introspect_doc_string(doc_lines(&pat_type.attrs));
+        annotations.extend(introspect_annotations(&parse_annotations(
+            attrs.annotations.as_deref(),
+            pat_type,
+        )?));
+        args.extend(introspect_arg(
+            Some(&arg_name),
+            quote!(<#ty>::signature()),
+            dir,
+            &annotations,
+        ));
+    }
 
-            let ident = pat_ident(pat_type).unwrap();
-            let arg_name = quote!(#ident).to_string();
-            let dir = if is_signal { "" } else { " direction=\"in\"" };
-            Some(quote!(
-                ::std::writeln!(writer, "{:indent$}<arg name=\"{}\" type=\"{}\"{}/>", "",
-                         #arg_name, <#ty>::signature(), #dir, indent = level).unwrap();
-            ))
-        })
+    Ok(args)
 }
 
-fn introspect_output_arg(ty: &Type, arg_name: Option<&String>) -> TokenStream {
-    let arg_name = match arg_name {
-        Some(name) => format!("name=\"{name}\" "),
-        None => String::from(""),
+// Write an `<arg>` element, with the given annotations. `ty` is an expression evaluating to the
+// signature of the argument.
+fn introspect_arg(
+    name: Option<&str>,
+    ty: TokenStream,
+    direction: Option<&str>,
+    annotations: &TokenStream,
+) -> TokenStream {
+    let name = match name {
+        Some(name) => format!("name=\"{}\" ", xml_escape(name)),
+        None => String::new(),
     };
+    let direction = match direction {
+        Some(direction) => format!(" direction=\"{direction}\""),
+        None => String::new(),
+    };
+
+    if annotations.is_empty() {
+        return quote!(
+            ::std::writeln!(writer, "{:indent$}<arg {}type=\"{}\"{}/>", "",
+                     #name, #ty, #direction, indent = level).unwrap();
+        );
+    }
 
     quote!(
-        ::std::writeln!(writer, "{:indent$}<arg {}type=\"{}\" direction=\"out\"/>", "",
-                 #arg_name, <#ty>::signature(), indent = level).unwrap();
+        ::std::writeln!(writer, "{:indent$}<arg {}type=\"{}\"{}>", "",
+                 #name, #ty, #direction, indent = level).unwrap();
+        {
+            let level = level + 2;
+            #annotations
+        }
+        ::std::writeln!(writer, "{:indent$}</arg>", "", indent = level).unwrap();
     )
 }
 
+// Parse a list of annotations, given as `"name=value"` strings.
+fn parse_annotations<T: ToTokens>(
+    annotations: Option<&[String]>,
+    spanned: T,
+) -> syn::Result<Vec<(String, String)>> {
+    annotations
+        .unwrap_or_default()
+        .iter()
+        .map(|annotation| match annotation.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.to_string())),
+            None => Err(Error::new_spanned(
+                &spanned,
+                format!("invalid annotation `{annotation}`, expected `name=value`"),
+            )),
+        })
+        .collect()
+}
+
+fn introspect_annotations(annotations: &[(String, String)]) -> TokenStream {
+    let mut tokens = quote!();
+    for (name, value) in annotations {
+        let name = xml_escape(name);
+        let value = xml_escape(value);
+        tokens.extend(quote!(
+            ::std::writeln!(writer, "{:indent$}<annotation name=\"{}\" value=\"{}\"/>", "",
+                     #name, #value, indent = level).unwrap();
+        ));
+    }
+
+    tokens
+}
+
+// The annotations of the out argument `name`, from `out_annotations` entries of the form
+// `"arg:name=value"`.
+fn out_arg_annotations(
+    name: Option<&str>,
+    out_annotations: &[(String, String, String)],
+) -> TokenStream {
+    let annotations: Vec<_> = out_annotations
+        .iter()
+        .filter(|(arg, _, _)| Some(arg.as_str()) == name)
+        .map(|(_, name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    introspect_annotations(&annotations)
+}
+
 fn get_result_type(p: &TypePath) -> syn::Result<&Type> {
     if let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) = &p
         .path
@@ -777,9 +840,26 @@ fn introspect_add_output_args(
     args: &mut TokenStream,
     output: &ReturnType,
     arg_names: Option<&[String]>,
+    out_annotations: Option<&[String]>,
 ) -> syn::Result<bool> {
     let mut is_result_output = false;
 
+    let out_annotations = parse_annotations(out_annotations, output)?
+        .into_iter()
+        .map(|(name, value)| match name.split_once(':') {
+            Some((arg, name)) if arg_names.unwrap_or_default().iter().any(|n| n == arg) => {
+                Ok((arg.to_string(), name.trim().to_string(), value))
+            }
+            _ => Err(Error::new_spanned(
+                output,
+                format!(
+                    "invalid out argument annotation `{name}={value}`, expected \
+                     `arg:name=value` with `arg` listed in `out_args`"
+                ),
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
     if let ReturnType::Type(_, ty) = output {
         let mut ty = ty.as_ref();
 
@@ -799,22 +879,69 @@ fn introspect_add_output_args(
         if let Type::Tuple(t) = ty {
             if let Some(arg_names) = arg_names {
                 if t.elems.len() != arg_names.len() {
-                    // Turn into error
-                    panic!("Number of out arg names different from out args specified")
+                    return Err(Error::new_spanned(
+                        t,
+                        "number of out arg names different from out args specified",
+                    ));
                 }
             }
-            for i in 0..t.elems.len() {
-                let name = arg_names.map(|names| &names[i]);
-                args.extend(introspect_output_arg(&t.elems[i], name));
+            for (i, ty) in t.elems.iter().enumerate() {
+                let name = arg_names.map(|names| names[i].as_str());
+                let annotations = out_arg_annotations(name, &out_annotations);
+                args.extend(introspect_arg(
+                    name,
+                    quote!(<#ty>::signature()),
+                    Some("out"),
+                    &annotations,
+                ));
             }
         } else {
-            args.extend(introspect_output_arg(ty, None));
+            args.extend(introspect_single_output_arg(
+                ty,
+                arg_names.unwrap_or_default(),
+                &out_annotations,
+            ));
         }
     }
 
     Ok(is_result_output)
 }
 
+// A single returned structure is sent as multiple out arguments, one for each field, so the
+// introspection data has to describe them as such. Whether `ty` is a structure is only known at
+// runtime.
+fn introspect_single_output_arg(
+    ty: &Type,
+    arg_names: &[String],
+    out_annotations: &[(String, String, String)],
+) -> TokenStream {
+    let field_args = arg_names.iter().enumerate().map(|(i, name)| {
+        let annotations = out_arg_annotations(Some(name), out_annotations);
+        let arg = introspect_arg(Some(name), quote!(field_ty), Some("out"), &annotations);
+
+        quote!(#i => { #arg })
+    });
+    let unnamed_field_arg = introspect_arg(None, quote!(field_ty), Some("out"), &quote!());
+    let name = arg_names.first().map(String::as_str);
+    let annotations = out_arg_annotations(name, out_annotations);
+    let arg = introspect_arg(name, quote!(signature), Some("out"), &annotations);
+
+    quote!({
+        let signature = <#ty>::signature();
+        if signature.starts_with('(') {
+            let fields = signature.slice(1..signature.len() - 1);
+            for (i, field_ty) in fields.complete_types().enumerate() {
+                match i {
+                    #(#field_args)*
+                    _ => { #unnamed_field_arg }
+                }
+            }
+        } else {
+            #arg
+        }
+    })
+}
+
 fn get_property_type(output: &ReturnType) -> syn::Result<&Type> {
     if let ReturnType::Type(_, ty) = output {
         let ty = ty.as_ref();
@@ -859,15 +986,31 @@ fn introspect_properties(
             Error::new_spanned(&name, "Write-only properties aren't supported yet")
         })?;
 
-        let doc_comments = prop.doc_comments;
-        introspection.extend(quote!(
-            #doc_comments
-            ::std::writeln!(
-                writer,
-                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{}\"/>",
-                "", #name, <#ty>::signature(), #access, indent = level,
-            ).unwrap();
-        ));
+        introspection.extend(prop.doc_comments);
+        let mut annotations = prop.doc_string;
+        annotations.extend(introspect_annotations(&prop.annotations));
+        if annotations.is_empty() {
+            introspection.extend(quote!(
+                ::std::writeln!(
+                    writer,
+                    "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{}\"/>",
+                    "", #name, <#ty>::signature(), #access, indent = level,
+                ).unwrap();
+            ));
+        } else {
+            introspection.extend(quote!(
+                ::std::writeln!(
+                    writer,
+                    "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{}\">",
+                    "", #name, <#ty>::signature(), #access, indent = level,
+                ).unwrap();
+                {
+                    let level = level + 2;
+                    #annotations
+                }
+                ::std::writeln!(writer, "{:indent$}</property>", "", indent = level).unwrap();
+            ));
+        }
     }
 
     Ok(())
@@ -904,3 +1047,64 @@ pub fn to_xml_docs(lines: Vec<String>) -> TokenStream {
 
     docs
 }
+
+// The `org.freedesktop.DBus.DocString` annotation for the given doc comment lines, if any.
+pub fn introspect_doc_string(lines: Vec<String>) -> TokenStream {
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .flat_map(|s| s.split('\n'))
+        .skip_while(|s| is_blank(s))
+        .map(|s| s.strip_prefix(' ').unwrap_or(s).trim_end())
+        .collect();
+
+    while let Some(true) = lines.last().map(|s| is_blank(s)) {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        return quote!();
+    }
+
+    let doc = xml_escape(&lines.join("\n"));
+    quote!(
+        ::std::writeln!(
+            writer,
+            "{:indent$}<annotation name=\"org.freedesktop.DBus.DocString\" value=\"{}\"/>",
+            "", #doc, indent = level,
+        ).unwrap();
+    )
+}
+
+// The doc comment lines in `attrs`.
+pub fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    get_doc_attrs(attrs)
+        .iter()
+        .filter_map(|attr| {
+            if let Ok(NameValue(MetaNameValue { lit: Str(s), .. })) = attr.parse_meta() {
+                Some(s.value())
+            } else {
+                // non #[doc = "..."] attributes are not our concern
+                // we leave them for rustc to handle
+                None
+            }
+        })
+        .collect()
+}
+
+// Escape `s` for use in an XML attribute value. Line breaks are escaped too, since they would
+// otherwise be normalized to spaces by XML parsers.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
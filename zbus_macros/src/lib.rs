@@ -104,6 +104,18 @@ mod utils;
 /// signal arguments. It also implements `Deref<Target = Message>` to allow easy access to the
 /// underlying [`zbus::Message`].
 ///
+/// # Introspection
+///
+/// The proxy types also provide an associated `introspection_xml()` function, returning the
+/// introspection XML of the interface as described by the trait (arguments, property access and
+/// the `org.freedesktop.DBus.Method.NoReply`, `org.freedesktop.DBus.Property.EmitsChangedSignal`
+/// and `org.freedesktop.DBus.Deprecated` annotations), and `introspect_to_writer()` to write it to
+/// a [`std::fmt::Write`]. Doc comments on the trait and its methods are included as
+/// `org.freedesktop.DBus.DocString` annotations. This is useful to check the proxy against the
+/// introspection data of the actual service, in tests. Since the names of the out arguments are not
+/// known, they're omitted.
+/// Methods and signals using generic types are omitted entirely.
+///
 /// # Example
 ///
 /// ```no_run
@@ -226,11 +238,20 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   important. You can use `out_args` to specify their names.
 ///
 ///   In such case, your method must return a tuple containing
-///   your out arguments, in the same order as passed to `out_args`.
+///   your out arguments, in the same order as passed to `out_args`. If the method returns a
+///   structure instead, each of its fields is a separate out argument (see below) and is named
+///   after the corresponding entry of `out_args`.
+///
+/// * `out_annotations` - Annotations on the out arguments named by `out_args`, as a list of
+///   `"arg:name=value"` strings.
+///
+/// * `annotations` - Annotations on the method, property or signal, as a list of `"name=value"`
+///   strings. The annotations of a property's getter and setter are merged.
 ///
-/// The `struct_return` attribute (from zbus 1.x) is no longer supported. If you want to return a
-/// single structure from a method, declare it to return a tuple containing either a named structure
-/// or a nested tuple.
+/// The `struct_return` attribute (from zbus 1.x) is no longer supported. A structure returned from
+/// a method is sent as multiple out arguments, one for each of its fields, and is described as such
+/// in the introspection data. If you want to return a single structure from a method, declare it to
+/// return a tuple containing either a named structure or a nested tuple.
 ///
 /// Note: a `<property_name_in_snake_case>_changed` method is generated for each property: this
 /// method emits the "PropertiesChanged" signal for the associated property. The setter (if it
@@ -253,6 +274,13 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   D-Bus method call being handled.
 /// * `signal_context` - This marks the method argument to receive a [`SignalContext`]
 ///   instance, which is needed for emitting signals the easy way.
+/// * `name` - The name of the argument in the introspection data, instead of the name of the Rust
+///   binding.
+/// * `annotations` - Annotations on the argument, as a list of `"name=value"` strings.
+///
+/// Doc comments on the `impl` block, methods, properties, signals and their arguments are included
+/// in the introspection data as `org.freedesktop.DBus.DocString` annotations, so that D-Bus
+/// debugging tools and code generators can show them.
 ///
 /// # Example
 ///
@@ -306,10 +334,18 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     #[dbus_interface(signal)]
 ///     async fn bye(signal_ctxt: &SignalContext<'_>, message: &str) -> zbus::Result<()>;
 ///
-///     #[dbus_interface(out_args("answer", "question"))]
+///     #[dbus_interface(
+///         out_args("answer", "question"),
+///         out_annotations("answer:org.myservice.Unit=none"),
+///     )]
 ///     fn meaning_of_life(&self) -> zbus::fdo::Result<(i32, String)> {
 ///         Ok((42, String::from("Meaning of life")))
 ///     }
+///
+///     // "Lookup" method, with its `type` argument named after a Rust keyword.
+///     fn lookup(&self, #[zbus(name = "type")] kind: &str) -> u32 {
+///         kind.len() as u32
+///     }
 /// }
 ///
 ///# Ok::<_, Box<dyn Error + Send + Sync>>(())
@@ -1,10 +1,14 @@
-use crate::utils::{pat_ident, typed_arg, zbus_path};
+use crate::{
+    iface::{doc_lines, introspect_doc_string},
+    utils::{pat_ident, typed_arg, zbus_path},
+};
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use regex::Regex;
+use std::collections::BTreeMap;
 use syn::{
-    self, fold::Fold, parse_quote, spanned::Spanned, AttributeArgs, Error, FnArg, Ident, ItemTrait,
-    ReturnType, TraitItemMethod,
+    self, fold::Fold, parse_quote, spanned::Spanned, AttributeArgs, Error, FnArg, Generics, Ident,
+    ItemTrait, PathArguments, ReturnType, TraitItemMethod, Type,
 };
 use zvariant_utils::{case, def_attrs};
 
@@ -193,6 +197,8 @@ pub fn create_proxy(
     let mut stream_types = TokenStream::new();
     let mut has_properties = false;
     let mut uncached_properties: Vec<String> = vec![];
+    let mut introspect = introspect_doc_string(doc_lines(&input.attrs));
+    let mut properties = BTreeMap::new();
 
     let async_opts = AsyncOpts::new(blocking);
 
@@ -226,6 +232,10 @@ pub fn create_proxy(
                 } else {
                     PropertyEmitsChangedSignal::True
                 };
+                let prop = properties
+                    .entry(member_name.clone())
+                    .or_insert_with(IntrospectProperty::default);
+                introspect_add_property(prop, m, &emits_changed_signal);
 
                 if let PropertyEmitsChangedSignal::False = emits_changed_signal {
                     uncached_properties.push(member_name.clone());
@@ -239,6 +249,7 @@ pub fn create_proxy(
                     emits_changed_signal,
                 )
             } else if is_signal {
+                introspect.extend(introspect_member(&member_name, m, &attrs, true));
                 let (method, types) = gen_proxy_signal(
                     &proxy_name,
                     &iface_name,
@@ -252,12 +263,17 @@ pub fn create_proxy(
 
                 method
             } else {
+                introspect.extend(introspect_member(&member_name, m, &attrs, false));
                 gen_proxy_method_call(&member_name, &method_name, m, &attrs, &async_opts)
             };
             methods.extend(m);
         }
     }
 
+    for (name, prop) in properties {
+        introspect.extend(introspect_property(&name, prop));
+    }
+
     let AsyncOpts { usage, wait, .. } = async_opts;
     let (proxy_struct, connection, builder) = if blocking {
         let connection = quote! { #zbus::blocking::Connection };
@@ -397,6 +413,49 @@ pub fn create_proxy(
                 &self.0
             }
 
+            /// Write the introspection XML of the interface, as described by the proxy, to `writer`.
+            ///
+            /// Members with generic types are omitted, since their signature is unknown.
+            pub fn introspect_to_writer(writer: &mut dyn ::std::fmt::Write, level: usize) {
+                ::std::writeln!(
+                    writer,
+                    r#"{:indent$}<interface name="{}">"#,
+                    "",
+                    #iface_name,
+                    indent = level
+                ).unwrap();
+                {
+                    use #zbus::zvariant::Type;
+
+                    let level = level + 2;
+                    #introspect
+                }
+                ::std::writeln!(writer, r#"{:indent$}</interface>"#, "", indent = level).unwrap();
+            }
+
+            /// The introspection XML of the interface, as described by the proxy.
+            ///
+            /// This is useful to check the proxy against the actual introspection data of a service.
+            pub fn introspection_xml() -> ::std::string::String {
+                let mut xml = ::std::string::String::new();
+                Self::introspect_to_writer(&mut xml, 0);
+
+                xml
+            }
+
+            #zbus::__if_quick_xml! {
+                /// Check the proxy against the introspection data of the associated object.
+                ///
+                /// See `zbus::Proxy::verify_against_introspection` for details.
+                pub #usage fn verify_introspection(
+                    &self,
+                ) -> #zbus::Result<::std::vec::Vec<#zbus::quick_xml::Mismatch>> {
+                    self.0
+                        .verify_against_introspection(&Self::introspection_xml())
+                        #wait
+                }
+            }
+
             #methods
         }
 
@@ -788,6 +847,259 @@ fn gen_proxy_property(
     }
 }
 
+#[derive(Default)]
+struct IntrospectProperty {
+    ty: Option<TokenStream>,
+    read: bool,
+    write: bool,
+    deprecated: bool,
+    annotations: TokenStream,
+    doc_string: TokenStream,
+}
+
+fn introspect_add_property(
+    prop: &mut IntrospectProperty,
+    m: &TraitItemMethod,
+    emits_changed_signal: &PropertyEmitsChangedSignal,
+) {
+    let generics = &m.sig.generics;
+    if m.sig.inputs.len() > 1 {
+        prop.write = true;
+        if let Some(FnArg::Typed(arg)) = m.sig.inputs.last() {
+            prop.ty = prop
+                .ty
+                .take()
+                .or_else(|| introspect_type(&arg.ty, generics));
+        }
+    } else {
+        prop.read = true;
+        if let Some(ty) = result_ok_type(&m.sig.output) {
+            // The getter's type is preferred, since setters may take a reference.
+            prop.ty = introspect_type(ty, generics).or_else(|| prop.ty.take());
+        }
+        let value = match emits_changed_signal {
+            PropertyEmitsChangedSignal::True => None,
+            PropertyEmitsChangedSignal::Invalidates => Some("invalidates"),
+            PropertyEmitsChangedSignal::Const => Some("const"),
+            PropertyEmitsChangedSignal::False => Some("false"),
+        };
+        if let Some(value) = value {
+            prop.annotations.extend(introspect_annotation(
+                "org.freedesktop.DBus.Property.EmitsChangedSignal",
+                value,
+            ));
+        }
+        prop.doc_string = introspect_doc_string(doc_lines(&m.attrs));
+    }
+    prop.deprecated |= is_deprecated(m);
+}
+
+fn introspect_property(name: &str, prop: IntrospectProperty) -> TokenStream {
+    let IntrospectProperty {
+        ty,
+        read,
+        write,
+        deprecated,
+        annotations,
+        doc_string,
+    } = prop;
+    let mut annotations = quote!(#doc_string #annotations);
+    if deprecated {
+        annotations.extend(introspect_annotation(
+            "org.freedesktop.DBus.Deprecated",
+            "true",
+        ));
+    }
+    let access = match (read, write) {
+        (true, true) => "readwrite",
+        (true, false) => "read",
+        _ => "write",
+    };
+    let ty = match ty {
+        Some(ty) => ty,
+        None => return quote!(),
+    };
+
+    if annotations.is_empty() {
+        quote!(
+            ::std::writeln!(
+                writer,
+                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{}\"/>",
+                "", #name, #ty, #access, indent = level,
+            ).unwrap();
+        )
+    } else {
+        quote!(
+            ::std::writeln!(
+                writer,
+                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{}\">",
+                "", #name, #ty, #access, indent = level,
+            ).unwrap();
+            {
+                let level = level + 2;
+                #annotations
+            }
+            ::std::writeln!(writer, "{:indent$}</property>", "", indent = level).unwrap();
+        )
+    }
+}
+
+fn introspect_member(
+    name: &str,
+    m: &TraitItemMethod,
+    attrs: &MethodAttributes,
+    is_signal: bool,
+) -> TokenStream {
+    let generics = &m.sig.generics;
+    let mut args = introspect_doc_string(doc_lines(&m.attrs));
+    for arg in m.sig.inputs.iter().filter_map(typed_arg) {
+        let ty = match introspect_type(&arg.ty, generics) {
+            Some(ty) => ty,
+            None => return quote!(),
+        };
+        let arg_name = pat_ident(arg).map(|i| i.to_string()).unwrap_or_default();
+        let dir = if is_signal { "" } else { " direction=\"in\"" };
+        args.extend(quote!(
+            ::std::writeln!(writer, "{:indent$}<arg name=\"{}\" type=\"{}\"{}/>", "",
+                     #arg_name, #ty, #dir, indent = level).unwrap();
+        ));
+    }
+
+    if !is_signal {
+        let mut is_tuple = false;
+        let out_types = if attrs.object.is_some() {
+            let zbus = zbus_path();
+            vec![quote!(<#zbus::zvariant::OwnedObjectPath as Type>::signature())]
+        } else {
+            let ty = result_ok_type(&m.sig.output);
+            is_tuple = matches!(ty, Some(Type::Tuple(_)));
+            let tys: Vec<&Type> = match ty {
+                Some(Type::Tuple(t)) => t.elems.iter().collect(),
+                Some(ty) => vec![ty],
+                None => vec![],
+            };
+            let mut out_types = vec![];
+            for ty in tys {
+                match introspect_type(ty, generics) {
+                    Some(ty) => out_types.push(ty),
+                    None => return quote!(),
+                }
+            }
+
+            out_types
+        };
+        let write_out_arg = |ty: &TokenStream| {
+            quote!(
+                ::std::writeln!(writer, "{:indent$}<arg type=\"{}\" direction=\"out\"/>", "",
+                         #ty, indent = level).unwrap();
+            )
+        };
+        match &out_types[..] {
+            // A single structure is received as multiple out arguments, one for each field.
+            [ty] if attrs.object.is_none() && !is_tuple => {
+                let field_arg = write_out_arg(&quote!(field_ty));
+                let arg = write_out_arg(&quote!(signature));
+                args.extend(quote!({
+                    let signature = #ty;
+                    if signature.starts_with('(') {
+                        let fields = signature.slice(1..signature.len() - 1);
+                        for field_ty in fields.complete_types() {
+                            #field_arg
+                        }
+                    } else {
+                        #arg
+                    }
+                }));
+            }
+            out_types => {
+                for ty in out_types {
+                    args.extend(write_out_arg(ty));
+                }
+            }
+        }
+        if attrs.no_reply {
+            args.extend(introspect_annotation(
+                "org.freedesktop.DBus.Method.NoReply",
+                "true",
+            ));
+        }
+    }
+    if is_deprecated(m) {
+        args.extend(introspect_annotation(
+            "org.freedesktop.DBus.Deprecated",
+            "true",
+        ));
+    }
+
+    let kind = if is_signal { "signal" } else { "method" };
+    quote!(
+        ::std::writeln!(writer, "{:indent$}<{} name=\"{}\">", "", #kind, #name, indent = level).unwrap();
+        {
+            let level = level + 2;
+            #args
+        }
+        ::std::writeln!(writer, "{:indent$}</{}>", "", #kind, indent = level).unwrap();
+    )
+}
+
+fn introspect_annotation(name: &str, value: &str) -> TokenStream {
+    quote!(
+        ::std::writeln!(writer, "{:indent$}<annotation name=\"{}\" value=\"{}\"/>", "",
+                 #name, #value, indent = level).unwrap();
+    )
+}
+
+fn is_deprecated(m: &TraitItemMethod) -> bool {
+    m.attrs.iter().any(|a| a.path.is_ident("deprecated"))
+}
+
+// The expression giving the signature of `ty`, or `None` if it depends on a generic type (or is an
+// `impl Trait`).
+fn introspect_type(ty: &Type, generics: &Generics) -> Option<TokenStream> {
+    let ty_str = ty.to_token_stream().to_string();
+    let is_generic = ty_str.contains("impl ")
+        || generics.type_params().any(|param| {
+            let regex = Regex::new(&format!(r"\b{}\b", param.ident)).unwrap();
+            regex.is_match(&ty_str)
+        });
+    if is_generic {
+        return None;
+    }
+    let ty = ElideLifetimes.fold_type(ty.clone());
+
+    Some(quote!(<#ty as Type>::signature()))
+}
+
+// The `T` of a `Result<T, E>` (or `Result<T>` alias) return type.
+fn result_ok_type(output: &ReturnType) -> Option<&Type> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => ty.as_ref(),
+        ReturnType::Default => return None,
+    };
+    if let Type::Path(p) = ty {
+        if let Some(PathArguments::AngleBracketed(args)) =
+            p.path.segments.last().map(|s| &s.arguments)
+        {
+            if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                return match ty {
+                    Type::Tuple(t) if t.elems.is_empty() => None,
+                    ty => Some(ty),
+                };
+            }
+        }
+    }
+
+    Some(ty)
+}
+
+struct ElideLifetimes;
+
+impl Fold for ElideLifetimes {
+    fn fold_lifetime(&mut self, _node: syn::Lifetime) -> syn::Lifetime {
+        syn::Lifetime::new("'_", Span::call_site())
+    }
+}
+
 struct SetLifetimeS;
 
 impl Fold for SetLifetimeS {
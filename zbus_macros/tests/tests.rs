@@ -92,6 +92,89 @@ fn test_proxy() {
     });
 }
 
+#[test]
+fn test_proxy_introspection() {
+    #[derive(serde::Deserialize, zbus::zvariant::Type)]
+    struct Point {
+        _x: i32,
+        _y: i32,
+    }
+
+    #[dbus_proxy(
+        interface = "org.freedesktop.zbus_macros.Introspection",
+        default_service = "org.freedesktop.zbus_macros",
+        default_path = "/org/freedesktop/zbus_macros/introspection"
+    )]
+    trait Introspection {
+        /// Concatenate two strings.
+        fn concat(&self, first: &str, second: &str) -> zbus::Result<String>;
+
+        fn stats(&self, values: &[u32]) -> zbus::Result<(u32, (u32, f64))>;
+
+        fn origin(&self) -> zbus::Result<Point>;
+
+        #[dbus_proxy(no_reply)]
+        fn notify(&self, kind: &str) -> zbus::Result<()>;
+
+        // Omitted, since the signature of `T` is unknown.
+        fn generic<T>(&self, value: &T) -> zbus::Result<()>
+        where
+            T: serde::Serialize + zbus::zvariant::Type;
+
+        #[dbus_proxy(signal)]
+        fn changed(&self, name: &str, value: zbus::zvariant::Value<'_>) -> zbus::Result<()>;
+
+        #[dbus_proxy(property)]
+        fn count(&self) -> zbus::Result<u32>;
+
+        #[dbus_proxy(property)]
+        fn set_count(&self, count: u32) -> zbus::Result<()>;
+
+        #[deprecated]
+        #[dbus_proxy(property(emits_changed_signal = "const"))]
+        fn version(&self) -> zbus::Result<String>;
+    }
+
+    assert_eq!(
+        IntrospectionProxy::introspection_xml(),
+        r#"<interface name="org.freedesktop.zbus_macros.Introspection">
+  <method name="Concat">
+    <annotation name="org.freedesktop.DBus.DocString" value="Concatenate two strings."/>
+    <arg name="first" type="s" direction="in"/>
+    <arg name="second" type="s" direction="in"/>
+    <arg type="s" direction="out"/>
+  </method>
+  <method name="Stats">
+    <arg name="values" type="au" direction="in"/>
+    <arg type="u" direction="out"/>
+    <arg type="(ud)" direction="out"/>
+  </method>
+  <method name="Origin">
+    <arg type="i" direction="out"/>
+    <arg type="i" direction="out"/>
+  </method>
+  <method name="Notify">
+    <arg name="kind" type="s" direction="in"/>
+    <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+  </method>
+  <signal name="Changed">
+    <arg name="name" type="s"/>
+    <arg name="value" type="v"/>
+  </signal>
+  <property name="Count" type="u" access="readwrite"/>
+  <property name="Version" type="s" access="read">
+    <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+    <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+  </property>
+</interface>
+"#
+    );
+    assert_eq!(
+        IntrospectionProxyBlocking::introspection_xml(),
+        IntrospectionProxy::introspection_xml()
+    );
+}
+
 #[test]
 fn test_derive_error() {
     #[derive(Debug, DBusError)]
@@ -124,6 +207,7 @@ fn test_interface() {
     #[derive(Serialize, Deserialize, Type, Value)]
     struct MyCustomPropertyType(u32);
 
+    /// A test interface.
     #[dbus_interface(name = "org.freedesktop.zbus.Test")]
     impl<T: 'static> Test<T>
     where
@@ -136,7 +220,11 @@ fn test_interface() {
 
         // Also tests that mut argument bindings work for regular methods
         #[allow(unused_assignments)]
-        fn str_u32(&self, mut val: &str) -> zbus::fdo::Result<u32> {
+        fn str_u32(
+            &self,
+            /// A number, as a string.
+            mut val: &str,
+        ) -> zbus::fdo::Result<u32> {
             let res = val
                 .parse()
                 .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid val: {e}")));
@@ -184,17 +272,26 @@ fn test_interface() {
 
         /// Emit a signal.
         #[dbus_interface(signal)]
-        async fn signal(ctxt: &SignalContext<'_>, arg: u8, other: &str) -> zbus::Result<()>;
+        async fn signal(
+            ctxt: &SignalContext<'_>,
+            arg: u8,
+            /// Some <other> "thing" & more.
+            other: &str,
+        ) -> zbus::Result<()>;
     }
 
     const EXPECTED_XML: &str = r#"<interface name="org.freedesktop.zbus.Test">
+  <annotation name="org.freedesktop.DBus.DocString" value="A test interface."/>
   <!--
    Testing `no_arg` documentation is reflected in XML.
    -->
   <method name="NoArg">
+    <annotation name="org.freedesktop.DBus.DocString" value="Testing `no_arg` documentation is reflected in XML."/>
   </method>
   <method name="StrU32">
-    <arg name="val" type="s" direction="in"/>
+    <arg name="val" type="s" direction="in">
+      <annotation name="org.freedesktop.DBus.DocString" value="A number, as a string."/>
+    </arg>
     <arg type="u" direction="out"/>
   </method>
   <method name="ManyOutput">
@@ -211,8 +308,11 @@ fn test_interface() {
    Emit a signal.
    -->
   <signal name="Signal">
+    <annotation name="org.freedesktop.DBus.DocString" value="Emit a signal."/>
     <arg name="arg" type="y"/>
-    <arg name="other" type="s"/>
+    <arg name="other" type="s">
+      <annotation name="org.freedesktop.DBus.DocString" value="Some &lt;other&gt; &quot;thing&quot; &amp; more."/>
+    </arg>
   </signal>
   <property name="MyCustomProperty" type="u" access="readwrite"/>
   <!--
@@ -220,7 +320,9 @@ fn test_interface() {
 
    And that too.
    -->
-  <property name="MyProp" type="q" access="readwrite"/>
+  <property name="MyProp" type="q" access="readwrite">
+    <annotation name="org.freedesktop.DBus.DocString" value="Testing my_prop documentation is reflected in XML.&#10;&#10;And that too."/>
+  </property>
 </interface>
 "#;
     let t = Test {
@@ -248,6 +350,77 @@ fn test_interface() {
     }
 }
 
+#[test]
+fn test_interface_arg_names_and_annotations() {
+    use serde::{Deserialize, Serialize};
+    use zbus::{zvariant::Type, Interface};
+
+    struct Test;
+
+    #[derive(Serialize, Deserialize, Type)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[dbus_interface(name = "org.freedesktop.zbus.ArgNames")]
+    impl Test {
+        #[dbus_interface(
+            out_args("x", "y"),
+            out_annotations("y:org.freedesktop.zbus.Unit=px"),
+            annotations("org.freedesktop.DBus.Method.NoReply=false")
+        )]
+        fn struct_output(
+            &self,
+            #[zbus(name = "type", annotations("org.freedesktop.zbus.Kind=raw"))] _kind: u8,
+            r#ref: u8,
+        ) -> Point {
+            Point {
+                x: 0,
+                y: r#ref.into(),
+            }
+        }
+
+        #[dbus_interface(
+            property,
+            annotations("org.freedesktop.DBus.Property.EmitsChangedSignal=invalidates")
+        )]
+        fn count(&self) -> u32 {
+            unimplemented!()
+        }
+
+        #[dbus_interface(signal)]
+        async fn signal(
+            ctxt: &SignalContext<'_>,
+            #[zbus(name = "Arg")] arg: u8,
+        ) -> zbus::Result<()>;
+    }
+
+    const EXPECTED_XML: &str = r#"<interface name="org.freedesktop.zbus.ArgNames">
+  <method name="StructOutput">
+    <arg name="type" type="y" direction="in">
+      <annotation name="org.freedesktop.zbus.Kind" value="raw"/>
+    </arg>
+    <arg name="ref" type="y" direction="in"/>
+    <arg name="x" type="i" direction="out"/>
+    <arg name="y" type="i" direction="out">
+      <annotation name="org.freedesktop.zbus.Unit" value="px"/>
+    </arg>
+    <annotation name="org.freedesktop.DBus.Method.NoReply" value="false"/>
+  </method>
+  <signal name="Signal">
+    <arg name="Arg" type="y"/>
+  </signal>
+  <property name="Count" type="u" access="read">
+    <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="invalidates"/>
+  </property>
+</interface>
+"#;
+    let mut xml = String::new();
+    Test.introspect_to_writer(&mut xml, 0);
+    assert_eq!(xml, EXPECTED_XML);
+}
+
 mod signal_from_message {
     use super::*;
     use std::sync::Arc;
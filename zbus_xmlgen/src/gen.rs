@@ -1,19 +1,28 @@
 use snakecase::ascii::to_snakecase;
-use std::fmt::{Display, Formatter};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter, Write},
+};
 
 use zbus::{
     names::BusName,
-    quick_xml::{Arg, ArgDirection, Interface},
+    quick_xml::{Annotation, Arg, ArgDirection, Interface},
 };
 use zvariant::{
     Basic, ObjectPath, Signature, ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_END_CHAR,
     DICT_ENTRY_SIG_START_CHAR, STRUCT_SIG_END_CHAR, STRUCT_SIG_START_CHAR, VARIANT_SIGNATURE_CHAR,
 };
 
+use crate::types::{is_type_name, NamedKind, NamedType, TypeMap};
+
+const QT_TYPE_NAME_ANNOTATION: &str = "org.qtproject.QtDBus.QtTypeName";
+const GDBUS_C_NAME_ANNOTATION: &str = "org.gtk.GDBus.C.Name";
+
 pub struct GenTrait<'i> {
     pub interface: &'i Interface<'i>,
     pub service: Option<&'i BusName<'i>>,
     pub path: Option<&'i ObjectPath<'i>>,
+    pub types: &'i TypeMap,
 }
 
 impl<'i> Display for GenTrait<'i> {
@@ -38,7 +47,10 @@ impl<'i> Display for GenTrait<'i> {
         let mut methods = iface.methods().to_vec();
         methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for m in &methods {
-            let (inputs, output) = inputs_output_from_args(m.args());
+            let path = format!("{}.{}", iface.name(), m.name());
+            let named = named_arg_types(self.types, &path, m.annotations(), m.args(), false);
+            let reply = self.types.member(&path);
+            let (inputs, output) = inputs_output_from_args(m.args(), &named, reply, self.types)?;
             let name = to_identifier(&to_snakecase(m.name().as_str()));
             writeln!(f)?;
             writeln!(f, "    /// {} method", m.name())?;
@@ -51,7 +63,10 @@ impl<'i> Display for GenTrait<'i> {
         let mut signals = iface.signals().to_vec();
         signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for signal in &signals {
-            let args = parse_signal_args(signal.args());
+            let path = format!("{}.{}", iface.name(), signal.name());
+            let named =
+                named_arg_types(self.types, &path, signal.annotations(), signal.args(), true);
+            let args = parse_signal_args(signal.args(), &named, self.types)?;
             let name = to_identifier(&to_snakecase(signal.name().as_str()));
             writeln!(f)?;
             writeln!(f, "    /// {} signal", signal.name())?;
@@ -67,22 +82,33 @@ impl<'i> Display for GenTrait<'i> {
         props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for p in props {
             let name = to_identifier(&to_snakecase(p.name().as_str()));
+            let named = self
+                .types
+                .member(&format!("{}.{}", iface.name(), p.name()))
+                .cloned()
+                .or_else(|| annotated_type(p.annotations(), self.types));
+
+            // Both the getter and the setter need the attribute.
+            let attr = if pascal_case(&name) != p.name().as_str() {
+                format!("    #[dbus_proxy(property, name = \"{}\")]", p.name())
+            } else {
+                String::from("    #[dbus_proxy(property)]")
+            };
 
             writeln!(f)?;
             writeln!(f, "    /// {} property", p.name())?;
-            if pascal_case(&name) != p.name().as_str() {
-                writeln!(f, "    #[dbus_proxy(property, name = \"{}\")]", p.name())?;
-            } else {
-                writeln!(f, "    #[dbus_proxy(property)]")?;
-            }
 
             if p.access().read() {
-                let output = to_rust_type(p.ty(), false, false);
+                let output = to_rust_type(p.ty(), false, false, named.as_ref(), self.types)?;
+                writeln!(f, "{attr}")?;
                 writeln!(f, "    fn {name}(&self) -> zbus::Result<{output}>;",)?;
             }
 
             if p.access().write() {
-                let input = to_rust_type(p.ty(), true, true);
+                // Named types need to be passed by value, to be converted into a `Value`.
+                let as_ref = named.is_none() && self.types.signature(p.ty()).is_none();
+                let input = to_rust_type(p.ty(), true, as_ref, named.as_ref(), self.types)?;
+                writeln!(f, "{attr}")?;
                 writeln!(
                     f,
                     "    fn set_{name}(&self, value: {input}) -> zbus::Result<()>;",
@@ -93,19 +119,152 @@ impl<'i> Display for GenTrait<'i> {
     }
 }
 
-fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
+pub struct GenInterface<'i> {
+    pub interface: &'i Interface<'i>,
+    pub types: &'i TypeMap,
+}
+
+impl<'i> Display for GenInterface<'i> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let iface = self.interface;
+        let idx = iface.name().rfind('.').unwrap() + 1;
+        let name = &iface.name()[idx..];
+
+        writeln!(f, "pub struct {name};")?;
+        writeln!(f)?;
+        writeln!(f, "#[dbus_interface(name = \"{}\")]", iface.name())?;
+        writeln!(f, "impl {name} {{")?;
+
+        let mut methods = iface.methods().to_vec();
+        methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for m in &methods {
+            let path = format!("{}.{}", iface.name(), m.name());
+            let named = named_arg_types(self.types, &path, m.annotations(), m.args(), false);
+            let (inputs, output, out_args) =
+                interface_inputs_output_from_args(m.args(), &named, self.types)?;
+            let name = to_identifier(&to_snakecase(m.name().as_str()));
+            let mut attrs = vec![];
+            if pascal_case(&name) != m.name().as_str() {
+                attrs.push(format!("name = \"{}\"", m.name()));
+            }
+            if let Some(out_args) = out_args {
+                attrs.push(format!("out_args({out_args})"));
+            }
+            writeln!(f)?;
+            writeln!(f, "    /// {} method", m.name())?;
+            if !attrs.is_empty() {
+                writeln!(f, "    #[dbus_interface({})]", attrs.join(", "))?;
+            }
+            writeln!(f, "    async fn {name}({inputs}){output} {{")?;
+            writeln!(f, "        todo!()")?;
+            writeln!(f, "    }}")?;
+        }
+
+        let mut signals = iface.signals().to_vec();
+        signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for signal in &signals {
+            let path = format!("{}.{}", iface.name(), signal.name());
+            let named =
+                named_arg_types(self.types, &path, signal.annotations(), signal.args(), true);
+            let args = parse_signal_args(signal.args(), &named, self.types)?;
+            let args = args.replacen("&self", "signal_ctxt: &zbus::SignalContext<'_>", 1);
+            let name = to_identifier(&to_snakecase(signal.name().as_str()));
+            writeln!(f)?;
+            writeln!(f, "    /// {} signal", signal.name())?;
+            if pascal_case(&name) != signal.name().as_str() {
+                writeln!(
+                    f,
+                    "    #[dbus_interface(signal, name = \"{}\")]",
+                    signal.name()
+                )?;
+            } else {
+                writeln!(f, "    #[dbus_interface(signal)]")?;
+            }
+            writeln!(f, "    async fn {name}({args}) -> zbus::Result<()>;",)?;
+        }
+
+        let mut props = iface.properties().to_vec();
+        props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for p in props {
+            let name = to_identifier(&to_snakecase(p.name().as_str()));
+            let named = self
+                .types
+                .member(&format!("{}.{}", iface.name(), p.name()))
+                .cloned()
+                .or_else(|| annotated_type(p.annotations(), self.types));
+            let attr = if pascal_case(&name) != p.name().as_str() {
+                format!("    #[dbus_interface(property, name = \"{}\")]", p.name())
+            } else {
+                "    #[dbus_interface(property)]".to_string()
+            };
+            let ty = to_rust_type(p.ty(), false, false, named.as_ref(), self.types)?;
+
+            writeln!(f)?;
+            writeln!(f, "    /// {} property", p.name())?;
+            if p.access().read() {
+                writeln!(f, "{attr}")?;
+                writeln!(
+                    f,
+                    "    async fn {name}(&self) -> zbus::fdo::Result<{ty}> {{"
+                )?;
+                writeln!(f, "        todo!()")?;
+                writeln!(f, "    }}")?;
+            }
+
+            if p.access().write() {
+                writeln!(f, "{attr}")?;
+                writeln!(
+                    f,
+                    "    async fn set_{name}(&mut self, value: {ty}) -> zbus::fdo::Result<()> {{",
+                )?;
+                writeln!(f, "        todo!()")?;
+                writeln!(f, "    }}")?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The definitions of the named types used by the generated proxies or skeletons.
+///
+/// This must be formatted after them, since the types are only known once they're generated.
+pub struct GenTypes<'i> {
+    pub types: &'i TypeMap,
+}
+
+impl<'i> Display for GenTypes<'i> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (signature, definition) in self.types.used.borrow().values() {
+            writeln!(f)?;
+            writeln!(f, "/// `{signature}`")?;
+            writeln!(f, "{definition}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Returns the inputs, the output and, if all out args are named, their names for `out_args`.
+fn interface_inputs_output_from_args(
+    args: &[Arg],
+    named: &[Option<NamedType>],
+    types: &TypeMap,
+) -> Result<(String, String, Option<String>), fmt::Error> {
     let mut inputs = vec!["&self".to_string()];
     let mut output = vec![];
+    let mut out_names = vec![];
+    let mut out_signatures = vec![];
     let mut n = 0;
     let mut gen_name = || {
         n += 1;
         format!("arg_{n}")
     };
 
-    for a in args {
+    for (a, named) in args.iter().zip(named) {
+        // Unlike proxies, interface methods receive and return owned values.
+        let ty = to_rust_type(a.ty(), false, false, named.as_ref(), types)?;
         match a.direction() {
             None | Some(ArgDirection::In) => {
-                let ty = to_rust_type(a.ty(), true, true);
                 let arg = if let Some(name) = a.name() {
                     to_identifier(name)
                 } else {
@@ -114,31 +273,121 @@ fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
                 inputs.push(format!("{arg}: {ty}"));
             }
             Some(ArgDirection::Out) => {
-                let ty = to_rust_type(a.ty(), false, false);
                 output.push(ty);
+                out_names.push(a.name());
+                out_signatures.push(a.ty());
             }
         }
     }
 
+    // A single structure (named or not) needs to be wrapped in a tuple, or it'd be taken for
+    // multiple out args.
+    let wrapped = out_signatures.len() == 1 && out_signatures[0].starts_with('(');
     let output = match output.len() {
         0 => "()".to_string(),
+        1 if wrapped => format!("({},)", output[0]),
         1 => output[0].to_string(),
         _ => format!("({})", output.join(", ")),
     };
+    let out_args = if (out_names.len() > 1 || wrapped) && out_names.iter().all(Option::is_some) {
+        let names: Vec<_> = out_names
+            .iter()
+            .map(|name| format!("\"{}\"", name.unwrap()))
+            .collect();
+
+        Some(names.join(", "))
+    } else {
+        None
+    };
 
-    (inputs.join(", "), format!(" -> zbus::Result<{output}>"))
+    Ok((
+        inputs.join(", "),
+        format!(" -> zbus::fdo::Result<{output}>"),
+        out_args,
+    ))
 }
 
-fn parse_signal_args(args: &[Arg]) -> String {
+fn inputs_output_from_args(
+    args: &[Arg],
+    named: &[Option<NamedType>],
+    reply: Option<&NamedType>,
+    types: &TypeMap,
+) -> Result<(String, String), fmt::Error> {
     let mut inputs = vec!["&self".to_string()];
+    let mut output = vec![];
+    let mut out_args = vec![];
     let mut n = 0;
     let mut gen_name = || {
         n += 1;
         format!("arg_{n}")
     };
 
-    for a in args {
-        let ty = to_rust_type(a.ty(), true, false);
+    for (a, named) in args.iter().zip(named) {
+        match a.direction() {
+            None | Some(ArgDirection::In) => {
+                let ty = to_rust_type(a.ty(), true, true, named.as_ref(), types)?;
+                let arg = if let Some(name) = a.name() {
+                    to_identifier(name)
+                } else {
+                    gen_name()
+                };
+                inputs.push(format!("{arg}: {ty}"));
+            }
+            Some(ArgDirection::Out) => {
+                let ty = to_rust_type(a.ty(), false, false, named.as_ref(), types)?;
+                output.push(ty);
+                out_args.push(a);
+            }
+        }
+    }
+
+    let output = match (output.len(), reply) {
+        (0, _) => "()".to_string(),
+        (1, _) => output[0].to_string(),
+        // Multiple out args can be received as a named structure, with a field per arg.
+        (_, Some(reply)) => {
+            let signature: String = out_args.iter().map(|a| a.ty()).collect();
+            let signature = format!("({signature})");
+            let reply = match &reply.kind {
+                NamedKind::Struct(fields) if fields.is_empty() => NamedType {
+                    name: reply.name.clone(),
+                    kind: NamedKind::Struct(
+                        out_args
+                            .iter()
+                            .enumerate()
+                            .map(|(i, a)| {
+                                a.name()
+                                    .map(Into::into)
+                                    .unwrap_or_else(|| format!("field_{}", i + 1))
+                            })
+                            .collect(),
+                    ),
+                },
+                _ => reply.clone(),
+            };
+
+            to_rust_type(&signature, false, false, Some(&reply), types)?
+        }
+        _ => format!("({})", output.join(", ")),
+    };
+
+    Ok((inputs.join(", "), format!(" -> zbus::Result<{output}>")))
+}
+
+fn parse_signal_args(
+    args: &[Arg],
+    named: &[Option<NamedType>],
+    types: &TypeMap,
+) -> Result<String, fmt::Error> {
+    let mut inputs = vec!["&self".to_string()];
+    let mut n = 0;
+    let mut gen_name = || {
+        n += 1;
+        format!("arg_{n}")
+    };
+
+    for (a, named) in args.iter().zip(named) {
+        let ty = to_rust_type(a.ty(), true, false, named.as_ref(), types)?;
         let arg = if let Some(name) = a.name() {
             to_identifier(name)
         } else {
@@ -147,106 +396,385 @@ fn parse_signal_args(args: &[Arg]) -> String {
         inputs.push(format!("{arg}: {ty}"));
     }
 
-    inputs.join(", ")
+    Ok(inputs.join(", "))
 }
 
-fn to_rust_type(ty: &str, input: bool, as_ref: bool) -> String {
-    // can't haz recursive closure, yet
-    fn iter_to_rust_type(
-        it: &mut std::iter::Peekable<std::slice::Iter<'_, u8>>,
-        input: bool,
-        as_ref: bool,
-    ) -> String {
-        let c = it.next().unwrap();
-        match *c as char {
-            u8::SIGNATURE_CHAR => "u8".into(),
-            bool::SIGNATURE_CHAR => "bool".into(),
-            i16::SIGNATURE_CHAR => "i16".into(),
-            u16::SIGNATURE_CHAR => "u16".into(),
-            i32::SIGNATURE_CHAR => "i32".into(),
-            u32::SIGNATURE_CHAR => "u32".into(),
-            i64::SIGNATURE_CHAR => "i64".into(),
-            u64::SIGNATURE_CHAR => "u64".into(),
-            f64::SIGNATURE_CHAR => "f64".into(),
-            // xmlgen accepts 'h' on Windows, only for code generation
-            'h' => (if input {
-                "zbus::zvariant::Fd"
+// Returns the named type of each arg, if any.
+//
+// Types are looked up, in order, in the type map by arg path, in the arg annotations, in the
+// `QtTypeName.{In,Out}N` annotations of the member and, for the sole out arg of a method, in the
+// type map by member path.
+fn named_arg_types(
+    types: &TypeMap,
+    path: &str,
+    annotations: &[Annotation],
+    args: &[Arg],
+    signal: bool,
+) -> Vec<Option<NamedType>> {
+    let outs = args
+        .iter()
+        .filter(|a| a.direction() == Some(ArgDirection::Out))
+        .count();
+    let (mut n_in, mut n_out) = (0, 0);
+
+    args.iter()
+        .map(|a| {
+            let out = a.direction() == Some(ArgDirection::Out);
+            // Qt has historically used `Out` annotations for signal args, and `In` since.
+            let suffixes = if signal {
+                n_in += 1;
+                vec![format!("Out{}", n_in - 1), format!("In{}", n_in - 1)]
+            } else if out {
+                n_out += 1;
+                vec![format!("Out{}", n_out - 1)]
             } else {
-                "zbus::zvariant::OwnedFd"
-            })
-            .into(),
-            <&str>::SIGNATURE_CHAR => (if input || as_ref { "&str" } else { "String" }).into(),
-            ObjectPath::SIGNATURE_CHAR => (if input {
-                if as_ref {
-                    "&zbus::zvariant::ObjectPath<'_>"
-                } else {
-                    "zbus::zvariant::ObjectPath<'_>"
-                }
+                n_in += 1;
+                vec![format!("In{}", n_in - 1)]
+            };
+
+            a.name()
+                .and_then(|name| types.member(&format!("{path}.{name}")).cloned())
+                .or_else(|| annotated_type(a.annotations(), types))
+                .or_else(|| {
+                    suffixes.iter().find_map(|suffix| {
+                        let name = format!("{QT_TYPE_NAME_ANNOTATION}.{suffix}");
+                        annotations
+                            .iter()
+                            .find(|a| a.name() == name)
+                            .and_then(|a| type_from_name(a.value(), types))
+                    })
+                })
+                .or_else(|| {
+                    if !signal && out && outs == 1 {
+                        types.member(path).cloned()
+                    } else {
+                        None
+                    }
+                })
+        })
+        .collect()
+}
+
+// Returns the type named by the `QtTypeName` or `C.Name` annotation, if any.
+fn annotated_type(annotations: &[Annotation], types: &TypeMap) -> Option<NamedType> {
+    [QT_TYPE_NAME_ANNOTATION, GDBUS_C_NAME_ANNOTATION]
+        .iter()
+        .find_map(|name| annotations.iter().find(|a| a.name() == *name))
+        .and_then(|a| type_from_name(a.value(), types))
+}
+
+// Turns a (Qt or C) type name into a named type, with the fields given by the type map if any.
+fn type_from_name(name: &str, types: &TypeMap) -> Option<NamedType> {
+    // Use the innermost type of templates, e.g `Device` for `QList<Device>`.
+    let name = name.rsplit('<').next().unwrap();
+    let name = name.split(&[',', '>'][..]).next().unwrap().trim();
+    let name = pascal_case(name.rsplit("::").next().unwrap());
+    if !is_type_name(&name) {
+        return None;
+    }
+
+    Some(
+        types
+            .by_name(&name)
+            .cloned()
+            .unwrap_or_else(|| NamedType::new(name)),
+    )
+}
+
+// Fails if `ty` isn't a single complete type (see `is_complete_type`).
+fn to_rust_type(
+    ty: &str,
+    input: bool,
+    as_ref: bool,
+    named: Option<&NamedType>,
+    types: &TypeMap,
+) -> Result<String, fmt::Error> {
+    if !is_complete_type(ty) {
+        return Err(fmt::Error);
+    }
+
+    let reference = if as_ref { "&" } else { "" };
+    let c = ty.as_bytes()[0];
+    let ty = match c as char {
+        u8::SIGNATURE_CHAR => "u8".into(),
+        bool::SIGNATURE_CHAR => "bool".into(),
+        i16::SIGNATURE_CHAR => "i16".into(),
+        u16::SIGNATURE_CHAR => "u16".into(),
+        i32::SIGNATURE_CHAR => "i32".into(),
+        u32::SIGNATURE_CHAR => "u32".into(),
+        i64::SIGNATURE_CHAR => "i64".into(),
+        u64::SIGNATURE_CHAR => "u64".into(),
+        f64::SIGNATURE_CHAR => "f64".into(),
+        // xmlgen accepts 'h' on Windows, only for code generation
+        'h' => (if input {
+            "zbus::zvariant::Fd"
+        } else {
+            "zbus::zvariant::OwnedFd"
+        })
+        .into(),
+        <&str>::SIGNATURE_CHAR => (if input || as_ref { "&str" } else { "String" }).into(),
+        ObjectPath::SIGNATURE_CHAR => (if input {
+            if as_ref {
+                "&zbus::zvariant::ObjectPath<'_>"
             } else {
-                "zbus::zvariant::OwnedObjectPath"
-            })
-            .into(),
-            Signature::SIGNATURE_CHAR => (if input {
-                if as_ref {
-                    "&zbus::zvariant::Signature<'_>"
-                } else {
-                    "zbus::zvariant::Signature<'_>"
-                }
+                "zbus::zvariant::ObjectPath<'_>"
+            }
+        } else {
+            "zbus::zvariant::OwnedObjectPath"
+        })
+        .into(),
+        Signature::SIGNATURE_CHAR => (if input {
+            if as_ref {
+                "&zbus::zvariant::Signature<'_>"
             } else {
-                "zbus::zvariant::OwnedSignature"
-            })
-            .into(),
-            VARIANT_SIGNATURE_CHAR => (if input {
-                if as_ref {
-                    "&zbus::zvariant::Value<'_>"
-                } else {
-                    "zbus::zvariant::Value<'_>"
-                }
+                "zbus::zvariant::Signature<'_>"
+            }
+        } else {
+            "zbus::zvariant::OwnedSignature"
+        })
+        .into(),
+        VARIANT_SIGNATURE_CHAR => (if input {
+            if as_ref {
+                "&zbus::zvariant::Value<'_>"
             } else {
-                "zbus::zvariant::OwnedValue"
-            })
-            .into(),
-            ARRAY_SIGNATURE_CHAR => {
-                let c = it.peek().unwrap();
-                match **c as char {
-                    '{' => format!(
-                        "std::collections::HashMap<{}>",
-                        iter_to_rust_type(it, input, false)
-                    ),
-                    _ => {
-                        let ty = iter_to_rust_type(it, input, false);
-                        if input {
-                            format!("&[{ty}]")
-                        } else {
-                            format!("{}Vec<{}>", if as_ref { "&" } else { "" }, ty)
-                        }
-                    }
-                }
+                "zbus::zvariant::Value<'_>"
             }
-            c @ STRUCT_SIG_START_CHAR | c @ DICT_ENTRY_SIG_START_CHAR => {
-                let dict = c == '{';
-                let mut vec = vec![];
-                loop {
-                    let c = it.peek().unwrap();
-                    match **c as char {
-                        STRUCT_SIG_END_CHAR | DICT_ENTRY_SIG_END_CHAR => break,
-                        _ => vec.push(iter_to_rust_type(it, input, false)),
-                    }
-                }
-                if dict {
-                    vec.join(", ")
-                } else if vec.len() > 1 {
-                    format!("{}({})", if as_ref { "&" } else { "" }, vec.join(", "))
-                } else {
-                    vec[0].to_string()
-                }
+        } else {
+            "zbus::zvariant::OwnedValue"
+        })
+        .into(),
+        ARRAY_SIGNATURE_CHAR if ty.as_bytes()[1] == DICT_ENTRY_SIG_START_CHAR as u8 => {
+            let named_dict = named
+                .filter(|t| matches!(t.kind, NamedKind::Dict(_)) && ty == "a{sv}")
+                .or_else(|| types.signature(ty));
+            if let Some(named) = named_dict {
+                return Ok(format!("{}{}", reference, register_type(named, ty, types)?));
+            }
+
+            let entry = split_complete_types(&ty[2..ty.len() - 1]);
+            format!(
+                "std::collections::HashMap<{}, {}>",
+                to_rust_type(entry[0], input, false, None, types)?,
+                to_rust_type(entry[1], input, false, named, types)?,
+            )
+        }
+        ARRAY_SIGNATURE_CHAR => {
+            let ty = to_rust_type(&ty[1..], input, false, named, types)?;
+            if input {
+                format!("&[{ty}]")
+            } else {
+                format!("{reference}Vec<{ty}>")
+            }
+        }
+        STRUCT_SIG_START_CHAR => {
+            let named_struct = named
+                .filter(|t| matches!(t.kind, NamedKind::Struct(_)))
+                .or_else(|| types.signature(ty));
+            if let Some(named) = named_struct {
+                return Ok(format!("{}{}", reference, register_type(named, ty, types)?));
+            }
+
+            let fields = split_complete_types(&ty[1..ty.len() - 1])
+                .into_iter()
+                .map(|field| to_rust_type(field, input, false, None, types))
+                .collect::<Result<Vec<_>, _>>()?;
+            match fields.as_slice() {
+                [field] => field.to_string(),
+                _ => format!("{}({})", reference, fields.join(", ")),
+            }
+        }
+        _ => return Err(fmt::Error),
+    };
+
+    Ok(ty)
+}
+
+// Whether `signature` is a valid single complete type, which is what the generators expect.
+pub(crate) fn is_complete_type(signature: &str) -> bool {
+    Signature::try_from(signature).is_ok() && split_complete_types(signature).len() == 1
+}
+
+// Splits a signature into its complete types.
+pub(crate) fn split_complete_types(signature: &str) -> Vec<&str> {
+    let bytes = signature.as_bytes();
+    let mut types = vec![];
+    let mut start = 0;
+    while start < bytes.len() {
+        let mut end = start;
+        while bytes[end] == ARRAY_SIGNATURE_CHAR as u8 {
+            end += 1;
+        }
+        let mut depth = 0;
+        loop {
+            match bytes[end] as char {
+                STRUCT_SIG_START_CHAR | DICT_ENTRY_SIG_START_CHAR => depth += 1,
+                STRUCT_SIG_END_CHAR | DICT_ENTRY_SIG_END_CHAR => depth -= 1,
+                _ => (),
+            }
+            end += 1;
+            if depth == 0 {
+                break;
             }
-            _ => unimplemented!(),
         }
+        types.push(&signature[start..end]);
+        start = end;
     }
 
-    let mut it = ty.as_bytes().iter().peekable();
-    iter_to_rust_type(&mut it, input, as_ref)
+    types
+}
+
+// Records the definition of `named` for `signature`, returning the name to use.
+//
+// A numeric suffix is added if a type of the same name was already defined for another signature.
+fn register_type(
+    named: &NamedType,
+    signature: &str,
+    types: &TypeMap,
+) -> Result<String, fmt::Error> {
+    let mut name = named.name.clone();
+    let mut n = 1;
+    loop {
+        match types.used.borrow().get(&name) {
+            Some((s, _)) if s == signature => return Ok(name),
+            Some(_) => {
+                n += 1;
+                name = format!("{}{}", named.name, n);
+            }
+            None => break,
+        }
+    }
+
+    let mut definition = String::new();
+    write_type_definition(&mut definition, &name, &named.kind, signature, types)?;
+    types
+        .used
+        .borrow_mut()
+        .insert(name.clone(), (signature.to_string(), definition));
+
+    Ok(name)
+}
+
+fn write_type_definition(
+    f: &mut String,
+    name: &str,
+    kind: &NamedKind,
+    signature: &str,
+    types: &TypeMap,
+) -> std::fmt::Result {
+    let keys = match kind {
+        NamedKind::Struct(field_names) => {
+            writeln!(
+                f,
+                "#[derive(Debug, serde::Serialize, serde::Deserialize, zbus::zvariant::Type, \
+                 zbus::zvariant::Value, zbus::zvariant::OwnedValue)]"
+            )?;
+            writeln!(f, "pub struct {name} {{")?;
+            let fields = split_complete_types(&signature[1..signature.len() - 1]);
+            for (i, field) in fields.into_iter().enumerate() {
+                let field_name = match field_names.get(i) {
+                    Some(field_name) => to_identifier(&to_snakecase(field_name)),
+                    None => format!("field_{}", i + 1),
+                };
+                let ty = to_rust_type(field, false, false, None, types)?;
+                writeln!(f, "    pub {field_name}: {ty},")?;
+            }
+            return write!(f, "}}");
+        }
+        NamedKind::Dict(keys) => keys,
+    };
+
+    let fields = keys
+        .iter()
+        .map(|(key, signature)| {
+            let ty = to_rust_type(signature, false, false, None, types)?;
+
+            Ok((key, to_identifier(&to_snakecase(key)), ty))
+        })
+        .collect::<Result<Vec<_>, fmt::Error>>()?;
+
+    writeln!(
+        f,
+        "#[derive(Debug, Default, zbus::zvariant::SerializeDict, \
+         zbus::zvariant::DeserializeDict, zbus::zvariant::Type)]"
+    )?;
+    writeln!(f, "#[zvariant(signature = \"dict\")]")?;
+    writeln!(f, "pub struct {name} {{")?;
+    for (key, field_name, ty) in &fields {
+        if field_name != *key {
+            writeln!(f, "    #[zvariant(rename = \"{key}\")]")?;
+        }
+        writeln!(f, "    pub {field_name}: Option<{ty}>,")?;
+    }
+    writeln!(f, "}}")?;
+
+    // The `Value` derive doesn't support optional entries, so the conversions needed for
+    // properties and variants are generated too.
+    writeln!(f)?;
+    writeln!(
+        f,
+        "impl std::convert::TryFrom<zbus::zvariant::OwnedValue> for {name} {{"
+    )?;
+    writeln!(f, "    type Error = zbus::zvariant::Error;")?;
+    writeln!(f)?;
+    writeln!(
+        f,
+        "    fn try_from(value: zbus::zvariant::OwnedValue) -> zbus::zvariant::Result<Self> {{"
+    )?;
+    writeln!(
+        f,
+        "        Self::try_from(zbus::zvariant::Value::from(value))"
+    )?;
+    writeln!(f, "    }}")?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+    writeln!(
+        f,
+        "impl<'a> std::convert::TryFrom<zbus::zvariant::Value<'a>> for {name} {{"
+    )?;
+    writeln!(f, "    type Error = zbus::zvariant::Error;")?;
+    writeln!(f)?;
+    writeln!(
+        f,
+        "    fn try_from(value: zbus::zvariant::Value<'a>) -> zbus::zvariant::Result<Self> {{"
+    )?;
+    writeln!(
+        f,
+        "        let mut fields = \
+         <std::collections::HashMap<String, zbus::zvariant::Value<'a>>>::try_from(value)?;"
+    )?;
+    writeln!(f)?;
+    writeln!(f, "        Ok(Self {{")?;
+    for (key, field_name, _) in &fields {
+        writeln!(f, "            {field_name}: fields")?;
+        writeln!(f, "                .remove(\"{key}\")")?;
+        writeln!(
+            f,
+            "                .map(|v| v.downcast().ok_or(zbus::zvariant::Error::IncorrectType))"
+        )?;
+        writeln!(f, "                .transpose()?,")?;
+    }
+    writeln!(f, "        }})")?;
+    writeln!(f, "    }}")?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+    writeln!(f, "impl<'a> From<{name}> for zbus::zvariant::Value<'a> {{")?;
+    writeln!(f, "    fn from(s: {name}) -> Self {{")?;
+    writeln!(
+        f,
+        "        let mut fields = std::collections::HashMap::<&str, zbus::zvariant::Value<'a>>::new();"
+    )?;
+    for (key, field_name, _) in &fields {
+        writeln!(f, "        if let Some(v) = s.{field_name} {{")?;
+        writeln!(
+            f,
+            "            fields.insert(\"{key}\", zbus::zvariant::Value::new(v));"
+        )?;
+        writeln!(f, "        }}")?;
+    }
+    writeln!(f)?;
+    writeln!(f, "        fields.into()")?;
+    writeln!(f, "    }}")?;
+    write!(f, "}}")
 }
 
 static KWORDS: &[&str] = &[
@@ -257,7 +785,7 @@ static KWORDS: &[&str] = &[
     "union", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
 ];
 
-fn to_identifier(id: &str) -> String {
+pub(crate) fn to_identifier(id: &str) -> String {
     if KWORDS.contains(&id) {
         format!("{id}_")
     } else {
@@ -286,7 +814,8 @@ pub fn pascal_case(s: &str) -> String {
 mod tests {
     use std::{error::Error, result::Result};
 
-    use super::GenTrait;
+    use super::{GenInterface, GenTrait, GenTypes};
+    use crate::types::TypeMap;
     use zbus::quick_xml::Node;
 
     static EXAMPLE: &str = r##"
@@ -331,9 +860,117 @@ mod tests {
                 interface: &node.interfaces()[0],
                 path: None,
                 service: None,
+                types: &TypeMap::default(),
+            }
+        );
+        println!("{t}");
+        // The setter needs the attribute too, or it would be a method call.
+        assert!(t.contains(
+            "#[dbus_proxy(property)]\n    fn bar(&self) -> zbus::Result<u8>;\n    \
+             #[dbus_proxy(property)]\n    fn set_bar(&self, value: u8) -> zbus::Result<()>;"
+        ));
+        Ok(())
+    }
+    #[test]
+    fn gen_interface() -> Result<(), Box<dyn Error>> {
+        let node = Node::from_reader(EXAMPLE.as_bytes())?;
+        let t = format!(
+            "{}",
+            GenInterface {
+                interface: &node.interfaces()[0],
+                types: &TypeMap::default(),
             }
         );
         println!("{t}");
+        assert!(t.contains("#[dbus_interface(name = \"com.example.SampleInterface0\")]"));
+        assert!(t.contains("#[dbus_interface(out_args(\"bar\", \"baz\"))]"));
+        assert!(t.contains(
+            "async fn bazify(&self, bar: (i32, i32, u32)) -> zbus::fdo::Result<zbus::zvariant::OwnedValue>"
+        ));
+        assert!(t.contains(
+            "async fn changed(signal_ctxt: &zbus::SignalContext<'_>, new_value: bool) -> zbus::Result<()>;"
+        ));
+        assert!(t.contains("async fn set_bar(&mut self, value: u8) -> zbus::fdo::Result<()>"));
+        Ok(())
+    }
+
+    static NAMED_TYPES: &str = r##"
+<node>
+  <interface name="org.example.Manager">
+    <method name="GetDevices">
+      <arg name="devices" type="a(sou)" direction="out"/>
+      <annotation name="org.qtproject.QtDBus.QtTypeName.Out0" value="QList&lt;Device&gt;"/>
+    </method>
+    <method name="GetDevice">
+      <arg name="device" type="(sou)" direction="out"/>
+    </method>
+    <method name="GetStatus">
+      <arg name="state" type="u" direction="out"/>
+      <arg name="message" type="s" direction="out"/>
+    </method>
+    <method name="Frobate">
+      <arg name="point" type="(ii)" direction="in">
+        <annotation name="org.gtk.GDBus.C.Name" value="point"/>
+      </arg>
+    </method>
+    <signal name="Added">
+      <arg name="device" type="(sou)"/>
+    </signal>
+    <property name="Settings" type="a{sv}" access="readwrite"/>
+  </interface>
+</node>
+"##;
+
+    #[test]
+    fn gen_named_types() -> Result<(), Box<dyn Error>> {
+        let node = Node::from_reader(NAMED_TYPES.as_bytes())?;
+        let types = TypeMap::parse(
+            r#"
+            (sou) = Device(name, path, flags)
+            org.example.Manager.GetStatus = Status
+            org.example.Manager.Settings = Settings{Brightness: u, display-name: s}
+            "#,
+        )?;
+        let t = GenTrait {
+            interface: &node.interfaces()[0],
+            path: None,
+            service: None,
+            types: &types,
+        }
+        .to_string();
+        let defs = GenTypes { types: &types }.to_string();
+        println!("{t}{defs}");
+        assert!(t.contains("fn get_devices(&self) -> zbus::Result<Vec<Device>>;"));
+        assert!(t.contains("fn get_status(&self) -> zbus::Result<Status>;"));
+        assert!(t.contains("fn frobate(&self, point: &Point) -> zbus::Result<()>;"));
+        assert!(t.contains("fn added(&self, device: Device) -> zbus::Result<()>;"));
+        assert!(t.contains("fn settings(&self) -> zbus::Result<Settings>;"));
+        assert!(t.contains("fn set_settings(&self, value: Settings) -> zbus::Result<()>;"));
+        assert!(defs.contains(
+            "pub struct Device {\n    pub name: String,\n    pub path: zbus::zvariant::OwnedObjectPath,\n    pub flags: u32,\n}"
+        ));
+        assert!(
+            defs.contains("pub struct Point {\n    pub field_1: i32,\n    pub field_2: i32,\n}")
+        );
+        assert!(
+            defs.contains("pub struct Status {\n    pub state: u32,\n    pub message: String,\n}")
+        );
+        assert!(defs.contains(
+            "#[zvariant(rename = \"display-name\")]\n    pub display_name: Option<String>,"
+        ));
+
+        let i = GenInterface {
+            interface: &node.interfaces()[0],
+            types: &types,
+        }
+        .to_string();
+        println!("{i}");
+        assert!(i.contains("async fn get_devices(&self) -> zbus::fdo::Result<Vec<Device>>"));
+        // A single named structure is still wrapped, to tell it from multiple out args.
+        assert!(i.contains("#[dbus_interface(out_args(\"device\"))]"));
+        assert!(i.contains("async fn get_device(&self) -> zbus::fdo::Result<(Device,)>"));
+        // Multiple out args are kept as a tuple, since a structure is a single out arg.
+        assert!(i.contains("async fn get_status(&self) -> zbus::fdo::Result<(u32, String)>"));
         Ok(())
     }
 }
@@ -0,0 +1,848 @@
+#![deny(rust_2018_idioms)]
+#![doc(
+    html_logo_url = "https://storage.googleapis.com/fdo-gitlab-uploads/project/avatar/3213/zbus-logomark.png"
+)]
+
+//! Generate [zbus]-based Rust code from D-Bus XML interface descriptions.
+//!
+//! This is the library behind the `zbus-xmlgen` tool, which can also be used from a build script
+//! to generate proxies (or interface skeletons) at build time:
+//!
+//! ```no_run
+//! // build.rs
+//! use zbus_xmlgen::Generator;
+//!
+//! fn main() -> zbus_xmlgen::Result<()> {
+//!     Generator::new().build("org.example.Manager.xml", "manager.rs")?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The generated code can then be included with `include!(concat!(env!("OUT_DIR"),
+//! "/manager.rs"));`. [`Generator::generate`] gives more control over the input and output.
+//!
+//! Alternatively, `zbus_xmlgen_macros::proxy_from_xml!` generates proxies from a XML file at
+//! compile time, without a build script.
+//!
+//! [zbus]: https://crates.io/crates/zbus
+
+use std::{
+    convert::TryFrom,
+    env, error, fmt, fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use snakecase::ascii::to_snakecase;
+use zbus::{
+    blocking::{fdo::IntrospectableProxy, Connection},
+    names::BusName,
+    quick_xml::{Interface, Node},
+};
+use zvariant::{ObjectPath, OwnedObjectPath};
+
+mod gen;
+pub use gen::{GenInterface, GenTrait, GenTypes};
+mod types;
+pub use types::{NamedKind, NamedType, ParseError, TypeMap};
+
+const FDO_IFACE_PREFIX: &str = "org.freedesktop.DBus";
+const TOOL_NAME: &str = "zbus-xmlgen";
+
+/// The error type for `zbus_xmlgen`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An I/O error.
+    InputOutput(io::Error),
+    /// A zbus error, while introspecting or parsing introspection data.
+    Zbus(zbus::Error),
+    /// An error in a type mapping file.
+    Types(ParseError),
+    /// An invalid type signature in the introspection data.
+    InvalidSignature {
+        /// Where the introspection data comes from.
+        source: String,
+        /// The member path (`<interface>.<member>[.<arg>]`).
+        member: String,
+        /// The invalid signature.
+        signature: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InputOutput(e) => write!(f, "I/O error: {e}"),
+            Error::Zbus(e) => write!(f, "{e}"),
+            Error::Types(e) => write!(f, "type mapping error: {e}"),
+            Error::InvalidSignature {
+                source,
+                member,
+                signature,
+            } => write!(
+                f,
+                "{source}: `{member}`: invalid type signature `{signature}`"
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::InputOutput(e) => Some(e),
+            Error::Zbus(e) => Some(e),
+            Error::Types(e) => Some(e),
+            Error::InvalidSignature { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::InputOutput(e)
+    }
+}
+
+impl From<zbus::Error> for Error {
+    fn from(e: zbus::Error) -> Self {
+        Error::Zbus(e)
+    }
+}
+
+impl From<zbus::fdo::Error> for Error {
+    fn from(e: zbus::fdo::Error) -> Self {
+        Error::Zbus(e.into())
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Types(e)
+    }
+}
+
+/// Alias for a `Result` with the error type `zbus_xmlgen::Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The introspection data of an object.
+#[derive(Debug, Clone)]
+pub struct Object<'a> {
+    /// The object path, if known.
+    pub path: Option<OwnedObjectPath>,
+    /// The introspection data. Only its interfaces are used, not its children nodes.
+    pub node: Node<'a>,
+}
+
+/// Flatten the introspection tree of `node`, including the interfaces of its children nodes.
+///
+/// The object paths of the children are only known if `path` is given, or if the name of `node`
+/// is an absolute object path (as is typically the case for the root node of an XML file).
+pub fn objects_from_node<'a>(node: &Node<'a>, path: Option<ObjectPath<'_>>) -> Vec<Object<'a>> {
+    let path = path
+        .map(OwnedObjectPath::from)
+        .or_else(|| node.name().and_then(|n| OwnedObjectPath::try_from(n).ok()));
+    let mut objects = vec![];
+    add_objects(node, path, &mut objects);
+
+    objects
+}
+
+fn add_objects<'a>(node: &Node<'a>, path: Option<OwnedObjectPath>, objects: &mut Vec<Object<'a>>) {
+    if !node.interfaces().is_empty() {
+        objects.push(Object {
+            path: path.clone(),
+            node: node.clone(),
+        });
+    }
+    for child in node.nodes() {
+        let child_path = match (&path, child.name()) {
+            (Some(path), Some(name)) => child_path(path, name),
+            _ => None,
+        };
+        add_objects(child, child_path, objects);
+    }
+}
+
+fn child_path(path: &ObjectPath<'_>, name: &str) -> Option<OwnedObjectPath> {
+    let path = if path.as_str() == "/" {
+        format!("/{name}")
+    } else {
+        format!("{path}/{name}")
+    };
+
+    OwnedObjectPath::try_from(path).ok()
+}
+
+/// Introspect the object at `path` of `service` and, if `recursive`, all the objects below it.
+pub fn introspect(
+    conn: &Connection,
+    service: &BusName<'_>,
+    path: &ObjectPath<'_>,
+    recursive: bool,
+) -> Result<Vec<Object<'static>>> {
+    let mut objects = vec![];
+    let mut paths = vec![OwnedObjectPath::from(path.to_owned())];
+
+    while let Some(path) = paths.pop() {
+        let xml = IntrospectableProxy::builder(conn)
+            .destination(service)?
+            .path(&path)?
+            .build()?
+            .introspect()?;
+        let node = Node::from_reader(xml.as_bytes())?;
+        if recursive {
+            // Keep the objects in order, since `paths` is a stack.
+            for name in node.nodes().iter().rev().filter_map(Node::name) {
+                paths.extend(child_path(&path, name));
+            }
+        }
+        if !node.interfaces().is_empty() {
+            objects.push(Object {
+                path: Some(path),
+                node,
+            });
+        }
+    }
+
+    Ok(objects)
+}
+
+/// A generated Rust module, for a single interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    /// The module name.
+    pub name: String,
+    /// The name of the interface.
+    pub interface: String,
+    /// The module code.
+    pub code: String,
+}
+
+/// Rust code generator, for client-side proxies or server-side interface skeletons.
+#[derive(Debug)]
+pub struct Generator {
+    server: bool,
+    header: bool,
+    rustfmt: bool,
+    service: Option<String>,
+    types: TypeMap,
+    select: Vec<String>,
+    skip: Vec<String>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator {
+    /// Create a generator of `#[dbus_proxy]` traits.
+    pub fn new() -> Self {
+        Self {
+            server: false,
+            header: true,
+            rustfmt: true,
+            service: None,
+            types: TypeMap::default(),
+            select: vec![],
+            skip: vec![],
+        }
+    }
+
+    /// Generate `#[dbus_interface]` skeletons instead of proxies.
+    pub fn server(mut self, server: bool) -> Self {
+        self.server = server;
+
+        self
+    }
+
+    /// Whether to start the code with an inner doc comment describing it (the default).
+    ///
+    /// This needs to be disabled for code that is to be `include!`d.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+
+        self
+    }
+
+    /// Whether to format the code with `rustfmt` (the default).
+    ///
+    /// The `RUSTFMT` environment variable can be used to set the `rustfmt` binary to use. If it
+    /// can't be run, the code is left unformatted.
+    pub fn rustfmt(mut self, rustfmt: bool) -> Self {
+        self.rustfmt = rustfmt;
+
+        self
+    }
+
+    /// The service to use as `default_service` of the proxies.
+    pub fn service(mut self, service: &BusName<'_>) -> Self {
+        self.service = Some(service.to_string());
+
+        self
+    }
+
+    /// The named types to use, see [`TypeMap`].
+    pub fn types(mut self, types: TypeMap) -> Self {
+        self.types = types;
+
+        self
+    }
+
+    /// Only generate code for the interfaces matching `pattern`.
+    ///
+    /// A pattern is either an interface name, or a prefix followed by `*`. This can be called
+    /// multiple times, to select multiple interfaces. Standard `org.freedesktop.DBus.*` interfaces,
+    /// which are skipped by default, are generated if selected explicitly.
+    pub fn select_interface<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.select.push(pattern.into());
+
+        self
+    }
+
+    /// Skip the interfaces matching `pattern`.
+    ///
+    /// See [`Generator::select_interface`] for the pattern format.
+    pub fn skip_interface<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.skip.push(pattern.into());
+
+        self
+    }
+
+    /// Generate the code for all the interfaces of `objects`, as a single module.
+    ///
+    /// `source` describes where the introspection data comes from, for the header and errors.
+    /// Fails with [`Error::InvalidSignature`] if a type signature isn't a single complete type.
+    pub fn generate(&self, objects: &[Object<'_>], source: &str) -> Result<String> {
+        self.validate(objects, source)?;
+        let (ifaces, fdo_ifaces) = self.interfaces(objects);
+        self.types.used.borrow_mut().clear();
+
+        let mut code = String::new();
+        if self.header {
+            let names: Vec<_> = ifaces.iter().map(|(i, _)| i.name().to_string()).collect();
+            code.push_str(&self.header_text(&names, &fdo_ifaces, source));
+        }
+        code.push_str(self.use_line());
+        for (iface, path) in &ifaces {
+            code.push('\n');
+            code.push_str(&self.gen(iface, *path));
+        }
+        code.push_str(&GenTypes { types: &self.types }.to_string());
+
+        Ok(self.format(code))
+    }
+
+    /// Generate the code for all the interfaces of an introspection XML file.
+    pub fn generate_from_file<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        let path = path.as_ref();
+        let source = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let node = Node::from_reader(fs::File::open(path)?)?;
+        let objects = objects_from_node(&node, None);
+
+        self.generate(&objects, &source)
+    }
+
+    /// Generate code from an introspection XML file in a build script.
+    ///
+    /// The code is written to `file_name` in `$OUT_DIR`, from where it can be included with
+    /// `include!(concat!(env!("OUT_DIR"), "/<file_name>"))`, and cargo is told to rerun the build
+    /// script when the XML file changes. The header is never generated, since inner doc comments
+    /// can't be included. Returns the path of the written file.
+    pub fn build<P: AsRef<Path>>(mut self, xml: P, file_name: &str) -> Result<PathBuf> {
+        let xml = xml.as_ref();
+        println!("cargo:rerun-if-changed={}", xml.display());
+
+        let out_dir = env::var_os("OUT_DIR")
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "`OUT_DIR` is not set"))?;
+        self.header = false;
+        let code = self.generate_from_file(xml)?;
+        let path = Path::new(&out_dir).join(file_name);
+        fs::write(&path, code)?;
+
+        Ok(path)
+    }
+
+    // Checks that all the type signatures are valid single complete types, since the generators
+    // assume so.
+    fn validate(&self, objects: &[Object<'_>], source: &str) -> Result<()> {
+        let check = |member: String, signature: &str| {
+            if gen::is_complete_type(signature) {
+                Ok(())
+            } else {
+                Err(Error::InvalidSignature {
+                    source: source.to_string(),
+                    member,
+                    signature: signature.to_string(),
+                })
+            }
+        };
+        let check_args = |path: String, args: &[zbus::quick_xml::Arg]| {
+            for (i, arg) in args.iter().enumerate() {
+                let name = arg
+                    .name()
+                    .map(String::from)
+                    .unwrap_or_else(|| i.to_string());
+                check(format!("{path}.{name}"), arg.ty())?;
+            }
+
+            Ok::<_, Error>(())
+        };
+
+        let (ifaces, _) = self.interfaces(objects);
+        for (iface, _) in ifaces {
+            for m in iface.methods() {
+                check_args(format!("{}.{}", iface.name(), m.name()), m.args())?;
+            }
+            for signal in iface.signals() {
+                check_args(format!("{}.{}", iface.name(), signal.name()), signal.args())?;
+            }
+            for p in iface.properties() {
+                check(format!("{}.{}", iface.name(), p.name()), p.ty())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate a module for each interface of `objects`.
+    ///
+    /// Named types (see [`TypeMap`]) are generated in a separate `types` module, that the other
+    /// modules import from their parent. [`Generator::write_modules`] writes them all, along with
+    /// the parent module. Fails like [`Generator::generate`].
+    pub fn generate_modules(&self, objects: &[Object<'_>], source: &str) -> Result<Vec<Module>> {
+        self.validate(objects, source)?;
+        let (ifaces, _) = self.interfaces(objects);
+        self.types.used.borrow_mut().clear();
+
+        // The named types are only known once all the interfaces are generated.
+        let bodies: Vec<_> = ifaces
+            .iter()
+            .map(|(iface, path)| self.gen(iface, *path))
+            .collect();
+        let types = GenTypes { types: &self.types }.to_string();
+
+        let mut modules: Vec<_> = ifaces
+            .iter()
+            .zip(module_names(&ifaces))
+            .zip(bodies)
+            .map(|(((iface, _), name), body)| {
+                let mut code = String::new();
+                if self.header {
+                    code.push_str(&self.header_text(&[iface.name().to_string()], &[], source));
+                }
+                code.push_str(self.use_line());
+                if !types.is_empty() {
+                    code.push_str("#[allow(unused_imports)]\nuse super::types::*;\n");
+                }
+                code.push('\n');
+                code.push_str(&body);
+
+                Module {
+                    name,
+                    interface: iface.name().to_string(),
+                    code: self.format(code),
+                }
+            })
+            .collect();
+        if !types.is_empty() {
+            modules.push(Module {
+                name: "types".to_string(),
+                interface: String::new(),
+                code: self.format(types),
+            });
+        }
+
+        Ok(modules)
+    }
+
+    /// Write a module for each interface of `objects` in `dir`, along with a `mod.rs` declaring
+    /// them.
+    ///
+    /// `dir` is created if needed. Returns the paths of the written files.
+    pub fn write_modules<P: AsRef<Path>>(
+        &self,
+        objects: &[Object<'_>],
+        source: &str,
+        dir: P,
+    ) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let modules = self.generate_modules(objects, source)?;
+        let mut paths = vec![];
+        let mut mod_rs = String::new();
+        if self.header {
+            let (_, fdo_ifaces) = self.interfaces(objects);
+            let names: Vec<_> = modules
+                .iter()
+                .filter(|m| !m.interface.is_empty())
+                .map(|m| m.interface.clone())
+                .collect();
+            mod_rs.push_str(&self.header_text(&names, &fdo_ifaces, source));
+        }
+        for module in &modules {
+            mod_rs.push_str(&format!("pub mod {};\n", module.name));
+
+            let path = dir.join(format!("{}.rs", module.name));
+            fs::write(&path, &module.code)?;
+            paths.push(path);
+        }
+        let path = dir.join("mod.rs");
+        fs::write(&path, self.format(mod_rs))?;
+        paths.push(path);
+
+        Ok(paths)
+    }
+
+    // Returns the interfaces to generate, with the path of the object implementing them if they're
+    // implemented by a single object, and the skipped standard interfaces.
+    fn interfaces<'o>(
+        &self,
+        objects: &'o [Object<'o>],
+    ) -> (
+        Vec<(&'o Interface<'o>, Option<&'o ObjectPath<'o>>)>,
+        Vec<String>,
+    ) {
+        let mut ifaces: Vec<(&Interface<'_>, Option<&ObjectPath<'_>>)> = vec![];
+        let mut fdo_ifaces = vec![];
+
+        for object in objects {
+            for iface in object.node.interfaces() {
+                let name = iface.name();
+                let selected = self.select.iter().any(|p| matches_pattern(p, &name));
+                if (!self.select.is_empty() && !selected)
+                    || self.skip.iter().any(|p| matches_pattern(p, &name))
+                {
+                    continue;
+                }
+                if name.starts_with(FDO_IFACE_PREFIX) && !selected {
+                    if !fdo_ifaces.iter().any(|i| i == name.as_str()) {
+                        fdo_ifaces.push(name.to_string());
+                    }
+                    continue;
+                }
+
+                let path = object.path.as_deref();
+                match ifaces.iter_mut().find(|(i, _)| i.name() == name) {
+                    Some((_, p)) if *p != path => *p = None,
+                    Some(_) => (),
+                    None => ifaces.push((iface, path)),
+                }
+            }
+        }
+
+        (ifaces, fdo_ifaces)
+    }
+
+    // Formatting can't fail once the signatures are validated.
+    fn gen(&self, iface: &Interface<'_>, path: Option<&ObjectPath<'_>>) -> String {
+        if self.server {
+            GenInterface {
+                interface: iface,
+                types: &self.types,
+            }
+            .to_string()
+        } else {
+            let service = self
+                .service
+                .as_deref()
+                .and_then(|s| BusName::try_from(s).ok());
+            GenTrait {
+                interface: iface,
+                service: service.as_ref(),
+                path,
+                types: &self.types,
+            }
+            .to_string()
+        }
+    }
+
+    fn use_line(&self) -> &'static str {
+        if self.server {
+            "use zbus::dbus_interface;\n"
+        } else {
+            "use zbus::dbus_proxy;\n"
+        }
+    }
+
+    fn header_text(&self, ifaces: &[String], fdo_ifaces: &[String], source: &str) -> String {
+        let (kind, kinds) = if self.server {
+            ("skeleton", "skeletons")
+        } else {
+            ("proxy", "proxies")
+        };
+        let mut header = String::new();
+        if !ifaces.is_empty() {
+            let names: Vec<_> = ifaces.iter().map(|i| format!("`{i}`")).collect();
+            let kind = if ifaces.len() == 1 { kind } else { kinds };
+            header.push_str(&format!(
+                "//! # DBus interface {kind} for: {}\n",
+                names.join(", ")
+            ));
+        }
+        let (section, page) = if self.server {
+            ("Writing a server interface", "server")
+        } else {
+            ("Writing a client proxy", "client")
+        };
+        header.push_str(&format!(
+            "//!\n\
+             //! This code was generated by `{}` `{}` from DBus introspection data.\n\
+             //! Source: `{source}`.\n\
+             //!\n\
+             //! You may prefer to adapt it, instead of using it verbatim.\n\
+             //!\n\
+             //! More information can be found in the\n\
+             //! [{section}](https://dbus.pages.freedesktop.org/zbus/{page}.html)\n\
+             //! section of the zbus documentation.\n\
+             //!\n",
+            TOOL_NAME,
+            env!("CARGO_PKG_VERSION"),
+        ));
+        if !fdo_ifaces.is_empty() {
+            header.push_str(
+                "//! This DBus object implements\n\
+                 //! [standard DBus interfaces](https://dbus.freedesktop.org/doc/dbus-specification.html),\n\
+                 //! (`org.freedesktop.DBus.*`) for which the following zbus proxies can be used:\n\
+                 //!\n",
+            );
+            for iface in fdo_ifaces {
+                let idx = iface.rfind('.').unwrap() + 1;
+                let name = &iface[idx..];
+                header.push_str(&format!("//! * [`zbus::fdo::{name}Proxy`]\n"));
+            }
+            header.push_str(&format!(
+                "//!\n\
+                 //! …consequently `{}` did not generate code for the above interfaces.\n",
+                TOOL_NAME,
+            ));
+        }
+        header.push('\n');
+
+        header
+    }
+
+    fn format(&self, code: String) -> String {
+        if !self.rustfmt {
+            return code;
+        }
+
+        format_code(&code).unwrap_or(code)
+    }
+}
+
+// Formats `code` with rustfmt, if it can be run.
+fn format_code(code: &str) -> Option<String> {
+    let rustfmt = env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let mut process = Command::new(rustfmt)
+        .args(["--edition", "2018"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Write from another thread, so rustfmt can't block on a full stdout pipe.
+    let mut stdin = process.stdin.take()?;
+    let input = code.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = process.wait_with_output().ok()?;
+    writer.join().ok()?.ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+// Module names are the snake-cased last component of the interface names, or the whole names if
+// that's ambiguous.
+fn module_names(ifaces: &[(&Interface<'_>, Option<&ObjectPath<'_>>)]) -> Vec<String> {
+    fn short_name(name: &str) -> String {
+        let idx = name.rfind('.').unwrap() + 1;
+
+        to_snakecase(&name[idx..]).to_string()
+    }
+
+    ifaces
+        .iter()
+        .map(|(iface, _)| {
+            let name = iface.name();
+            let short = short_name(&name);
+            let ambiguous = ifaces
+                .iter()
+                .filter(|(i, _)| short_name(&i.name()) == short)
+                .count()
+                > 1;
+            let name = if ambiguous || short == "types" || short == "mod" {
+                to_snakecase(name.replace('.', "_")).to_string()
+            } else {
+                short
+            };
+
+            gen::to_identifier(&name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{objects_from_node, Generator};
+    use zbus::quick_xml::Node;
+
+    static TREE: &str = r##"
+<node name="/org/example">
+  <interface name="org.freedesktop.DBus.Peer">
+    <method name="Ping"/>
+  </interface>
+  <interface name="org.example.Manager">
+    <method name="Reload"/>
+  </interface>
+  <node name="first">
+    <interface name="org.example.Device">
+      <property name="Name" type="s" access="read"/>
+    </interface>
+    <interface name="org.other.Device"/>
+  </node>
+  <node name="second">
+    <interface name="org.example.Device">
+      <property name="Name" type="s" access="read"/>
+    </interface>
+  </node>
+</node>
+"##;
+
+    #[test]
+    fn objects() {
+        let node = Node::from_reader(TREE.as_bytes()).unwrap();
+        let objects = objects_from_node(&node, None);
+        let paths: Vec<_> = objects
+            .iter()
+            .map(|o| o.path.as_ref().unwrap().as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            ["/org/example", "/org/example/first", "/org/example/second"]
+        );
+    }
+
+    #[test]
+    fn generate() {
+        let node = Node::from_reader(TREE.as_bytes()).unwrap();
+        let objects = objects_from_node(&node, None);
+
+        let code = Generator::new()
+            .rustfmt(false)
+            .generate(&objects, "tree.xml")
+            .unwrap();
+        assert!(code.contains("//! # DBus interface proxies for: `org.example.Manager`, `org.example.Device`, `org.other.Device`"));
+        assert!(code.contains("//! * [`zbus::fdo::PeerProxy`]"));
+        assert!(code.contains(
+            "#[dbus_proxy(interface = \"org.example.Manager\", default_path = \"/org/example\", assume_defaults = true)]"
+        ));
+        // Implemented by multiple objects.
+        assert!(code
+            .contains("#[dbus_proxy(interface = \"org.example.Device\", assume_defaults = true)]"));
+
+        let code = Generator::new()
+            .rustfmt(false)
+            .header(false)
+            .select_interface("org.example.*")
+            .select_interface("org.freedesktop.DBus.Peer")
+            .skip_interface("org.example.Manager")
+            .generate(&objects, "tree.xml")
+            .unwrap();
+        assert!(!code.contains("//!"));
+        assert!(code.contains("trait Peer {"));
+        assert!(code.contains("trait Device {"));
+        assert!(!code.contains("trait Manager {"));
+        assert!(!code.contains("org.other.Device"));
+    }
+
+    #[test]
+    fn generate_modules() {
+        let node = Node::from_reader(TREE.as_bytes()).unwrap();
+        let objects = objects_from_node(&node, None);
+
+        let modules = Generator::new()
+            .server(true)
+            .rustfmt(false)
+            .generate_modules(&objects, "tree.xml")
+            .unwrap();
+        let names: Vec<_> = modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["manager", "org_example_device", "org_other_device"]);
+        assert!(modules[0]
+            .code
+            .contains("//! # DBus interface skeleton for: `org.example.Manager`"));
+        assert!(modules[0].code.contains("use zbus::dbus_interface;"));
+        assert!(modules[1].code.contains("pub struct Device;"));
+    }
+
+    #[test]
+    fn validate() {
+        let generator = Generator::new();
+        let node = Node::from_reader(TREE.as_bytes()).unwrap();
+        generator
+            .validate(&objects_from_node(&node, None), "tree.xml")
+            .unwrap();
+
+        let xml = r#"
+<node>
+  <interface name="org.example.Manager">
+    <method name="GetDevice">
+      <arg name="device" type="(su" direction="out"/>
+    </method>
+    <property name="Flags" type="uu" access="read"/>
+  </interface>
+</node>"#;
+        let node = Node::from_reader(xml.as_bytes()).unwrap();
+        let err = generator
+            .validate(&objects_from_node(&node, None), "manager.xml")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manager.xml: `org.example.Manager.GetDevice.device`: invalid type signature `(su`"
+        );
+
+        let node = Node::from_reader(xml.replace("(su", "(su)").as_bytes()).unwrap();
+        let err = generator
+            .validate(&objects_from_node(&node, None), "manager.xml")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manager.xml: `org.example.Manager.Flags`: invalid type signature `uu`"
+        );
+
+        // The generators validate the introspection data first.
+        let node = Node::from_reader(xml.replace("(su", "()").as_bytes()).unwrap();
+        let err = generator
+            .generate(&objects_from_node(&node, None), "manager.xml")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manager.xml: `org.example.Manager.GetDevice.device`: invalid type signature `()`"
+        );
+        assert!(generator
+            .generate_modules(&objects_from_node(&node, None), "manager.xml")
+            .is_err());
+    }
+}
@@ -2,195 +2,149 @@
 
 use std::{
     convert::TryInto,
-    env::args,
     error::Error,
     fs::File,
-    io::Write,
-    path::Path,
-    process::{Command, Stdio},
+    io::{self, Write},
+    path::{Path, PathBuf},
     result::Result,
 };
 
+use clap::Parser;
 use zbus::{
-    blocking::{Connection, ConnectionBuilder, ProxyBuilder},
+    blocking::{Connection, ConnectionBuilder},
     names::BusName,
-    quick_xml::{Interface, Node},
+    quick_xml::Node,
 };
-
-mod gen;
-use gen::GenTrait;
+use zbus_xmlgen::{introspect, objects_from_node, Generator, TypeMap};
 use zvariant::ObjectPath;
 
+/// Generate zbus-based Rust code from D-Bus XML interface descriptions.
+///
+/// The introspection data is either read from XML files, or from a running service if one of
+/// `--system`, `--session` or `--address` is given, in which case the arguments are the service
+/// name and the object path.
+#[derive(Parser, Debug)]
+#[clap(name = "zbus-xmlgen", version)]
+struct Args {
+    /// Introspect a service on the system bus.
+    #[clap(long, conflicts_with_all = &["session", "address"])]
+    system: bool,
+
+    /// Introspect a service on the session bus.
+    #[clap(long, conflicts_with = "address")]
+    session: bool,
+
+    /// Introspect a service on the bus at the given address.
+    #[clap(long, value_name = "ADDRESS")]
+    address: Option<String>,
+
+    /// Also introspect all the objects below the object path.
+    #[clap(short, long)]
+    recursive: bool,
+
+    /// Generate `#[dbus_interface]` skeletons instead of proxies.
+    #[clap(long)]
+    server: bool,
+
+    /// Write a module per interface in the given directory, instead of printing the code.
+    #[clap(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Only generate code for the given interfaces (or prefixes, ending with `*`).
+    #[clap(short, long = "interface", value_name = "NAME")]
+    interfaces: Vec<String>,
+
+    /// Skip the given interfaces (or prefixes, ending with `*`).
+    #[clap(short, long = "skip", value_name = "NAME")]
+    skip: Vec<String>,
+
+    /// Generate named types, as described in the given type mapping file.
+    #[clap(long, value_name = "FILE")]
+    types: Option<PathBuf>,
+
+    /// Don't format the code with rustfmt.
+    #[clap(long)]
+    no_rustfmt: bool,
+
+    /// XML files, or the service and object path to introspect.
+    #[clap(required = true, value_name = "FILE | SERVICE OBJECT_PATH")]
+    args: Vec<String>,
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let input_src;
-
-    let proxy = |conn: Connection, service, path| -> zbus::blocking::fdo::IntrospectableProxy<'_> {
-        ProxyBuilder::new(&conn)
-            .destination(service)
-            .expect("invalid destination")
-            .path(path)
-            .expect("invalid path")
-            .build()
-            .unwrap()
+    let args = Args::parse();
+
+    let mut generator = Generator::new()
+        .server(args.server)
+        .rustfmt(!args.no_rustfmt);
+    if let Some(types) = &args.types {
+        generator = generator.types(TypeMap::from_file(types)?);
+    }
+    for iface in &args.interfaces {
+        generator = generator.select_interface(iface);
+    }
+    for iface in &args.skip {
+        generator = generator.skip_interface(iface);
+    }
+
+    let connection = if args.system {
+        Some(("system bus", Connection::system()?))
+    } else if args.session {
+        Some(("session bus", Connection::session()?))
+    } else if let Some(address) = &args.address {
+        Some(("bus", ConnectionBuilder::address(&**address)?.build()?))
+    } else {
+        None
     };
 
-    let (node, service, path) = match args().nth(1) {
-        Some(bus) if bus == "--system" || bus == "--session" => {
-            let connection = if bus == "--system" {
-                Connection::system()?
+    let (objects, source) = match connection {
+        Some((bus, connection)) => {
+            let (service, path) = match args.args.as_slice() {
+                [service, path] => (service, path),
+                _ => return Err("Expected a service and an object path".into()),
+            };
+            let service: BusName<'_> = service.as_str().try_into()?;
+            let path: ObjectPath<'_> = path.as_str().try_into()?;
+            generator = generator.service(&service);
+
+            let source = if args.recursive {
+                format!("Interfaces under '{path}' from service '{service}' on {bus}")
             } else {
-                Connection::session()?
+                format!("Interface '{path}' from service '{service}' on {bus}")
             };
-            let service: BusName<'_> = args()
-                .nth(2)
-                .expect("Missing param for service")
-                .try_into()?;
-            let path: ObjectPath<'_> = args()
-                .nth(3)
-                .expect("Missing param for object path")
-                .try_into()?;
-
-            input_src = format!(
-                "Interface '{}' from service '{}' on {} bus",
-                path,
-                service,
-                bus.trim_start_matches("--")
-            );
-
-            let xml = proxy(connection, service.clone(), path.clone()).introspect()?;
-            (
-                Node::from_reader(xml.as_bytes())?,
-                Some(service),
-                Some(path),
-            )
-        }
-        Some(address) if address == "--address" => {
-            let address = args().nth(2).expect("Missing param for address path");
-            let service: BusName<'_> = args()
-                .nth(3)
-                .expect("Missing param for service")
-                .try_into()?;
-            let path: ObjectPath<'_> = args()
-                .nth(4)
-                .expect("Missing param for object path")
-                .try_into()?;
-
-            let connection = ConnectionBuilder::address(&*address)?.build()?;
-
-            input_src = format!("Interface '{path}' from service '{service}'");
-
-            let xml = proxy(connection, service.clone(), path.clone()).introspect()?;
+
             (
-                Node::from_reader(xml.as_bytes())?,
-                Some(service),
-                Some(path),
+                introspect(&connection, &service, &path, args.recursive)?,
+                source,
             )
         }
-        Some(path) => {
-            input_src = Path::new(&path)
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string();
-            let f = File::open(path)?;
-            (Node::from_reader(f)?, None, None)
-        }
         None => {
-            eprintln!(
-                r#"Usage:
-  zbus-xmlgen <interface.xml>
-  zbus-xmlgen --system|--session <service> <object_path>
-  zbus-xmlgen --address <address> <service> <object_path>
-"#
-            );
-            return Ok(());
+            let mut objects = vec![];
+            let mut sources = vec![];
+            for path in &args.args {
+                let node = Node::from_reader(File::open(path)?)?;
+                objects.extend(objects_from_node(&node, None));
+                sources.push(
+                    Path::new(path)
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            }
+
+            (objects, sources.join("`, `"))
         }
     };
 
-    let mut process = match Command::new("rustfmt").stdin(Stdio::piped()).spawn() {
-        Err(why) => panic!("couldn't spawn rustfmt: {}", why),
-        Ok(process) => process,
-    };
-    let rustfmt_stdin = process.stdin.as_mut().unwrap();
-    let fdo_iface_prefix = "org.freedesktop.DBus";
-    let (fdo_standard_ifaces, needed_ifaces): (Vec<&Interface<'_>>, Vec<&Interface<'_>>) = node
-        .interfaces()
-        .iter()
-        .partition(|&i| i.name().starts_with(fdo_iface_prefix));
-
-    if let Some((first_iface, following_ifaces)) = needed_ifaces.split_first() {
-        if following_ifaces.is_empty() {
-            writeln!(
-                rustfmt_stdin,
-                "//! # DBus interface proxy for: `{}`",
-                first_iface.name()
-            )?;
-        } else {
-            write!(
-                rustfmt_stdin,
-                "//! # DBus interface proxies for: `{}`",
-                first_iface.name()
-            )?;
-            for iface in following_ifaces {
-                write!(rustfmt_stdin, ", `{}`", iface.name())?;
+    match &args.output {
+        Some(dir) => {
+            for path in generator.write_modules(&objects, &source, dir)? {
+                eprintln!("Wrote {}", path.display());
             }
-            writeln!(rustfmt_stdin)?;
         }
+        None => io::stdout().write_all(generator.generate(&objects, &source)?.as_bytes())?,
     }
 
-    write!(
-        rustfmt_stdin,
-        "//!
-         //! This code was generated by `{}` `{}` from DBus introspection data.
-         //! Source: `{}`.
-         //!
-         //! You may prefer to adapt it, instead of using it verbatim.
-         //!
-         //! More information can be found in the
-         //! [Writing a client proxy](https://dbus.pages.freedesktop.org/zbus/client.html)
-         //! section of the zbus documentation.
-         //!
-        ",
-        env!("CARGO_BIN_NAME"),
-        env!("CARGO_PKG_VERSION"),
-        input_src,
-    )?;
-    if !fdo_standard_ifaces.is_empty() {
-        write!(rustfmt_stdin,
-            "//! This DBus object implements
-             //! [standard DBus interfaces](https://dbus.freedesktop.org/doc/dbus-specification.html),
-             //! (`org.freedesktop.DBus.*`) for which the following zbus proxies can be used:
-             //!
-            ")?;
-        for iface in &fdo_standard_ifaces {
-            let idx = iface.name().rfind('.').unwrap() + 1;
-            let name = &iface.name()[idx..];
-            writeln!(rustfmt_stdin, "//! * [`zbus::fdo::{name}Proxy`]")?;
-        }
-        write!(
-            rustfmt_stdin,
-            "//!
-             //! …consequently `{}` did not generate code for the above interfaces.
-            ",
-            env!("CARGO_BIN_NAME")
-        )?;
-    }
-    write!(
-        rustfmt_stdin,
-        "
-        use zbus::dbus_proxy;
-        "
-    )?;
-    for iface in &needed_ifaces {
-        writeln!(rustfmt_stdin)?;
-        let gen = GenTrait {
-            interface: iface,
-            service: service.as_ref(),
-            path: path.as_ref(),
-        }
-        .to_string();
-        rustfmt_stdin.write_all(gen.as_bytes())?;
-    }
-    process.wait()?;
     Ok(())
 }
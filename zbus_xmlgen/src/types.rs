@@ -0,0 +1,249 @@
+//! Mapping of D-Bus types to named Rust types.
+
+use std::{cell::RefCell, collections::BTreeMap, error, fmt, fs, path::Path};
+
+use crate::gen::is_complete_type;
+
+/// The shape of a named type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamedKind {
+    /// A structure, with the given field names. Missing names are generated.
+    Struct(Vec<String>),
+    /// A `a{sv}` dictionary with known keys and their signatures.
+    Dict(Vec<(String, String)>),
+}
+
+/// A named Rust type to generate for a D-Bus type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedType {
+    pub name: String,
+    pub kind: NamedKind,
+}
+
+impl NamedType {
+    /// A structure type with no explicit field names.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            kind: NamedKind::Struct(vec![]),
+        }
+    }
+}
+
+/// An error in a type mapping file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// The named types known to the generators.
+///
+/// By default, structures are generated as anonymous tuples and dictionaries as `HashMap`s. A
+/// `TypeMap` allows naming them instead, either through annotations in the introspection data
+/// (`org.qtproject.QtDBus.QtTypeName` or `org.gtk.GDBus.C.Name`) or through a sidecar mapping
+/// file, with one mapping per line:
+///
+/// ```text
+/// # Name all `(ssu)` structures, with named fields.
+/// (ssu) = Device(name, path, flags)
+/// # Name the type of a method or signal argument, or of a property.
+/// org.example.Manager.GetDevice.device = Device
+/// # Name the reply of a method with multiple out arguments (fields are named after them).
+/// org.example.Manager.GetStatus = Status
+/// # Generate a dictionary type for a `a{sv}` with known keys.
+/// org.example.Manager.Settings = Settings{Brightness: u, DisplayName: s}
+/// ```
+///
+/// The left-hand side is either a signature or a member path (`<interface>.<member>[.<arg>]`),
+/// which are told apart by the presence of a `.` (signatures never contain one).
+///
+/// The generators record the definitions of the types they use, so that they can be emitted
+/// once, through [`GenTypes`](crate::GenTypes), after all the interfaces.
+#[derive(Debug, Default)]
+pub struct TypeMap {
+    // Ordered, so that the first match of `by_name` doesn't depend on hashing.
+    signatures: BTreeMap<String, NamedType>,
+    members: BTreeMap<String, NamedType>,
+    // Name -> (signature, definition).
+    pub(crate) used: RefCell<BTreeMap<String, (String, String)>>,
+}
+
+impl TypeMap {
+    /// Parse a type mapping file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn error::Error>> {
+        let s = fs::read_to_string(path)?;
+
+        Ok(Self::parse(&s)?)
+    }
+
+    /// Parse type mappings, in the format described in the [`TypeMap`] documentation.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut map = Self::default();
+
+        for (idx, line) in s.lines().enumerate() {
+            let error = |message: String| ParseError {
+                line: idx + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, spec) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `<key> = <type>`, got `{line}`")))?;
+            let (key, named) = (key.trim(), parse_named_type(spec.trim()).map_err(error)?);
+            if key.is_empty() {
+                return Err(error("missing signature or member path".into()));
+            }
+
+            if key.contains('.') {
+                map.members.insert(key.to_string(), named);
+            } else {
+                if !is_complete_type(key) {
+                    return Err(error(format!("invalid type signature `{key}`")));
+                }
+                match named.kind {
+                    NamedKind::Struct(_) if !key.starts_with('(') || !key.ends_with(')') => {
+                        return Err(error(format!("`{key}` is not a structure signature")));
+                    }
+                    NamedKind::Dict(_) if key != "a{sv}" => {
+                        return Err(error(format!(
+                            "dictionary types are only supported for `a{{sv}}`, not `{key}`"
+                        )));
+                    }
+                    _ => (),
+                }
+                map.signatures.insert(key.to_string(), named);
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// The named type for all occurrences of `signature`, if any.
+    pub fn signature(&self, signature: &str) -> Option<&NamedType> {
+        self.signatures.get(signature)
+    }
+
+    /// The named type for a member path (`<interface>.<member>[.<arg>]`), if any.
+    pub fn member(&self, path: &str) -> Option<&NamedType> {
+        self.members.get(path)
+    }
+
+    /// The mapping giving the type `name`, if any.
+    ///
+    /// This is used to find field names and dictionary keys for types named through annotations.
+    pub fn by_name(&self, name: &str) -> Option<&NamedType> {
+        self.signatures
+            .values()
+            .chain(self.members.values())
+            .find(|t| t.name == name)
+    }
+}
+
+fn parse_named_type(spec: &str) -> Result<NamedType, String> {
+    let end = spec.find(&['(', '{'][..]).unwrap_or(spec.len());
+    let (name, rest) = (spec[..end].trim(), &spec[end..]);
+    if !is_type_name(name) {
+        return Err(format!("invalid type name `{name}`"));
+    }
+    let list = |close| {
+        rest[1..]
+            .strip_suffix(close)
+            .ok_or_else(|| format!("missing `{close}` in `{spec}`"))
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    let kind = match rest.chars().next() {
+        None => NamedKind::Struct(vec![]),
+        Some('(') => NamedKind::Struct(list(')')?.into_iter().map(Into::into).collect()),
+        Some(_) => {
+            let mut keys = vec![];
+            for entry in list('}')? {
+                let (key, signature) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected `<key>: <signature>`, got `{entry}`"))?;
+                let (key, signature) = (key.trim(), signature.trim());
+                if key.is_empty() || signature.is_empty() {
+                    return Err(format!("expected `<key>: <signature>`, got `{entry}`"));
+                }
+                if !is_complete_type(signature) {
+                    return Err(format!("invalid type signature `{signature}`"));
+                }
+                keys.push((key.to_string(), signature.to_string()));
+            }
+
+            NamedKind::Dict(keys)
+        }
+    };
+
+    Ok(NamedType {
+        name: name.to_string(),
+        kind,
+    })
+}
+
+pub(crate) fn is_type_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NamedKind, NamedType, TypeMap};
+
+    #[test]
+    fn parse() {
+        let map = TypeMap::parse(
+            r#"
+            # Comment
+            (ssu) = Device(name, path, flags)
+            org.example.Manager.GetDevice.device = Device # Trailing comment
+            org.example.Manager.Settings = Settings{Brightness: u, DisplayName: s}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            map.signature("(ssu)").unwrap().kind,
+            NamedKind::Struct(vec!["name".into(), "path".into(), "flags".into()])
+        );
+        assert_eq!(
+            map.member("org.example.Manager.GetDevice.device"),
+            Some(&NamedType::new("Device".into()))
+        );
+        assert_eq!(
+            map.member("org.example.Manager.Settings").unwrap().kind,
+            NamedKind::Dict(vec![
+                ("Brightness".into(), "u".into()),
+                ("DisplayName".into(), "s".into())
+            ])
+        );
+        assert_eq!(map.by_name("Settings").unwrap().name, "Settings");
+
+        assert_eq!(
+            TypeMap::parse("\n(ssu)").unwrap_err().to_string(),
+            "line 2: expected `<key> = <type>`, got `(ssu)`"
+        );
+        assert!(TypeMap::parse("s = Name").is_err());
+        assert!(TypeMap::parse("a{ss} = Name{Key: s}").is_err());
+        assert!(TypeMap::parse("(s) = Name(field").is_err());
+        assert!(TypeMap::parse("(s) = 0Name").is_err());
+        assert!(TypeMap::parse("(s = Name").is_err());
+        assert!(TypeMap::parse("a{sv} = Name{Key: uu}").is_err());
+    }
+}
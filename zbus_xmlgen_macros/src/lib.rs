@@ -0,0 +1,248 @@
+#![deny(rust_2018_idioms)]
+#![doc(
+    html_logo_url = "https://storage.googleapis.com/fdo-gitlab-uploads/project/avatar/3213/zbus-logomark.png"
+)]
+#![doc = include_str!("../README.md")]
+
+use std::{convert::TryFrom, env, fs, path::PathBuf};
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Error, Lit, LitStr, Meta, MetaNameValue, NestedMeta, Token,
+};
+use zbus::{names::BusName, quick_xml::Node, zvariant::ObjectPath};
+use zbus_xmlgen::{objects_from_node, Generator};
+
+const FDO_IFACE_PREFIX: &str = "org.freedesktop.DBus";
+
+/// Function-like macro generating proxies from a D-Bus introspection XML file.
+///
+/// For each interface described in the file, a `#[dbus_proxy]` trait is generated by
+/// [`zbus_xmlgen`], exactly as the `zbus-xmlgen` tool would, along with the named types it may
+/// need. The file path is relative to the crate's manifest directory (`CARGO_MANIFEST_DIR`) and the
+/// crate is rebuilt whenever the file changes.
+///
+/// The standard `org.freedesktop.DBus.*` interfaces are skipped, unless explicitly selected. The
+/// following optional arguments are supported:
+///
+/// * `interface` - only generate the proxy for the given interface.
+///
+/// * `default_service` - the default service name of the proxies.
+///
+/// * `default_path` - the object path of the root node. If the root node of the XML has an
+///   absolute object path as its name, it is used by default.
+///
+/// Missing defaults are assumed from the interface name (see `assume_defaults` in
+/// `zbus::dbus_proxy`).
+///
+/// The XML is validated first (see `zbus::quick_xml::Node::validate`) and errors are reported
+/// with their location in the file.
+///
+/// # Example
+///
+/// ```ignore
+/// zbus_xmlgen_macros::proxy_from_xml!(
+///     "xml/org.freedesktop.login1.Manager.xml",
+///     interface = "org.freedesktop.login1.Manager",
+///     default_service = "org.freedesktop.login1",
+/// );
+///
+/// # fn main() -> zbus::Result<()> {
+/// let connection = zbus::blocking::Connection::system()?;
+/// let manager = ManagerProxyBlocking::new(&connection)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`zbus_xmlgen`]: https://docs.rs/zbus_xmlgen
+#[proc_macro]
+pub fn proxy_from_xml(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as XmlArgs);
+    expand(args)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+// The arguments of `proxy_from_xml!`.
+struct XmlArgs {
+    path: LitStr,
+    interface: Option<String>,
+    default_service: Option<String>,
+    default_path: Option<String>,
+}
+
+impl Parse for XmlArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        let mut args = XmlArgs {
+            path,
+            interface: None,
+            default_service: None,
+            default_path: None,
+        };
+        if input.is_empty() {
+            return Ok(args);
+        }
+        input.parse::<Token![,]>()?;
+
+        let metas = Punctuated::<NestedMeta, Token![,]>::parse_terminated(input)?;
+        for meta in metas {
+            let (ident, value) = match &meta {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(value),
+                    ..
+                })) => match path.get_ident() {
+                    Some(ident) => (ident.to_string(), value.value()),
+                    None => return Err(Error::new_spanned(meta, "expected `name = \"value\"`")),
+                },
+                _ => return Err(Error::new_spanned(meta, "expected `name = \"value\"`")),
+            };
+            let field = match ident.as_str() {
+                "interface" => &mut args.interface,
+                "default_service" => &mut args.default_service,
+                "default_path" => &mut args.default_path,
+                _ => {
+                    return Err(Error::new_spanned(
+                        meta,
+                        format!("unknown attribute `{ident}`"),
+                    ))
+                }
+            };
+            *field = Some(value);
+        }
+
+        Ok(args)
+    }
+}
+
+fn expand(args: XmlArgs) -> Result<proc_macro2::TokenStream, Error> {
+    let span = args.path.span();
+    let mut path = PathBuf::from(args.path.value());
+    if path.is_relative() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+            .map_err(|_| Error::new(span, "`CARGO_MANIFEST_DIR` is not set"))?;
+        path = PathBuf::from(manifest_dir).join(path);
+    }
+    let xml = fs::read_to_string(&path)
+        .map_err(|e| Error::new(span, format!("failed to read `{}`: {e}", path.display())))?;
+    let file = path.display().to_string();
+    let error = |msg: String| Error::new(span, format!("{file}: {msg}"));
+
+    let node = Node::try_from(xml.as_str()).map_err(|e| error(e.to_string()))?;
+    node.validate().map_err(|e| {
+        let (line, column) = line_column(&xml, locate(&xml, e.location()));
+
+        Error::new(span, format!("{file}:{line}:{column}: {e}"))
+    })?;
+
+    let root_path = args
+        .default_path
+        .as_deref()
+        .map(ObjectPath::try_from)
+        .transpose()
+        .map_err(|e| Error::new(span, format!("invalid `default_path`: {e}")))?;
+    let objects = objects_from_node(&node, root_path);
+
+    let mut generator = Generator::new().rustfmt(false).header(false);
+    if let Some(service) = &args.default_service {
+        let service = BusName::try_from(service.as_str())
+            .map_err(|e| Error::new(span, format!("invalid `default_service`: {e}")))?;
+        generator = generator.service(&service);
+    }
+    let mut names = objects
+        .iter()
+        .flat_map(|o| o.node.interfaces())
+        .map(|i| i.name().to_string());
+    match &args.interface {
+        Some(name) => {
+            if !names.any(|n| n == *name) {
+                return Err(error(format!("no interface `{name}`")));
+            }
+            generator = generator.select_interface(name.as_str());
+        }
+        None => {
+            if names.all(|n| n.starts_with(FDO_IFACE_PREFIX)) {
+                return Err(error("no interfaces".to_string()));
+            }
+        }
+    }
+
+    let code: proc_macro2::TokenStream = generator
+        .generate(&objects, &file)
+        .map_err(|e| Error::new(span, e.to_string()))?
+        .parse()
+        .map_err(|e| error(format!("failed to generate code: {e}")))?;
+
+    // The generated code imports `dbus_proxy`, so it's kept in its own module.
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let module = format_ident!(
+        "__zbus_xml_{}",
+        to_identifier(&format!("{stem}_{}", args.interface.unwrap_or_default()))
+    );
+
+    Ok(quote! {
+        // Rebuild when the file changes.
+        const _: &[u8] = ::std::include_bytes!(#file);
+
+        #[doc(hidden)]
+        mod #module {
+            #code
+        }
+        pub use #module::*;
+    })
+}
+
+// Finds the byte offset of the element at `location` in `xml`, as reported by `Node::validate`
+// (e.g ``node `/org/example` > interface `org.example.Manager` > method `Reload` > arg 0``).
+fn locate(xml: &str, location: &str) -> usize {
+    let mut pos = 0;
+    for component in location.split(" > ") {
+        let (element, rest) = component.split_once(' ').unwrap_or((component, ""));
+        let tag = format!("<{element}");
+        let mut candidates = xml[pos..].match_indices(&tag).map(|(i, _)| pos + i);
+        let found = match rest.split('`').nth(1) {
+            Some(name) => {
+                let attr = format!("name=\"{name}\"");
+                candidates.find(|i| {
+                    let end = xml[*i..].find('>').map(|e| i + e).unwrap_or(xml.len());
+
+                    xml[*i..end].contains(&attr)
+                })
+            }
+            // Unnamed args are located by their index.
+            None => candidates.nth(rest.parse().unwrap_or(0)),
+        };
+        match found {
+            Some(found) => pos = found,
+            None => break,
+        }
+    }
+
+    pos
+}
+
+// Returns the 1-based line and column of the byte offset `pos`.
+fn line_column(s: &str, pos: usize) -> (usize, usize) {
+    let before = &s[..pos.min(s.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+
+    (line, column)
+}
+
+fn to_identifier(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use zbus::{dbus_interface, zvariant::Value, SignalContext};
+
+zbus_xmlgen_macros::proxy_from_xml!(
+    "tests/data/org.freedesktop.zbus_xmlgen_macros.Xml.xml",
+    default_service = "org.freedesktop.zbus_xmlgen_macros.Xml",
+);
+
+struct Xml {
+    count: u32,
+}
+
+#[dbus_interface(name = "org.freedesktop.zbus_xmlgen_macros.Xml")]
+impl Xml {
+    fn concat(&self, first: &str, second: &str) -> String {
+        format!("{first}{second}")
+    }
+
+    fn stats(&self, values: Vec<u32>) -> (u32, (u32, f64)) {
+        let sum: u32 = values.iter().sum();
+
+        (sum, (values.len() as u32, sum as f64 / values.len() as f64))
+    }
+
+    fn notify(&self, _type: &str) {}
+
+    #[dbus_interface(signal)]
+    async fn changed(
+        ctxt: &SignalContext<'_>,
+        properties: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+
+    #[dbus_interface(property)]
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    #[dbus_interface(property)]
+    fn set_count(&mut self, count: u32) {
+        self.count = count;
+    }
+
+    #[dbus_interface(property)]
+    fn version(&self) -> &str {
+        "1.0"
+    }
+}
+
+#[test]
+fn proxy() {
+    let connection = zbus::blocking::ConnectionBuilder::session()
+        .unwrap()
+        .name("org.freedesktop.zbus_xmlgen_macros.Xml")
+        .unwrap()
+        .serve_at("/org/freedesktop/zbus_xmlgen_macros/xml", Xml { count: 0 })
+        .unwrap()
+        .build()
+        .unwrap();
+    let proxy = XmlProxyBlocking::new(&connection).unwrap();
+
+    assert_eq!(proxy.concat("foo", "bar").unwrap(), "foobar");
+    assert_eq!(proxy.stats(&[1, 2, 3]).unwrap(), (6, (3, 2.0)));
+    proxy.notify("whatever").unwrap();
+    proxy.set_count(42).unwrap();
+    assert_eq!(proxy.count().unwrap(), 42);
+    assert_eq!(proxy.version().unwrap(), "1.0");
+    let _ = proxy.receive_changed().unwrap();
+}
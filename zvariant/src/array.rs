@@ -63,6 +63,11 @@ impl<'a> Array<'a> {
         &self.elements
     }
 
+    /// Get all the elements, mutably. The signature of the elements must not be changed.
+    pub(crate) fn get_mut(&mut self) -> &mut [Value<'a>] {
+        &mut self.elements
+    }
+
     /// Get the number of elements.
     pub fn len(&self) -> usize {
         self.elements.len()
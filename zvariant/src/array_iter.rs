@@ -0,0 +1,427 @@
+use serde::de::{
+    self,
+    value::{
+        BoolDeserializer, BorrowedBytesDeserializer, StrDeserializer, U64Deserializer,
+        U8Deserializer,
+    },
+    Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor,
+};
+use static_assertions::assert_impl_all;
+
+use std::{fmt, marker::PhantomData};
+
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+
+#[cfg(feature = "gvariant")]
+use crate::framing_offset_size::FramingOffsetSize;
+use crate::{
+    dbus::Deserializer as DBusDeserializer, signature_parser::SignatureParser, utils::*,
+    EncodingContext, EncodingFormat, Error, Result, Signature, Type,
+};
+
+/// The name [`ArrayIter`] passes to `deserialize_newtype_struct`, to ask our deserializers for the
+/// encoded array instead of its elements.
+pub(crate) const ARRAY_ITER_NAME: &str = "zvariant::ArrayIter";
+
+/// A lazy iterator over the elements of an encoded array.
+///
+/// Deserializing an `ArrayIter` doesn't deserialize (or allocate) any of the elements of the array.
+/// Instead, it borrows the encoded array and each element gets deserialized, directly from the
+/// encoded bytes, as the iterator advances. This makes it possible to go through very large arrays
+/// (e.g to filter them) without allocating them all at once.
+///
+/// Both D-Bus and GVariant formats are supported but since it needs access to the encoded data,
+/// `ArrayIter` can only be deserialized through this crate's deserializers.
+///
+/// Iteration stops after the first element that fails to deserialize.
+///
+/// # Example
+///
+/// ```
+/// use zvariant::{from_slice, to_bytes, ArrayIter, EncodingContext};
+/// use byteorder::LE;
+///
+/// let ctxt = EncodingContext::<LE>::new_dbus(0);
+/// let names = (0..1000u32).map(|i| (i, format!("name{i}"))).collect::<Vec<_>>();
+/// let encoded = to_bytes(ctxt, &names).unwrap();
+///
+/// let iter: ArrayIter<'_, (u32, &str)> = from_slice(&encoded, ctxt).unwrap();
+/// let found = iter
+///     .map(|element| element.unwrap())
+///     .filter(|(i, _)| i % 100 == 0)
+///     .collect::<Vec<_>>();
+/// assert_eq!(found.len(), 10);
+/// assert_eq!(found[1], (100, "name100"));
+/// ```
+pub struct ArrayIter<'de, T> {
+    array: EncodedArray<'de>,
+    element_alignment: usize,
+    // Position of the next element, relative to the start of the array.
+    pos: usize,
+    // Index of the next element.
+    index: usize,
+    // Size and start of the framing offsets of non-fixed-sized elements (GVariant-specific).
+    #[cfg(feature = "gvariant")]
+    offsets: Option<(FramingOffsetSize, usize)>,
+    done: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+assert_impl_all!(ArrayIter<'_, i32>: Send, Sync, Unpin);
+
+impl<'de, T> ArrayIter<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    fn new(array: EncodedArray<'de>) -> Result<Self> {
+        let element_alignment = alignment_for_signature(&array.element_signature, array.format)?;
+        #[cfg(feature = "gvariant")]
+        let offsets = if array.format == EncodingFormat::GVariant
+            && !is_fixed_sized_signature(&array.element_signature)?
+        {
+            let offset_size = FramingOffsetSize::for_encoded_container(array.bytes.len());
+            // The last offset tells us the start of offsets.
+            let offsets_start = offset_size.read_last_offset_from_buffer(array.bytes);
+            if offsets_start > array.bytes.len() {
+                return Err(de::Error::invalid_length(
+                    offsets_start,
+                    &format!("< {}", array.bytes.len()).as_str(),
+                ));
+            }
+
+            Some((offset_size, offsets_start))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            array,
+            element_alignment,
+            pos: 0,
+            index: 0,
+            #[cfg(feature = "gvariant")]
+            offsets,
+            done: false,
+            phantom: PhantomData,
+        })
+    }
+
+    /// The signature of the elements in the encoded array.
+    ///
+    /// Dictionary entries are reported as structures.
+    pub fn element_signature(&self) -> &Signature<'static> {
+        &self.array.element_signature
+    }
+
+    /// If there are no more elements to iterate over.
+    pub fn is_empty(&self) -> bool {
+        self.done || self.element_end().map_or(false, |end| end.is_none())
+    }
+
+    // The end of the next element, or `None` if there are no more elements.
+    fn element_end(&self) -> Result<Option<usize>> {
+        #[cfg(feature = "gvariant")]
+        if let Some((offset_size, offsets_start)) = self.offsets {
+            let offset_pos = offsets_start + self.index * offset_size as usize;
+            if offset_pos >= self.array.bytes.len() {
+                return Ok(None);
+            }
+            let offset_bytes = subslice(
+                self.array.bytes,
+                offset_pos..offset_pos + offset_size as usize,
+            )?;
+            let end = offset_size.read_last_offset_from_buffer(offset_bytes);
+            if end > offsets_start {
+                return Err(de::Error::invalid_length(
+                    end,
+                    &format!("< {offsets_start}").as_str(),
+                ));
+            }
+
+            return Ok(Some(end));
+        }
+
+        if self.pos >= self.array.bytes.len() {
+            Ok(None)
+        } else {
+            Ok(Some(self.array.bytes.len()))
+        }
+    }
+
+    fn next_element<B>(&mut self) -> Result<Option<T>>
+    where
+        B: byteorder::ByteOrder,
+    {
+        let end = match self.element_end()? {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+        let start =
+            self.pos + padding_for_n_bytes(self.array.position + self.pos, self.element_alignment);
+        let bytes = subslice(self.array.bytes, start..end)?;
+        let ctxt = EncodingContext::<B>::new(self.array.format, self.array.position + start);
+        #[cfg(unix)]
+        let fds = Some(&self.array.fds[..]);
+
+        let (element, len) = match self.array.format {
+            #[cfg(feature = "gvariant")]
+            EncodingFormat::GVariant => {
+                let mut de = crate::gvariant::Deserializer::new(
+                    bytes,
+                    #[cfg(unix)]
+                    fds,
+                    &self.array.element_signature,
+                    ctxt,
+                );
+                let element = T::deserialize(&mut de)?;
+
+                (element, de.0.pos)
+            }
+            EncodingFormat::DBus => {
+                let mut de = DBusDeserializer::new(
+                    bytes,
+                    #[cfg(unix)]
+                    fds,
+                    &self.array.element_signature,
+                    ctxt,
+                );
+                let element = T::deserialize(&mut de)?;
+
+                (element, de.0.pos)
+            }
+        };
+        self.pos = start + len;
+        #[cfg(feature = "gvariant")]
+        if self.offsets.is_some() {
+            self.pos = end;
+        }
+        self.index += 1;
+
+        Ok(Some(element))
+    }
+}
+
+impl<'de, T> Iterator for ArrayIter<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let element = if self.array.big_endian {
+            self.next_element::<byteorder::BE>()
+        } else {
+            self.next_element::<byteorder::LE>()
+        };
+        match element {
+            Ok(Some(element)) => Some(Ok(element)),
+            Ok(None) => {
+                self.done = true;
+
+                None
+            }
+            Err(e) => {
+                // We can't know where the next element starts.
+                self.done = true;
+
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'de, T> std::iter::FusedIterator for ArrayIter<'de, T> where T: Deserialize<'de> {}
+
+impl<'de, T> Clone for ArrayIter<'de, T> {
+    fn clone(&self) -> Self {
+        Self {
+            array: self.array.clone(),
+            element_alignment: self.element_alignment,
+            pos: self.pos,
+            index: self.index,
+            #[cfg(feature = "gvariant")]
+            offsets: self.offsets,
+            done: self.done,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> fmt::Debug for ArrayIter<'de, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayIter")
+            .field("array", &self.array)
+            .field("pos", &self.pos)
+            .field("index", &self.index)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<'de, T> Type for ArrayIter<'de, T>
+where
+    T: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ArrayIter<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let array =
+            deserializer.deserialize_newtype_struct(ARRAY_ITER_NAME, EncodedArrayVisitor)?;
+
+        ArrayIter::new(array).map_err(de::Error::custom)
+    }
+}
+
+/// An encoded array, with everything needed to deserialize its elements.
+#[derive(Debug, Clone)]
+pub(crate) struct EncodedArray<'de> {
+    // The elements, starting at the first element and including the framing offsets for GVariant.
+    pub bytes: &'de [u8],
+    // The absolute position of `bytes` in the encoded data.
+    pub position: usize,
+    pub format: EncodingFormat,
+    pub big_endian: bool,
+    // Dictionary entries are encoded like structures so we deserialize them as such.
+    pub element_signature: Signature<'static>,
+    #[cfg(unix)]
+    pub fds: Vec<RawFd>,
+}
+
+impl<'de> EncodedArray<'de> {
+    /// Create an `EncodedArray` from the signature of the array, with the parser at its start.
+    ///
+    /// The actual array contents are filled by the deserializers.
+    pub fn new<B>(sig_parser: &SignatureParser<'_>, format: EncodingFormat) -> Result<Self>
+    where
+        B: byteorder::ByteOrder,
+    {
+        let c = sig_parser.next_char()?;
+        if c != ARRAY_SIGNATURE_CHAR {
+            return Err(de::Error::invalid_type(
+                de::Unexpected::Char(c),
+                &"an array",
+            ));
+        }
+        let signature = sig_parser.next_signature()?;
+        let element_signature = if signature.as_bytes()[1] as char == DICT_ENTRY_SIG_START_CHAR {
+            Signature::from_string_unchecked(format!(
+                "{STRUCT_SIG_START_CHAR}{}{STRUCT_SIG_END_CHAR}",
+                &signature[2..signature.len() - 1],
+            ))
+        } else {
+            signature.slice(1..).to_owned()
+        };
+
+        Ok(Self {
+            bytes: &[],
+            position: 0,
+            format,
+            big_endian: B::read_u16(&[0, 1]) == 1,
+            element_signature,
+            #[cfg(unix)]
+            fds: vec![],
+        })
+    }
+
+    /// Pass `self` to `visitor`.
+    pub fn visit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(EncodedArrayAccess {
+            array: self,
+            field: 0,
+        })
+    }
+}
+
+// Passes the fields of an `EncodedArray` to `EncodedArrayVisitor`, through the serde API.
+struct EncodedArrayAccess<'de> {
+    array: EncodedArray<'de>,
+    field: usize,
+}
+
+impl<'de> SeqAccess<'de> for EncodedArrayAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let array = &self.array;
+        let field = self.field;
+        self.field += 1;
+
+        match field {
+            0 => seed.deserialize(BorrowedBytesDeserializer::new(array.bytes)),
+            1 => seed.deserialize(U64Deserializer::new(array.position as u64)),
+            2 => seed.deserialize(U8Deserializer::new(array.format as u8)),
+            3 => seed.deserialize(BoolDeserializer::new(array.big_endian)),
+            4 => seed.deserialize(StrDeserializer::new(array.element_signature.as_str())),
+            #[cfg(unix)]
+            5 => seed.deserialize(de::value::SeqDeserializer::new(array.fds.iter().copied())),
+            _ => return Ok(None),
+        }
+        .map(Some)
+    }
+}
+
+struct EncodedArrayVisitor;
+
+impl<'de> Visitor<'de> for EncodedArrayVisitor {
+    type Value = EncodedArray<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an array, encoded in D-Bus or GVariant format")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<EncodedArray<'de>, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let field = |i| de::Error::invalid_length(i, &self);
+        let bytes = seq.next_element()?.ok_or_else(|| field(0))?;
+        let position: u64 = seq.next_element()?.ok_or_else(|| field(1))?;
+        let format = match seq.next_element::<u8>()?.ok_or_else(|| field(2))? {
+            0 => EncodingFormat::DBus,
+            #[cfg(feature = "gvariant")]
+            1 => EncodingFormat::GVariant,
+            f => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(f.into()),
+                    &"an encoding format",
+                ))
+            }
+        };
+        let big_endian = seq.next_element()?.ok_or_else(|| field(3))?;
+        let element_signature: String = seq.next_element()?.ok_or_else(|| field(4))?;
+        #[cfg(unix)]
+        let fds = seq.next_element()?.ok_or_else(|| field(5))?;
+
+        Ok(EncodedArray {
+            bytes,
+            position: position as usize,
+            format,
+            big_endian,
+            element_signature: Signature::from_string_unchecked(element_signature),
+            #[cfg(unix)]
+            fds,
+        })
+    }
+}
@@ -77,7 +77,8 @@ impl Basic for bool {
     const SIGNATURE_CHAR: char = 'b';
     const SIGNATURE_STR: &'static str = "b";
 
-    alignment_method!(4);
+    // GVariant booleans are a single byte, unlike D-Bus ones.
+    alignment_method!(4, 1);
 }
 impl_type!(bool);
 
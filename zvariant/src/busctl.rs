@@ -0,0 +1,331 @@
+use std::convert::TryFrom;
+
+#[cfg(unix)]
+use crate::Fd;
+use crate::{
+    signature_parser::SignatureParser, Array, Basic, Dict, Error, ObjectPath, OwnedValue, Result,
+    Signature, Str, StructureBuilder, Value, ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_START_CHAR,
+    STRUCT_SIG_END_CHAR, STRUCT_SIG_START_CHAR, VARIANT_SIGNATURE_CHAR,
+};
+#[cfg(feature = "gvariant")]
+use crate::{Maybe, MAYBE_SIGNATURE_CHAR};
+
+/// Parse command-line arguments in the `busctl call` syntax into values.
+///
+/// `signature` can consist of any number of complete types and one value is returned for each.
+/// The syntax is the same as that of sd-bus's `busctl` utility:
+///
+/// * Basic types are given as a single argument. Booleans can be given as `true`/`false`,
+///   `yes`/`no`, `on`/`off` or `1`/`0` and integers in decimal or hexadecimal (`0x` prefix).
+/// * Arrays are given as the number of elements, followed by the elements.
+/// * Dictionaries are given as the number of entries, followed by the key and value of each entry.
+/// * Structures are given as their fields, one after another.
+/// * Variants are given as the signature of the contained value, followed by the value.
+/// * File descriptors are given as their numeric value.
+/// * Maybe types (GVariant only) are given as `0` for nothing or as `1`, followed by the value.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use std::collections::HashMap;
+/// use zvariant::{from_busctl_args, Signature, Value};
+///
+/// let signature = Signature::try_from("a{sv}u").unwrap();
+/// let args = ["2", "foo", "s", "bar", "baz", "i", "5", "42"];
+/// let values = from_busctl_args(&signature, args).unwrap();
+/// assert_eq!(values.len(), 2);
+///
+/// let dict = HashMap::<String, Value<'_>>::try_from(values[0].clone()).unwrap();
+/// assert_eq!(dict["foo"], Value::new("bar"));
+/// assert_eq!(dict["baz"], Value::new(5i32));
+/// assert_eq!(*values[1], Value::U32(42));
+///
+/// // Not enough arguments.
+/// assert!(from_busctl_args(&signature, ["1", "foo"]).is_err());
+/// ```
+pub fn from_busctl_args<I, S>(signature: &Signature<'_>, args: I) -> Result<Vec<OwnedValue>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut parser = ArgsParser {
+        args: args.into_iter(),
+        index: 0,
+    };
+    let mut signature_parser = SignatureParser::new(signature.clone());
+    let mut values = vec![];
+    while !signature_parser.done() {
+        let signature = signature_parser.parse_next_signature()?;
+        values.push(parser.parse_value(&signature)?.into());
+    }
+    if parser.args.next().is_some() {
+        return Err(Error::Message(format!(
+            "Too many arguments for signature `{signature}`, expected {}",
+            parser.index,
+        )));
+    }
+
+    Ok(values)
+}
+
+/// Print `values` as command-line arguments in the `busctl call` syntax.
+///
+/// This is the inverse of [`from_busctl_args`]: parsing the returned arguments, using the
+/// concatenated signatures of `values`, gives back the same values.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{to_busctl_args, Value};
+///
+/// let values = [Value::from(vec!["hello", "world"]), Value::new(Value::from(7u8))];
+/// assert_eq!(to_busctl_args(&values), ["2", "hello", "world", "y", "7"]);
+/// ```
+pub fn to_busctl_args(values: &[Value<'_>]) -> Vec<String> {
+    let mut args = vec![];
+    for value in values {
+        print_value(value, &mut args);
+    }
+
+    args
+}
+
+fn print_value(value: &Value<'_>, args: &mut Vec<String>) {
+    match value {
+        Value::U8(v) => args.push(v.to_string()),
+        Value::Bool(v) => args.push(v.to_string()),
+        Value::I16(v) => args.push(v.to_string()),
+        Value::U16(v) => args.push(v.to_string()),
+        Value::I32(v) => args.push(v.to_string()),
+        Value::U32(v) => args.push(v.to_string()),
+        Value::I64(v) => args.push(v.to_string()),
+        Value::U64(v) => args.push(v.to_string()),
+        Value::F64(v) => args.push(v.to_string()),
+        Value::Str(v) => args.push(v.to_string()),
+        Value::Signature(v) => args.push(v.to_string()),
+        Value::ObjectPath(v) => args.push(v.to_string()),
+        Value::Value(v) => {
+            args.push(v.value_signature().to_string());
+            print_value(v, args);
+        }
+        Value::Array(array) => {
+            args.push(array.len().to_string());
+            for element in array.get() {
+                print_value(element, args);
+            }
+        }
+        Value::Dict(dict) => {
+            args.push(dict.iter().count().to_string());
+            for (key, value) in dict.iter() {
+                print_value(key, args);
+                print_value(value, args);
+            }
+        }
+        Value::Structure(structure) => {
+            for field in structure.fields() {
+                print_value(field, args);
+            }
+        }
+        #[cfg(feature = "gvariant")]
+        Value::Maybe(maybe) => match maybe.inner() {
+            Some(value) => {
+                args.push("1".to_string());
+                print_value(value, args);
+            }
+            None => args.push("0".to_string()),
+        },
+        #[cfg(unix)]
+        Value::Fd(fd) => args.push(fd.to_string()),
+    }
+}
+
+struct ArgsParser<I> {
+    args: I,
+    // Number of arguments consumed so far.
+    index: usize,
+}
+
+impl<I, S> ArgsParser<I>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    fn next_arg(&mut self, signature: &Signature<'_>) -> Result<S> {
+        let arg = self.args.next().ok_or_else(|| {
+            Error::Message(format!(
+                "Missing argument {} for signature `{signature}`",
+                self.index,
+            ))
+        })?;
+        self.index += 1;
+
+        Ok(arg)
+    }
+
+    fn invalid_arg(&self, arg: &str, signature: &Signature<'_>) -> Error {
+        Error::Message(format!(
+            "Invalid argument {} `{arg}` for signature `{signature}`",
+            self.index - 1,
+        ))
+    }
+
+    fn parse_value(&mut self, signature: &Signature<'_>) -> Result<Value<'static>> {
+        let first = signature
+            .as_bytes()
+            .first()
+            .map(|b| *b as char)
+            .ok_or_else(|| {
+                Error::SignatureMismatch(signature.to_owned(), "a non-empty signature".to_string())
+            })?;
+
+        let value = match first {
+            ARRAY_SIGNATURE_CHAR => {
+                let len = self.parse_len(signature)?;
+                if signature.as_bytes().get(1) == Some(&(DICT_ENTRY_SIG_START_CHAR as u8)) {
+                    // `a{` + key signature + value signature + `}`
+                    let key_signature = signature.slice(2..3);
+                    let value_signature = signature.slice(3..signature.len() - 1);
+                    let mut dict = Dict::new_full_signature(signature.to_owned());
+                    for _ in 0..len {
+                        let key = self.parse_value(&key_signature)?;
+                        let value = self.parse_value(&value_signature)?;
+                        dict.append(key, value)?;
+                    }
+
+                    Value::Dict(dict)
+                } else {
+                    let element_signature = signature.slice(1..);
+                    let mut array = Array::new_full_signature(signature.to_owned());
+                    for _ in 0..len {
+                        array.append(self.parse_value(&element_signature)?)?;
+                    }
+
+                    Value::Array(array)
+                }
+            }
+            STRUCT_SIG_START_CHAR => {
+                let mut parser = SignatureParser::new(signature.clone());
+                parser.skip_char()?;
+                let mut builder = StructureBuilder::new();
+                while parser.next_char()? != STRUCT_SIG_END_CHAR {
+                    let field_signature = parser.parse_next_signature()?;
+                    builder = builder.append_field(self.parse_value(&field_signature)?);
+                }
+
+                Value::Structure(builder.build_with_signature(signature.to_owned()))
+            }
+            VARIANT_SIGNATURE_CHAR => {
+                let arg = self.next_arg(signature)?;
+                let child_signature = Signature::try_from(arg.as_ref())
+                    .map(|s| s.to_owned())
+                    .map_err(|_| self.invalid_arg(arg.as_ref(), signature))?;
+                let child_len = SignatureParser::new(child_signature.clone())
+                    .parse_next_signature()
+                    .map(|s| s.len());
+                if child_len.ok() != Some(child_signature.len()) {
+                    return Err(self.invalid_arg(arg.as_ref(), signature));
+                }
+
+                Value::Value(Box::new(self.parse_value(&child_signature)?))
+            }
+            #[cfg(feature = "gvariant")]
+            MAYBE_SIGNATURE_CHAR => {
+                let child_signature = signature.slice(1..);
+                let maybe = match self.parse_len(signature)? {
+                    0 => Maybe::nothing_full_signature(signature.to_owned()),
+                    1 => Maybe::just_full_signature(
+                        self.parse_value(&child_signature)?,
+                        signature.to_owned(),
+                    ),
+                    n => return Err(self.invalid_arg(&n.to_string(), signature)),
+                };
+
+                Value::Maybe(maybe)
+            }
+            _ => {
+                let arg = self.next_arg(signature)?;
+                self.parse_basic(arg.as_ref(), first, signature)?
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn parse_len(&mut self, signature: &Signature<'_>) -> Result<usize> {
+        let arg = self.next_arg(signature)?;
+
+        parse_integer(arg.as_ref())
+            .and_then(|len| usize::try_from(len).ok())
+            .ok_or_else(|| self.invalid_arg(arg.as_ref(), signature))
+    }
+
+    fn parse_basic(
+        &self,
+        arg: &str,
+        signature_char: char,
+        signature: &Signature<'_>,
+    ) -> Result<Value<'static>> {
+        let invalid = || self.invalid_arg(arg, signature);
+        let integer = || parse_integer(arg).ok_or_else(invalid);
+
+        let value = match signature_char {
+            u8::SIGNATURE_CHAR => Value::U8(u8::try_from(integer()?).map_err(|_| invalid())?),
+            bool::SIGNATURE_CHAR => Value::Bool(parse_bool(arg).ok_or_else(invalid)?),
+            i16::SIGNATURE_CHAR => Value::I16(i16::try_from(integer()?).map_err(|_| invalid())?),
+            u16::SIGNATURE_CHAR => Value::U16(u16::try_from(integer()?).map_err(|_| invalid())?),
+            i32::SIGNATURE_CHAR => Value::I32(i32::try_from(integer()?).map_err(|_| invalid())?),
+            u32::SIGNATURE_CHAR => Value::U32(u32::try_from(integer()?).map_err(|_| invalid())?),
+            i64::SIGNATURE_CHAR => Value::I64(i64::try_from(integer()?).map_err(|_| invalid())?),
+            u64::SIGNATURE_CHAR => Value::U64(u64::try_from(integer()?).map_err(|_| invalid())?),
+            f64::SIGNATURE_CHAR => Value::F64(arg.parse().map_err(|_| invalid())?),
+            <&str>::SIGNATURE_CHAR => Value::Str(Str::from(arg.to_string())),
+            ObjectPath::SIGNATURE_CHAR => {
+                Value::ObjectPath(ObjectPath::try_from(arg.to_string()).map_err(|_| invalid())?)
+            }
+            Signature::SIGNATURE_CHAR => {
+                Value::Signature(Signature::try_from(arg.to_string()).map_err(|_| invalid())?)
+            }
+            #[cfg(unix)]
+            Fd::SIGNATURE_CHAR => {
+                Value::Fd(Fd::from(i32::try_from(integer()?).map_err(|_| invalid())?))
+            }
+            c => {
+                return Err(serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Char(c),
+                    &"a valid signature character",
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+fn parse_bool(arg: &str) -> Option<bool> {
+    match arg {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+// Parses decimal and hexadecimal (`0x` prefix) integers.
+fn parse_integer(arg: &str) -> Option<i128> {
+    let (negative, digits) = match arg.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, arg.strip_prefix('+').unwrap_or(arg)),
+    };
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let v = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i128>().ok()?,
+    };
+
+    Some(if negative { -v } else { v })
+}
@@ -9,8 +9,11 @@ use std::{marker::PhantomData, str};
 use std::os::unix::io::RawFd;
 
 use crate::{
-    de::ValueParseStage, signature_parser::SignatureParser, utils::*, Basic, EncodingContext,
-    EncodingFormat, Error, ObjectPath, Result, Signature,
+    array_iter::{EncodedArray, ARRAY_ITER_NAME},
+    de::ValueParseStage,
+    signature_parser::SignatureParser,
+    utils::*,
+    Basic, EncodingContext, EncodingFormat, Error, ObjectPath, Result, Signature,
 };
 
 #[cfg(unix)]
@@ -258,10 +261,14 @@ where
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == ARRAY_ITER_NAME {
+            return deserialize_encoded_array(self, visitor);
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -293,13 +300,13 @@ where
 
                 self.0.sig_parser.skip_char()?;
 
-                visitor.visit_seq(StructureDeserializer { de: self })
+                visitor.visit_seq(StructureDeserializer { de: self, index: 0 })
             }
             u8::SIGNATURE_CHAR => {
                 // Empty struct: encoded as a `0u8`.
                 let _: u8 = serde::Deserialize::deserialize(&mut *self)?;
 
-                visitor.visit_seq(StructureDeserializer { de: self })
+                visitor.visit_seq(StructureDeserializer { de: self, index: 0 })
             }
             c => Err(de::Error::invalid_type(
                 de::Unexpected::Char(c),
@@ -311,15 +318,24 @@ where
         }
     }
 
+    #[cfg_attr(not(unix), allow(unused_variables))]
     fn deserialize_enum<V>(
         self,
         name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        #[cfg(unix)]
+        if name == OS_STRING_NAME
+            && variants == OS_STRING_VARIANTS
+            && self.0.sig_parser.next_signature()? == OS_STRING_SIGNATURE
+        {
+            return visitor.visit_enum(crate::de::OsStringEnum(self));
+        }
+
         let signature = self.0.sig_parser.next_signature()?;
         let alignment = alignment_for_signature(&signature, self.0.ctxt.format())?;
         self.0.parse_padding(alignment)?;
@@ -371,6 +387,10 @@ struct ArrayDeserializer<'d, 'de, 'sig, 'f, B> {
     element_alignment: usize,
     // where value signature starts
     element_signature_len: usize,
+    is_dict: bool,
+    // index and position of the current element
+    index: usize,
+    element_pos: usize,
 }
 
 impl<'d, 'de, 'sig, 'f, B> ArrayDeserializer<'d, 'de, 'sig, 'f, B>
@@ -390,7 +410,8 @@ where
         de.0.parse_padding(element_alignment)?;
         let start = de.0.pos;
 
-        if de.0.sig_parser.next_char()? == DICT_ENTRY_SIG_START_CHAR {
+        let is_dict = de.0.sig_parser.next_char()? == DICT_ENTRY_SIG_START_CHAR;
+        if is_dict {
             de.0.sig_parser.skip_char()?;
             element_signature_len -= 1;
         }
@@ -401,6 +422,9 @@ where
             start,
             element_alignment,
             element_signature_len,
+            is_dict,
+            index: 0,
+            element_pos: start,
         })
     }
 
@@ -408,7 +432,7 @@ where
     where
         T: DeserializeSeed<'de>,
     {
-        let ctxt = EncodingContext::new_dbus(self.de.0.ctxt.position() + self.de.0.pos);
+        let ctxt = EncodingContext::new_dbus(self.de.0.abs_pos());
 
         let mut de = Deserializer::<B>(crate::DeserializerCommon {
             ctxt,
@@ -451,8 +475,53 @@ where
         }
 
         self.de.0.parse_padding(self.element_alignment)?;
+        self.element_pos = self.de.0.pos;
+        self.index += 1;
+
+        self.next(seed, sig_parser).map(Some).map_err(|e| {
+            let offset = self.de.0.ctxt.position() + self.element_pos;
 
-        self.next(seed, sig_parser).map(Some)
+            e.in_element(|| self.index_segment(), offset)
+        })
+    }
+
+    // The path segment of the current element, by its index.
+    fn index_segment(&self) -> String {
+        format!("{}[{}]", self.signature_segment(), self.index - 1)
+    }
+
+    // The path segment of the value of the current dict entry, by its key.
+    fn key_segment(&self) -> String {
+        let ctxt = EncodingContext::<B>::new_dbus(self.de.0.ctxt.position() + self.element_pos);
+        let key = subslice(self.de.0.bytes, self.element_pos..).and_then(|bytes| {
+            let mut de = Deserializer::<B>(crate::DeserializerCommon {
+                ctxt,
+                sig_parser: self.de.0.sig_parser.clone(),
+                bytes,
+                fds: self.de.0.fds,
+                pos: 0,
+                container_depths: self.de.0.container_depths,
+                b: PhantomData,
+            });
+
+            de::Deserializer::deserialize_any(&mut de, crate::de::KeySegmentVisitor)
+        });
+
+        format!(
+            "{}{}",
+            self.signature_segment(),
+            key.unwrap_or_else(|_| String::from("[?]")),
+        )
+    }
+
+    fn signature_segment(&self) -> String {
+        let signature = self.de.0.sig_parser.signature();
+        let element_signature = &signature[..self.element_signature_len];
+        if self.is_dict {
+            format!(".a{{{element_signature}")
+        } else {
+            format!(".a{element_signature}")
+        }
     }
 
     fn done(&self) -> bool {
@@ -475,6 +544,32 @@ where
     de.0.next_slice(len)
 }
 
+// Deserialize the encoded array as is, for `ArrayIter`.
+fn deserialize_encoded_array<'de, B, V>(
+    de: &mut Deserializer<'de, '_, '_, B>,
+    visitor: V,
+) -> Result<V::Value>
+where
+    B: byteorder::ByteOrder,
+    V: Visitor<'de>,
+{
+    let mut array = EncodedArray::new::<B>(&de.0.sig_parser, EncodingFormat::DBus)?;
+
+    de.0.sig_parser.skip_char()?;
+    let ad = ArrayDeserializer::new(de)?;
+    let len = ad.len;
+    let element_signature_len = ad.element_signature_len;
+    de.0.sig_parser.skip_chars(element_signature_len)?;
+    array.position = de.0.abs_pos();
+    array.bytes = de.0.next_slice(len)?;
+    #[cfg(unix)]
+    if let Some(fds) = de.0.fds {
+        array.fds = fds.to_vec();
+    }
+
+    array.visit(visitor)
+}
+
 struct ArraySeqDeserializer<'d, 'de, 'sig, 'f, B>(ArrayDeserializer<'d, 'de, 'sig, 'f, B>);
 
 impl<'d, 'de, 'sig, 'f, B> SeqAccess<'de> for ArraySeqDeserializer<'d, 'de, 'sig, 'f, B>
@@ -515,13 +610,17 @@ where
         let mut sig_parser = self.0.de.0.sig_parser.clone();
         // Skip key signature (always 1 char)
         sig_parser.skip_char()?;
-        self.0.next(seed, sig_parser)
+        let offset = self.0.de.0.abs_pos();
+        self.0
+            .next(seed, sig_parser)
+            .map_err(|e| e.in_element(|| self.0.key_segment(), offset))
     }
 }
 
 #[derive(Debug)]
 struct StructureDeserializer<'d, 'de, 'sig, 'f, B> {
     de: &'d mut Deserializer<'de, 'sig, 'f, B>,
+    index: usize,
 }
 
 impl<'d, 'de, 'sig, 'f, B> SeqAccess<'de> for StructureDeserializer<'d, 'de, 'sig, 'f, B>
@@ -534,7 +633,13 @@ where
     where
         T: DeserializeSeed<'de>,
     {
-        let v = seed.deserialize(&mut *self.de).map(Some);
+        let offset = self.de.0.abs_pos();
+        let index = self.index;
+        self.index += 1;
+        let v = seed
+            .deserialize(&mut *self.de)
+            .map(Some)
+            .map_err(|e| e.in_element(|| format!("[{index}]"), offset));
 
         if self.de.0.sig_parser.next_char()? == STRUCT_SIG_END_CHAR {
             // Last item in the struct
@@ -227,16 +227,25 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(unix), allow(unused_variables))]
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
+        #[cfg(unix)]
+        if name == OS_STRING_NAME
+            && variant == OS_STRING_VARIANTS[0]
+            && self.0.sig_parser.next_signature()? == OS_STRING_SIGNATURE
+        {
+            return value.serialize(self);
+        }
+
         self.0.prep_serialize_enum_variant(variant_index)?;
         value.serialize(&mut *self)?;
         // Skip the `)`.
@@ -98,7 +98,7 @@ where
 /// use serde::{Deserialize, Serialize};
 ///
 /// use zvariant::{to_bytes_for_signature, from_slice_for_signature};
-/// use zvariant::EncodingContext;
+/// use zvariant::{EncodingContext, SignatureBuilder};
 ///
 /// let ctxt = EncodingContext::<byteorder::LE>::new_dbus(0);
 /// #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -134,8 +134,13 @@ where
 ///     Struct { y: u8, t: u64 },
 /// }
 ///
-/// // TODO: Provide convenience API to create complex signatures
-/// let signature = "(u(yt))".try_into().unwrap();
+/// let signature = SignatureBuilder::new()
+///     .add::<u32>()
+///     .add::<(u8, u64)>()
+///     .build_struct()
+///     .to_signature()
+///     .unwrap();
+/// assert_eq!(signature, "(u(yt))");
 /// let encoded = to_bytes_for_signature(ctxt, &signature, &Structs::Tuple(42, 42)).unwrap();
 /// assert_eq!(encoded.len(), 24);
 /// let decoded: Structs = from_slice_for_signature(&encoded, ctxt, &signature).unwrap();
@@ -597,6 +602,37 @@ where
     }
 }
 
+// Formats the (basic type) key of a dict entry as a path segment, for error reporting.
+pub(crate) struct KeySegmentVisitor;
+
+impl<'de> Visitor<'de> for KeySegmentVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a dict entry key")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<String, E> {
+        Ok(format!("[{v}]"))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<String, E> {
+        Ok(format!("[{v}]"))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<String, E> {
+        Ok(format!("[{v}]"))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> std::result::Result<String, E> {
+        Ok(format!("[{v}]"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<String, E> {
+        Ok(format!("[{v:?}]"))
+    }
+}
+
 pub(crate) trait GetDeserializeCommon<'de, 'sig, 'f, B>
 where
     B: byteorder::ByteOrder,
@@ -606,6 +642,71 @@ where
         Self: 'd;
 }
 
+// Access to the `Unix` variant of `OsString`, which is encoded as just its bytes (see
+// `OS_STRING_NAME`).
+#[cfg(unix)]
+pub(crate) struct OsStringEnum<D>(pub(crate) D);
+
+#[cfg(unix)]
+impl<'de, D> de::EnumAccess<'de> for OsStringEnum<D>
+where
+    D: de::Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(de::IntoDeserializer::<Error>::into_deserializer(0u32))?;
+
+        Ok((variant, self))
+    }
+}
+
+#[cfg(unix)]
+impl<'de, D> VariantAccess<'de> for OsStringEnum<D>
+where
+    D: de::Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
+
 // Enum handling is very generic so it can be here and specific deserializers can use this.
 pub(crate) struct Enum<B, D> {
     pub(crate) de: D,
@@ -4,7 +4,7 @@ use std::marker::PhantomData;
 use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
 use static_assertions::assert_impl_all;
 
-use crate::{Signature, Type, Value};
+use crate::{value::ValueSeed, Signature, Type, Value};
 
 /// A wrapper to deserialize a value to `T: Type + Deserialize`.
 ///
@@ -80,3 +80,72 @@ impl<'de, T: Type + Deserialize<'de>> Type for DeserializeValue<'de, T> {
         Value::signature()
     }
 }
+
+/// A wrapper to deserialize a value to `T: Type + Deserialize`, if it's of type `T`.
+///
+/// Unlike [`DeserializeValue`], a value of another type is skipped, giving `None`. Used by
+/// [`DeserializeDict`] derived implementations.
+///
+/// [`DeserializeDict`]: derive.DeserializeDict.html
+#[doc(hidden)]
+pub struct DeserializeValueOpt<'de, T: Type + Deserialize<'de>>(
+    pub Option<T>,
+    std::marker::PhantomData<&'de T>,
+);
+
+impl<'de, T: Type + Deserialize<'de>> Deserialize<'de> for DeserializeValueOpt<'de, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["zvariant::Value::Signature", "zvariant::Value::Value"];
+        Ok(DeserializeValueOpt(
+            deserializer.deserialize_struct(
+                "zvariant::Value",
+                FIELDS,
+                DeserializeValueOptVisitor(PhantomData),
+            )?,
+            PhantomData,
+        ))
+    }
+}
+
+struct DeserializeValueOptVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Type + Deserialize<'de>> Visitor<'de> for DeserializeValueOptVisitor<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("zvariant::Value")
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let signature: Signature<'de> = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+        if signature != T::signature() {
+            // The value still needs to be consumed.
+            let seed = ValueSeed::<Value<'_>> {
+                signature,
+                phantom: PhantomData,
+            };
+            seq.next_element_seed(seed)?
+                .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+
+            return Ok(None);
+        }
+
+        seq.next_element()?
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::invalid_length(1, &self))
+    }
+}
+
+impl<'de, T: Type + Deserialize<'de>> Type for DeserializeValueOpt<'de, T> {
+    fn signature() -> Signature<'static> {
+        Value::signature()
+    }
+}
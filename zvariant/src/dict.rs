@@ -1,4 +1,8 @@
-use std::{collections::HashMap, convert::TryFrom, hash::BuildHasher};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    hash::BuildHasher,
+};
 
 use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
 use static_assertions::assert_impl_all;
@@ -7,10 +11,38 @@ use crate::{Basic, DynamicType, Error, Signature, Type, Value};
 
 /// A helper type to wrap dictionaries in a [`Value`].
 ///
-/// API is provided to convert from, and to a [`HashMap`].
+/// API is provided to convert from, and to a [`HashMap`], a [`BTreeMap`] and (with the `indexmap`
+/// feature) an [`IndexMap`], as well as to access and modify the entries like a map. Entries are
+/// kept in the order they were added (or in the order of the map `self` was created from) and the
+/// signatures of all keys and values are checked against the ones of the `Dict`.
+///
+/// # Example
+///
+/// ```
+/// use zvariant::{Dict, Type, Value};
+///
+/// let mut dict = Dict::new(<&str>::signature(), Value::signature());
+/// dict.insert("Volume", Value::from(50u8)).unwrap();
+/// dict.insert("Title", Value::from("Lazy")).unwrap();
+/// assert!(dict.contains_key("Title").unwrap());
+/// // Keys must have the key signature of the dictionary.
+/// assert!(dict.contains_key(42u32).is_err());
+///
+/// // Insert only if the key doesn't exist yet.
+/// dict.entry("Volume").unwrap().or_insert(Value::from(100u8)).unwrap();
+/// dict.entry("Muted").unwrap().or_insert(Value::from(false)).unwrap();
+/// assert_eq!(dict.len(), 3);
+/// assert_eq!(dict.get::<str, u8>("Volume").unwrap(), Some(&50));
+///
+/// let title = dict.remove("Title").unwrap();
+/// assert_eq!(title, Some(Value::new(Value::from("Lazy"))));
+/// assert_eq!(dict.keys().collect::<Vec<_>>(), [&Value::from("Volume"), &Value::from("Muted")]);
+/// ```
 ///
 /// [`Value`]: enum.Value.html#variant.Dict
 /// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+/// [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
+/// [`IndexMap`]: https://docs.rs/indexmap/1/indexmap/map/struct.IndexMap.html
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dict<'k, 'v> {
     entries: Vec<DictEntry<'k, 'v>>,
@@ -97,6 +129,114 @@ impl<'k, 'v> Dict<'k, 'v> {
         Ok(None)
     }
 
+    /// Whether there is an entry for the given key.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of `K` doesn't match the key signature `self` was created for.
+    pub fn contains_key<'q, K>(&self, key: K) -> Result<bool, Error>
+    where
+        K: Basic + Into<Value<'q>>,
+    {
+        check_child_value_signature!(self.key_signature, K::signature(), "key");
+
+        Ok(self.position(&key.into()).is_some())
+    }
+
+    /// Insert `key` and `value`, replacing the current value for `key`, if any.
+    ///
+    /// Unlike [`Dict::add`], this ensures keys are unique. The replaced value is returned.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of `K` or [`value.dynamic_signature()`] doesn't match the key or value
+    /// signature `self` was created for, respectively.
+    ///
+    /// [`value.dynamic_signature()`]: trait.DynamicType.html#tymethod.dynamic_signature
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<Value<'v>>, Error>
+    where
+        K: Basic + Into<Value<'k>>,
+        V: Into<Value<'v>> + DynamicType,
+    {
+        match self.entry(key)? {
+            DictMapEntry::Occupied(mut entry) => entry.insert(value).map(Some),
+            DictMapEntry::Vacant(entry) => entry.insert(value).map(|_| None),
+        }
+    }
+
+    /// Remove the entry for the given key, returning its value.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of `K` doesn't match the key signature `self` was created for.
+    pub fn remove<'q, K>(&mut self, key: K) -> Result<Option<Value<'v>>, Error>
+    where
+        K: Basic + Into<Value<'q>>,
+    {
+        check_child_value_signature!(self.key_signature, K::signature(), "key");
+
+        Ok(self
+            .position(&key.into())
+            .map(|i| self.entries.remove(i).value))
+    }
+
+    /// Get the entry for the given key, for in-place manipulation.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of `K` doesn't match the key signature `self` was created for.
+    pub fn entry<K>(&mut self, key: K) -> Result<DictMapEntry<'_, 'k, 'v>, Error>
+    where
+        K: Basic + Into<Value<'k>>,
+    {
+        check_child_value_signature!(self.key_signature, K::signature(), "key");
+
+        let key = Value::new(key);
+        let entry = match self.position(&key) {
+            Some(index) => DictMapEntry::Occupied(OccupiedDictEntry { dict: self, index }),
+            None => DictMapEntry::Vacant(VacantDictEntry { dict: self, key }),
+        };
+
+        Ok(entry)
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries, as key-value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&Value<'k>, &Value<'v>)> {
+        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Iterate over the entries, with mutable values. The signature of the values must not be
+    /// changed.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&Value<'k>, &mut Value<'v>)> {
+        self.entries
+            .iter_mut()
+            .map(|entry| (&entry.key, &mut entry.value))
+    }
+
+    /// Iterate over the keys.
+    pub fn keys(&self) -> impl Iterator<Item = &Value<'k>> {
+        self.entries.iter().map(|entry| &entry.key)
+    }
+
+    /// Iterate over the values.
+    pub fn values(&self) -> impl Iterator<Item = &Value<'v>> {
+        self.entries.iter().map(|entry| &entry.value)
+    }
+
+    fn position(&self, key: &Value<'_>) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.key == *key)
+    }
+
     /// Get the signature of this `Dict`.
     ///
     /// NB: This method potentially allocates and copies. Use [`full_signature`] if you'd like to
@@ -134,7 +274,61 @@ impl<'k, 'v> Dict<'k, 'v> {
         }
     }
 
-    // TODO: Provide more API like https://docs.rs/toml/0.5.5/toml/map/struct.Map.html
+    // Convert the entries to a map of the given types.
+    fn try_into_map<M, K, V>(self) -> Result<M, Error>
+    where
+        M: std::iter::FromIterator<(K, V)>,
+        K: TryFrom<Value<'k>>,
+        V: TryFrom<Value<'v>>,
+        K::Error: Into<crate::Error>,
+        V::Error: Into<crate::Error>,
+    {
+        self.entries
+            .into_iter()
+            .map(|e| {
+                let key = if let Value::Value(v) = e.key {
+                    K::try_from(*v)
+                } else {
+                    K::try_from(e.key)
+                }
+                .map_err(Into::into)?;
+
+                let value = if let Value::Value(v) = e.value {
+                    V::try_from(*v)
+                } else {
+                    V::try_from(e.value)
+                }
+                .map_err(Into::into)?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    // Create a `Dict` from key-value pairs of the given types.
+    fn from_pairs<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Type + Into<Value<'k>>,
+        V: Type + Into<Value<'v>>,
+    {
+        let entries = pairs
+            .into_iter()
+            .map(|(key, value)| DictEntry {
+                key: Value::new(key),
+                value: Value::new(value),
+            })
+            .collect();
+        let key_signature = K::signature();
+        let value_signature = V::signature();
+        let signature = create_signature(&key_signature, &value_signature);
+
+        Self {
+            entries,
+            key_signature,
+            value_signature,
+            signature,
+        }
+    }
 }
 
 impl<'k, 'v> Serialize for Dict<'k, 'v> {
@@ -163,25 +357,7 @@ where
     type Error = Error;
 
     fn try_from(v: Dict<'k, 'v>) -> Result<Self, Self::Error> {
-        let mut map = HashMap::default();
-        for e in v.entries.into_iter() {
-            let key = if let Value::Value(v) = e.key {
-                K::try_from(*v)
-            } else {
-                K::try_from(e.key)
-            }
-            .map_err(Into::into)?;
-
-            let value = if let Value::Value(v) = e.value {
-                V::try_from(*v)
-            } else {
-                V::try_from(e.value)
-            }
-            .map_err(Into::into)?;
-
-            map.insert(key, value);
-        }
-        Ok(map)
+        v.try_into_map()
     }
 }
 
@@ -196,27 +372,218 @@ where
     H: BuildHasher + Default,
 {
     fn from(value: HashMap<K, V, H>) -> Self {
-        let entries = value
-            .into_iter()
-            .map(|(key, value)| DictEntry {
-                key: Value::new(key),
-                value: Value::new(value),
-            })
-            .collect();
-        let key_signature = K::signature();
-        let value_signature = V::signature();
-        let signature = create_signature(&key_signature, &value_signature);
+        Self::from_pairs(value)
+    }
+}
 
-        Self {
-            entries,
-            key_signature,
-            value_signature,
-            signature,
+// Conversion of Dict to BTreeMap
+impl<'k, 'v, K, V> TryFrom<Dict<'k, 'v>> for BTreeMap<K, V>
+where
+    K: Basic + TryFrom<Value<'k>> + std::cmp::Ord,
+    V: TryFrom<Value<'v>>,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = Error;
+
+    fn try_from(v: Dict<'k, 'v>) -> Result<Self, Self::Error> {
+        v.try_into_map()
+    }
+}
+
+// Conversion of BTreeMap to Dict
+impl<'k, 'v, K, V> From<BTreeMap<K, V>> for Dict<'k, 'v>
+where
+    K: Type + Into<Value<'k>> + std::cmp::Ord,
+    V: Type + Into<Value<'v>>,
+{
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Self::from_pairs(value)
+    }
+}
+
+// Conversion of Dict to IndexMap, keeping the order of the entries
+#[cfg(feature = "indexmap")]
+impl<'k, 'v, K, V, H> TryFrom<Dict<'k, 'v>> for indexmap::IndexMap<K, V, H>
+where
+    K: Basic + TryFrom<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    V: TryFrom<Value<'v>>,
+    H: BuildHasher + Default,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = Error;
+
+    fn try_from(v: Dict<'k, 'v>) -> Result<Self, Self::Error> {
+        v.try_into_map()
+    }
+}
+
+// Conversion of IndexMap to Dict, keeping the order of the entries
+#[cfg(feature = "indexmap")]
+impl<'k, 'v, K, V, H> From<indexmap::IndexMap<K, V, H>> for Dict<'k, 'v>
+where
+    K: Type + Into<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    V: Type + Into<Value<'v>>,
+    H: BuildHasher + Default,
+{
+    fn from(value: indexmap::IndexMap<K, V, H>) -> Self {
+        Self::from_pairs(value)
+    }
+}
+
+/// A view into a single entry of a [`Dict`], which may either be vacant or occupied.
+///
+/// This is constructed from the [`Dict::entry`] method.
+#[derive(Debug)]
+pub enum DictMapEntry<'a, 'k, 'v> {
+    /// An occupied entry.
+    Occupied(OccupiedDictEntry<'a, 'k, 'v>),
+    /// A vacant entry.
+    Vacant(VacantDictEntry<'a, 'k, 'v>),
+}
+
+impl<'a, 'k, 'v> DictMapEntry<'a, 'k, 'v> {
+    /// The key of this entry.
+    pub fn key(&self) -> &Value<'k> {
+        match self {
+            DictMapEntry::Occupied(entry) => entry.key(),
+            DictMapEntry::Vacant(entry) => entry.key(),
         }
     }
+
+    /// Insert `default` if the entry is vacant and return the value of the entry.
+    ///
+    /// # Errors
+    ///
+    /// If `default` needs to be inserted but its signature doesn't match the value signature of
+    /// the `Dict`.
+    pub fn or_insert<V>(self, default: V) -> Result<&'a Value<'v>, Error>
+    where
+        V: Into<Value<'v>> + DynamicType,
+    {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the value returned by `default` if the entry is vacant and return the value of the
+    /// entry.
+    ///
+    /// # Errors
+    ///
+    /// If `default` needs to be inserted but its signature doesn't match the value signature of
+    /// the `Dict`.
+    pub fn or_insert_with<V, F>(self, default: F) -> Result<&'a Value<'v>, Error>
+    where
+        V: Into<Value<'v>> + DynamicType,
+        F: FnOnce() -> V,
+    {
+        match self {
+            DictMapEntry::Occupied(entry) => Ok(entry.into_ref()),
+            DictMapEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+}
+
+/// An occupied entry of a [`Dict`].
+#[derive(Debug)]
+pub struct OccupiedDictEntry<'a, 'k, 'v> {
+    dict: &'a mut Dict<'k, 'v>,
+    index: usize,
+}
+
+impl<'a, 'k, 'v> OccupiedDictEntry<'a, 'k, 'v> {
+    /// The key of this entry.
+    pub fn key(&self) -> &Value<'k> {
+        &self.dict.entries[self.index].key
+    }
+
+    /// The value of this entry.
+    pub fn get(&self) -> &Value<'v> {
+        &self.dict.entries[self.index].value
+    }
+
+    /// Convert into a reference to the value of this entry.
+    pub fn into_ref(self) -> &'a Value<'v> {
+        &self.dict.entries[self.index].value
+    }
+
+    /// Replace the value of this entry, returning the old value.
+    ///
+    /// # Errors
+    ///
+    /// If [`value.dynamic_signature()`] doesn't match the value signature of the `Dict`.
+    ///
+    /// [`value.dynamic_signature()`]: trait.DynamicType.html#tymethod.dynamic_signature
+    pub fn insert<V>(&mut self, value: V) -> Result<Value<'v>, Error>
+    where
+        V: Into<Value<'v>> + DynamicType,
+    {
+        check_child_value_signature!(
+            self.dict.value_signature,
+            value.dynamic_signature(),
+            "value"
+        );
+
+        Ok(std::mem::replace(
+            &mut self.dict.entries[self.index].value,
+            Value::new(value),
+        ))
+    }
+
+    /// Remove this entry from the `Dict`, returning its value.
+    pub fn remove(self) -> Value<'v> {
+        self.dict.entries.remove(self.index).value
+    }
 }
 
-// TODO: Conversion of Dict from/to BTreeMap
+/// A vacant entry of a [`Dict`].
+#[derive(Debug)]
+pub struct VacantDictEntry<'a, 'k, 'v> {
+    dict: &'a mut Dict<'k, 'v>,
+    key: Value<'k>,
+}
+
+impl<'a, 'k, 'v> VacantDictEntry<'a, 'k, 'v> {
+    /// The key of this entry.
+    pub fn key(&self) -> &Value<'k> {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> Value<'k> {
+        self.key
+    }
+
+    /// Insert `value` for the key of this entry, returning a reference to it.
+    ///
+    /// # Errors
+    ///
+    /// If [`value.dynamic_signature()`] doesn't match the value signature of the `Dict`.
+    ///
+    /// [`value.dynamic_signature()`]: trait.DynamicType.html#tymethod.dynamic_signature
+    pub fn insert<V>(self, value: V) -> Result<&'a Value<'v>, Error>
+    where
+        V: Into<Value<'v>> + DynamicType,
+    {
+        check_child_value_signature!(
+            self.dict.value_signature,
+            value.dynamic_signature(),
+            "value"
+        );
+
+        self.dict.entries.push(DictEntry {
+            key: self.key,
+            value: Value::new(value),
+        });
+
+        Ok(&self
+            .dict
+            .entries
+            .last()
+            .expect("just pushed an entry")
+            .value)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 struct DictEntry<'k, 'v> {
@@ -0,0 +1,113 @@
+use serde::{de::DeserializeOwned, ser::SerializeMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+use crate::{EncodingContext, OwnedValue, Result, Signature};
+
+/// Types that can be serialized as entries of an `a{sv}` dictionary.
+///
+/// This is what allows a field to be flattened into the dictionary of a [`SerializeDict`] derived
+/// struct, through the `#[zvariant(flatten)]` attribute. [`SerializeDict`] implements this trait for
+/// `a{sv}` structs and it's implemented for string-keyed maps of [`OwnedValue`].
+///
+/// [`SerializeDict`]: derive.SerializeDict.html
+pub trait SerializeDictEntries {
+    /// Serialize the entries of `self` into `map`.
+    fn serialize_dict_entries<M>(&self, map: &mut M) -> std::result::Result<(), M::Error>
+    where
+        M: SerializeMap;
+}
+
+/// Types that can be deserialized from entries of an `a{sv}` dictionary.
+///
+/// This is what allows a field to be flattened into the dictionary of a [`DeserializeDict`]
+/// derived struct, through the `#[zvariant(flatten)]` attribute. Flattened fields are built in
+/// order, from the entries that didn't match any other field and weren't used by previous flattened
+/// fields. [`DeserializeDict`] implements this trait for `a{sv}` structs without generics and it's
+/// implemented for string-keyed maps of [`OwnedValue`], which use all the remaining entries.
+///
+/// [`DeserializeDict`]: derive.DeserializeDict.html
+pub trait DeserializeDictEntries: Sized {
+    /// Build `Self` from `entries`, removing the entries it uses.
+    fn deserialize_dict_entries(entries: &mut HashMap<String, OwnedValue>) -> Result<Self>;
+
+    /// Remove the entries `Self` would use from `entries`.
+    ///
+    /// By default, all entries are removed.
+    fn remove_dict_entries(entries: &mut HashMap<String, OwnedValue>) {
+        entries.clear();
+    }
+}
+
+impl<S> SerializeDictEntries for HashMap<String, OwnedValue, S>
+where
+    S: BuildHasher,
+{
+    fn serialize_dict_entries<M>(&self, map: &mut M) -> std::result::Result<(), M::Error>
+    where
+        M: SerializeMap,
+    {
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> DeserializeDictEntries for HashMap<String, OwnedValue, S>
+where
+    S: BuildHasher + Default,
+{
+    fn deserialize_dict_entries(entries: &mut HashMap<String, OwnedValue>) -> Result<Self> {
+        Ok(std::mem::take(entries).into_iter().collect())
+    }
+}
+
+impl SerializeDictEntries for BTreeMap<String, OwnedValue> {
+    fn serialize_dict_entries<M>(&self, map: &mut M) -> std::result::Result<(), M::Error>
+    where
+        M: SerializeMap,
+    {
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializeDictEntries for BTreeMap<String, OwnedValue> {
+    fn deserialize_dict_entries(entries: &mut HashMap<String, OwnedValue>) -> Result<Self> {
+        Ok(std::mem::take(entries).into_iter().collect())
+    }
+}
+
+/// Deserialize `T` from `entries`, by encoding them as an `a{sv}` dictionary first.
+///
+/// Used by [`DeserializeDict`] derived implementations of [`DeserializeDictEntries`].
+///
+/// [`DeserializeDict`]: derive.DeserializeDict.html
+#[doc(hidden)]
+pub fn from_dict_entries<T>(entries: &HashMap<String, OwnedValue>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let ctxt = EncodingContext::<byteorder::NativeEndian>::new_dbus(0);
+    let signature = Signature::from_static_str_unchecked("a{sv}");
+
+    #[cfg(unix)]
+    {
+        let (bytes, fds) = crate::to_bytes_fds(ctxt, entries)?;
+
+        crate::from_slice_fds_for_signature(&bytes, Some(&fds), ctxt, &signature)
+    }
+    #[cfg(not(unix))]
+    {
+        let bytes = crate::to_bytes(ctxt, entries)?;
+
+        crate::from_slice_for_signature(&bytes, ctxt, &signature)
+    }
+}
@@ -0,0 +1,103 @@
+use serde::de::{Deserialize, Deserializer};
+use static_assertions::assert_impl_all;
+
+use std::fmt;
+
+use crate::{ArrayIter, Result, Signature, Type};
+
+/// A lazy iterator over the entries of an encoded dictionary.
+///
+/// This is the dictionary counterpart of [`ArrayIter`]: deserializing a `DictIter` doesn't
+/// deserialize (or allocate) any of the entries. Instead, each entry gets deserialized directly from
+/// the encoded bytes, as a key-value pair, when the iterator advances. Keys are not checked for
+/// uniqueness.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{from_slice, to_bytes, DictIter, EncodingContext, Value};
+/// use byteorder::LE;
+///
+/// let ctxt = EncodingContext::<LE>::new_dbus(0);
+/// let mut dict = HashMap::new();
+/// dict.insert("Volume", Value::from(50u8));
+/// dict.insert("Title", Value::from("Lazy"));
+/// let encoded = to_bytes(ctxt, &dict).unwrap();
+///
+/// let iter: DictIter<'_, &str, Value<'_>> = from_slice(&encoded, ctxt).unwrap();
+/// let title = iter
+///     .map(|entry| entry.unwrap())
+///     .find(|(key, _)| *key == "Title")
+///     .map(|(_, value)| value);
+/// assert_eq!(title, Some(Value::from("Lazy")));
+/// ```
+pub struct DictIter<'de, K, V>(ArrayIter<'de, (K, V)>);
+
+assert_impl_all!(DictIter<'_, i32, i32>: Send, Sync, Unpin);
+
+impl<'de, K, V> DictIter<'de, K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    /// If there are no more entries to iterate over.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'de, K, V> Iterator for DictIter<'de, K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Item = Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<'de, K, V> std::iter::FusedIterator for DictIter<'de, K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+}
+
+impl<'de, K, V> Clone for DictIter<'de, K, V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'de, K, V> fmt::Debug for DictIter<'de, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DictIter").field(&self.0).finish()
+    }
+}
+
+impl<'de, K, V> Type for DictIter<'de, K, V>
+where
+    K: Type,
+    V: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        Signature::from_string_unchecked(format!("a{{{}{}}}", K::signature(), V::signature()))
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for DictIter<'de, K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ArrayIter::deserialize(deserializer).map(Self)
+    }
+}
@@ -10,6 +10,10 @@ pub enum EncodingFormat {
     /// format.
     DBus,
     /// [GVariant](https://developer.gnome.org/glib/stable/glib-GVariant.html) format.
+    ///
+    /// **Note:** Booleans are encoded as a single byte, as the specification requires and GLib
+    /// does. Older versions of zvariant encoded them as 4 bytes, like D-Bus does, and data
+    /// encoded by them can't be decoded anymore.
     #[cfg(feature = "gvariant")]
     GVariant,
 }
@@ -33,6 +33,10 @@ impl fmt::Display for MaxDepthExceeded {
 }
 
 /// Error type used by zvariant API.
+///
+/// Errors that occur while deserializing an element of a container are wrapped in
+/// [`Error::Context`], to tell where the error occurred. Hence, to check what kind of error
+/// occurred, match on [`Error::root_cause`] rather than on the error itself.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
@@ -63,6 +67,140 @@ pub enum Error {
     OutOfBounds,
     /// The maximum allowed depth for containers in encoding was exceeded.
     MaxDepthExceeded(MaxDepthExceeded),
+    /// An error (first argument) occurred while deserializing an element of a container, at the
+    /// location described by the second argument.
+    ///
+    /// The first argument is never an `Error::Context` itself.
+    Context(Box<Error>, ErrorContext),
+}
+
+/// The location of a deserialization error in the encoded data.
+///
+/// # Example
+///
+/// ```
+/// use std::{collections::HashMap, convert::TryFrom};
+/// use zvariant::{from_slice_for_signature, to_bytes, EncodingContext, ObjectPath, Signature};
+/// use byteorder::LE;
+///
+/// let ctxt = EncodingContext::<LE>::new_dbus(0);
+/// let mut dict = HashMap::new();
+/// dict.insert("Metadata", vec!["/a", "b"]);
+/// let encoded = to_bytes(ctxt, &(42u32, dict)).unwrap();
+///
+/// // `b` is not a valid object path.
+/// let signature = Signature::try_from("(ua{sas})").unwrap();
+/// let err = from_slice_for_signature::<_, (u32, HashMap<&str, Vec<ObjectPath<'_>>>)>(
+///     &encoded,
+///     ctxt,
+///     &signature,
+/// )
+/// .unwrap_err();
+/// let context = err.context().unwrap();
+/// assert_eq!(context.path(), r#"[1].a{sas}["Metadata"].as[1]"#);
+/// assert_eq!(context.offset(), 36);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    path: String,
+    offset: usize,
+}
+
+impl ErrorContext {
+    /// The path to the element that failed to deserialize.
+    ///
+    /// The path is made of segments for each nested container: `[n]` for the n-th field of a
+    /// structure and `.<signature>[n]` or `.<signature>[key]` for the n-th element of an array or
+    /// the value of a dictionary entry, respectively.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The byte offset of the innermost element that failed to deserialize.
+    ///
+    /// The offset is relative to the start of the encoded data, including the position of the
+    /// [`EncodingContext`](crate::EncodingContext).
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` (offset {})", self.path, self.offset)
+    }
+}
+
+impl Error {
+    /// The location of the error, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Context(_, context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without its location.
+    ///
+    /// This is what to match on, to check the kind of the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use zvariant::{from_slice_for_signature, to_bytes, EncodingContext, Error, Signature};
+    /// use byteorder::LE;
+    ///
+    /// let ctxt = EncodingContext::<LE>::new_dbus(0);
+    /// let encoded = to_bytes(ctxt, &vec![1u32, 2]).unwrap();
+    ///
+    /// // `2` is not a valid boolean, and the error is wrapped in its location in the array.
+    /// let signature = Signature::try_from("ab").unwrap();
+    /// let err = from_slice_for_signature::<_, Vec<bool>>(&encoded, ctxt, &signature).unwrap_err();
+    /// assert_eq!(err.context().unwrap().path(), ".ab[1]");
+    /// assert!(matches!(err.root_cause(), Error::Message(_)));
+    /// ```
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Context(e, _) => e,
+            e => e,
+        }
+    }
+
+    /// Prefix the path of the error location with `prefix`.
+    ///
+    /// This is a no-op if the location of the error is not known.
+    pub fn prefix_path(self, prefix: &str) -> Self {
+        match self {
+            Error::Context(e, mut context) => {
+                context.path.insert_str(0, prefix);
+
+                Error::Context(e, context)
+            }
+            e => e,
+        }
+    }
+
+    // Add the path segment of the container element at `offset`, that failed to deserialize.
+    pub(crate) fn in_element<F>(self, segment: F, offset: usize) -> Self
+    where
+        F: FnOnce() -> String,
+    {
+        match self {
+            Error::Context(e, mut context) => {
+                context.path.insert_str(0, &segment());
+
+                Error::Context(e, context)
+            }
+            e => Error::Context(
+                Box::new(e),
+                ErrorContext {
+                    path: segment(),
+                    offset,
+                },
+            ),
+        }
+    }
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
@@ -77,6 +215,9 @@ impl PartialEq for Error {
             (Error::PaddingNot0(p), Error::PaddingNot0(other)) => p == other,
             (Error::UnknownFd, Error::UnknownFd) => true,
             (Error::MaxDepthExceeded(max1), Error::MaxDepthExceeded(max2)) => max1 == max2,
+            (Error::Context(e1, context1), Error::Context(e2, context2)) => {
+                e1 == e2 && context1 == context2
+            }
             (_, _) => false,
         }
     }
@@ -89,6 +230,7 @@ impl error::Error for Error {
             Error::Io(e) => Some(e),
             Error::InputOutput(e) => Some(e),
             Error::Utf8(e) => Some(e),
+            Error::Context(e, _) => Some(e),
             _ => None,
         }
     }
@@ -122,6 +264,7 @@ impl fmt::Display for Error {
                 "Out of bounds range specified",
             ),
             Error::MaxDepthExceeded(max) => write!(f, "{max}"),
+            Error::Context(e, context) => write!(f, "{e} at {context}"),
         }
     }
 }
@@ -146,6 +289,7 @@ impl Clone for Error {
             }
             Error::OutOfBounds => Error::OutOfBounds,
             Error::MaxDepthExceeded(max) => Error::MaxDepthExceeded(*max),
+            Error::Context(e, context) => Error::Context(e.clone(), context.clone()),
         }
     }
 }
@@ -8,7 +8,11 @@ use crate::{
 #[cfg(unix)]
 use crate::Fd;
 
-use std::{collections::HashMap, convert::TryFrom, hash::BuildHasher};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    hash::BuildHasher,
+};
 
 macro_rules! value_try_from {
     ($kind:ident, $to:ty) => {
@@ -158,6 +162,44 @@ where
     }
 }
 
+impl<'a, K, V> TryFrom<Value<'a>> for BTreeMap<K, V>
+where
+    K: crate::Basic + TryFrom<Value<'a>> + std::cmp::Ord,
+    V: TryFrom<Value<'a>>,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = crate::Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        if let Value::Dict(v) = value {
+            Self::try_from(v)
+        } else {
+            Err(crate::Error::IncorrectType)
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'a, K, V, H> TryFrom<Value<'a>> for indexmap::IndexMap<K, V, H>
+where
+    K: crate::Basic + TryFrom<Value<'a>> + std::hash::Hash + std::cmp::Eq,
+    V: TryFrom<Value<'a>>,
+    H: BuildHasher + Default,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = crate::Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        if let Value::Dict(v) = value {
+            Self::try_from(v)
+        } else {
+            Err(crate::Error::IncorrectType)
+        }
+    }
+}
+
 // This would be great but somehow it conflicts with some blanket generic implementations from
 // core:
 //
@@ -9,9 +9,13 @@ use std::{ffi::CStr, marker::PhantomData, str};
 use std::os::unix::io::RawFd;
 
 use crate::{
-    de::ValueParseStage, framing_offset_size::FramingOffsetSize, framing_offsets::FramingOffsets,
-    signature_parser::SignatureParser, utils::*, Basic, EncodingContext, EncodingFormat, Error,
-    Result, Signature,
+    array_iter::{EncodedArray, ARRAY_ITER_NAME},
+    de::ValueParseStage,
+    framing_offset_size::FramingOffsetSize,
+    framing_offsets::FramingOffsets,
+    signature_parser::SignatureParser,
+    utils::*,
+    Basic, EncodingContext, EncodingFormat, Error, Result, Signature,
 };
 
 /// Our GVariant deserialization implementation.
@@ -110,7 +114,26 @@ where
         crate::de::deserialize_any::<Self, V>(self, c, visitor)
     }
 
-    deserialize_basic!(deserialize_bool);
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Unlike D-Bus booleans, GVariant ones are a single byte.
+        let v = self.0.next_const_size_slice::<bool>()?[0];
+        let b = match v {
+            1 => true,
+            0 => false,
+            _ => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(v as u64),
+                    &"0 or 1",
+                ))
+            }
+        };
+
+        visitor.visit_bool(b)
+    }
+
     deserialize_basic!(deserialize_i8);
     deserialize_basic!(deserialize_i16);
     deserialize_basic!(deserialize_i32);
@@ -266,10 +289,14 @@ where
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == ARRAY_ITER_NAME {
+            return deserialize_encoded_array(self, visitor);
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -313,6 +340,7 @@ where
                     end,
                     offsets_len: 0,
                     offset_size,
+                    index: 0,
                 });
                 self.0.container_depths = self.0.container_depths.dec_structure();
 
@@ -330,6 +358,7 @@ where
                     end,
                     offsets_len: 0,
                     offset_size: FramingOffsetSize::U8,
+                    index: 0,
                 })
             }
             c => Err(de::Error::invalid_type(
@@ -342,15 +371,24 @@ where
         }
     }
 
+    #[cfg_attr(not(unix), allow(unused_variables))]
     fn deserialize_enum<V>(
         self,
         name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        #[cfg(unix)]
+        if name == OS_STRING_NAME
+            && variants == OS_STRING_VARIANTS
+            && self.0.sig_parser.next_signature()? == OS_STRING_SIGNATURE
+        {
+            return visitor.visit_enum(crate::de::OsStringEnum(self));
+        }
+
         let signature = self.0.sig_parser.next_signature()?;
         let alignment = alignment_for_signature(&signature, self.0.ctxt.format())?;
         self.0.parse_padding(alignment)?;
@@ -397,6 +435,35 @@ where
     de.0.next_slice(len)
 }
 
+// Deserialize the encoded array as is, for `ArrayIter`.
+fn deserialize_encoded_array<'de, B, V>(
+    de: &mut Deserializer<'de, '_, '_, B>,
+    visitor: V,
+) -> Result<V::Value>
+where
+    B: byteorder::ByteOrder,
+    V: Visitor<'de>,
+{
+    let mut array = EncodedArray::new::<B>(&de.0.sig_parser, EncodingFormat::GVariant)?;
+
+    let element_signature_len = de.0.sig_parser.next_signature()?.len() - 1;
+    de.0.sig_parser.skip_char()?;
+    let element_signature = de.0.sig_parser.next_signature()?;
+    let element_alignment = alignment_for_signature(&element_signature, EncodingFormat::GVariant)?;
+    de.0.sig_parser.skip_chars(element_signature_len)?;
+
+    // The array takes up the rest of the container.
+    de.0.parse_padding(element_alignment)?;
+    array.position = de.0.abs_pos();
+    array.bytes = de.0.next_slice(de.0.bytes.len() - de.0.pos)?;
+    #[cfg(unix)]
+    if let Some(fds) = de.0.fds {
+        array.fds = fds.to_vec();
+    }
+
+    array.visit(visitor)
+}
+
 struct ArrayDeserializer<'d, 'de, 'sig, 'f, B> {
     de: &'d mut Deserializer<'de, 'sig, 'f, B>,
     len: usize,
@@ -411,6 +478,11 @@ struct ArrayDeserializer<'d, 'de, 'sig, 'f, B> {
     offsets_len: usize,
     // size of the framing offset of last dict-entry key read (GVariant-specific)
     key_offset_size: Option<FramingOffsetSize>,
+    is_dict: bool,
+    // index of the current element and range of the last dict-entry key read
+    index: usize,
+    key_start: usize,
+    key_end: usize,
 }
 
 impl<'d, 'de, 'sig, 'f, B> ArrayDeserializer<'d, 'de, 'sig, 'f, B>
@@ -459,7 +531,8 @@ where
         };
         let start = de.0.pos;
 
-        if de.0.sig_parser.next_char()? == DICT_ENTRY_SIG_START_CHAR {
+        let is_dict = de.0.sig_parser.next_char()? == DICT_ENTRY_SIG_START_CHAR;
+        if is_dict {
             de.0.sig_parser.skip_char()?;
         }
 
@@ -472,6 +545,10 @@ where
             offsets,
             offsets_len,
             key_offset_size,
+            is_dict,
+            index: 0,
+            key_start: start,
+            key_end: start,
         })
     }
 
@@ -497,6 +574,48 @@ where
             None => self.de.0.pos == self.start + self.len,
         }
     }
+
+    // The path segment of the current element, by its index.
+    fn index_segment(&self) -> String {
+        format!("{}[{}]", self.signature_segment(), self.index - 1)
+    }
+
+    // The path segment of the value of the current dict entry, by its key.
+    fn key_segment(&self) -> String {
+        let ctxt = EncodingContext::<B>::new(
+            self.de.0.ctxt.format(),
+            self.de.0.ctxt.position() + self.key_start,
+        );
+        let key = subslice(self.de.0.bytes, self.key_start..self.key_end).and_then(|bytes| {
+            let mut de = Deserializer::<B>(crate::DeserializerCommon {
+                ctxt,
+                sig_parser: self.de.0.sig_parser.clone(),
+                bytes,
+                fds: self.de.0.fds,
+                pos: 0,
+                container_depths: self.de.0.container_depths,
+                b: PhantomData,
+            });
+
+            de::Deserializer::deserialize_any(&mut de, crate::de::KeySegmentVisitor)
+        });
+
+        format!(
+            "{}{}",
+            self.signature_segment(),
+            key.unwrap_or_else(|_| String::from("[?]")),
+        )
+    }
+
+    fn signature_segment(&self) -> String {
+        let signature = self.de.0.sig_parser.signature();
+        if self.is_dict {
+            // Starting bracket was already skipped
+            format!(".a{{{}", &signature[..self.element_signature_len - 1])
+        } else {
+            format!(".a{}", &signature[..self.element_signature_len])
+        }
+    }
 }
 
 impl<'d, 'de, 'sig, 'f, B> SeqAccess<'de> for ArrayDeserializer<'d, 'de, 'sig, 'f, B>
@@ -525,6 +644,7 @@ where
             self.de.0.ctxt.position() + self.de.0.pos,
         );
         let end = self.element_end(true)?;
+        self.index += 1;
 
         let mut de = Deserializer::<B>(crate::DeserializerCommon {
             ctxt,
@@ -536,7 +656,10 @@ where
             b: PhantomData,
         });
 
-        let v = seed.deserialize(&mut de).map(Some);
+        let v = seed
+            .deserialize(&mut de)
+            .map(Some)
+            .map_err(|e| e.in_element(|| self.index_segment(), ctxt.position()));
         self.de.0.pos += de.0.pos;
         // No need for retaking the container depths as the child can't be incomplete.
 
@@ -592,6 +715,9 @@ where
             }
             None => element_end,
         };
+        self.index += 1;
+        self.key_start = self.de.0.pos;
+        self.key_end = key_end;
 
         let mut de = Deserializer::<B>(crate::DeserializerCommon {
             ctxt,
@@ -602,7 +728,10 @@ where
             container_depths: self.de.0.container_depths,
             b: PhantomData,
         });
-        let v = seed.deserialize(&mut de).map(Some);
+        let v = seed
+            .deserialize(&mut de)
+            .map(Some)
+            .map_err(|e| e.in_element(|| self.index_segment(), ctxt.position()));
         self.de.0.pos += de.0.pos;
         // No need for retaking the container depths as the key can't be incomplete.
 
@@ -642,7 +771,9 @@ where
             container_depths: self.de.0.container_depths,
             b: PhantomData,
         });
-        let v = seed.deserialize(&mut de);
+        let v = seed
+            .deserialize(&mut de)
+            .map_err(|e| e.in_element(|| self.key_segment(), ctxt.position()));
         self.de.0.pos += de.0.pos;
         // No need for retaking the container depths as the value can't be incomplete.
 
@@ -670,6 +801,7 @@ struct StructureDeserializer<'d, 'de, 'sig, 'f, B> {
     offsets_len: usize,
     // size of the framing offset
     offset_size: FramingOffsetSize,
+    index: usize,
 }
 
 impl<'d, 'de, 'sig, 'f, B> SeqAccess<'de> for StructureDeserializer<'d, 'de, 'sig, 'f, B>
@@ -727,7 +859,12 @@ where
             container_depths: self.de.0.container_depths,
             b: PhantomData,
         });
-        let v = seed.deserialize(&mut de).map(Some);
+        let index = self.index;
+        self.index += 1;
+        let v = seed
+            .deserialize(&mut de)
+            .map(Some)
+            .map_err(|e| e.in_element(|| format!("[{index}]"), ctxt.position()));
         self.de.0.pos += de.0.pos;
         // No need for retaking the container depths as the field can't be incomplete.
 
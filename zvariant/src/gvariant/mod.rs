@@ -2,3 +2,7 @@ mod de;
 pub use de::*;
 mod ser;
 pub use ser::*;
+mod text;
+pub use text::*;
+mod normal_form;
+pub use normal_form::*;
@@ -0,0 +1,440 @@
+use std::convert::TryFrom;
+
+#[cfg(unix)]
+use crate::Fd;
+use crate::{
+    container_depths::ContainerDepths, framing_offset_size::FramingOffsetSize,
+    signature_parser::SignatureParser, utils::*, Basic, EncodingFormat, Error, ObjectPath, Result,
+    Signature,
+};
+
+/// Check if `bytes` is the GVariant encoding of a value of type `signature`, in [normal form].
+///
+/// The GVariant format allows some freedom in how values are encoded, e.g the padding bytes don't
+/// have to be zero and framing offsets may overlap or point out of bounds. Moreover, decoders are
+/// expected to accept invalid data, reading the affected values as the default value of their
+/// type. Only data in normal form has a single valid interpretation and is guaranteed to be the
+/// same as what any encoder would produce for the same value. Hence it's important to ensure normal
+/// form before hashing or signing encoded data.
+///
+/// This is equivalent to GLib's `g_variant_is_normal_form`, assuming the data is correctly
+/// aligned.
+///
+/// # Errors
+///
+/// If `signature` isn't a single complete type or is nested too deep.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use byteorder::LE;
+/// use zvariant::{gvariant::is_normal_form, to_bytes, EncodingContext, Signature};
+///
+/// let ctxt = EncodingContext::<LE>::new_gvariant(0);
+/// let encoded = to_bytes(ctxt, &("hello", 42u32)).unwrap();
+/// let signature = Signature::try_from("(su)").unwrap();
+/// assert!(is_normal_form(&encoded, &signature).unwrap());
+///
+/// // Non-zero padding byte.
+/// let mut encoded = encoded.to_vec();
+/// encoded[6] = 1;
+/// assert!(!is_normal_form(&encoded, &signature).unwrap());
+/// ```
+///
+/// [normal form]: https://developer.gnome.org/documentation/specifications/gvariant-specification-1.0.html#normal-form
+pub fn is_normal_form(bytes: &[u8], signature: &Signature<'_>) -> Result<bool> {
+    // Normalization leaves data in normal form untouched and changes everything else.
+    to_normal_form(bytes, signature).map(|normal| normal == bytes)
+}
+
+/// Get the [normal form] of `bytes`, the GVariant encoding of a value of type `signature`.
+///
+/// Any invalid data is read the same way as GLib does, as the default value of its type (e.g
+/// `false`, `0`, an empty string or array) and everything is re-encoded in normal form. This
+/// never fails because of the data itself, so the result can be safely decoded, compared or
+/// hashed.
+///
+/// This is equivalent to GLib's `g_variant_get_normal_form`, assuming the data is correctly
+/// aligned.
+///
+/// # Errors
+///
+/// If `signature` isn't a single complete type or is nested too deep.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use zvariant::{gvariant::{is_normal_form, to_normal_form}, Signature};
+///
+/// // An array of two strings, the last one missing its nul terminator.
+/// let encoded = b"a\0bc\x02\x04";
+/// let signature = Signature::try_from("as").unwrap();
+/// assert!(!is_normal_form(encoded, &signature).unwrap());
+/// // The invalid string is read as an empty one.
+/// assert_eq!(to_normal_form(encoded, &signature).unwrap(), b"a\0\0\x02\x03");
+///
+/// // Booleans must be either 0 or 1.
+/// let signature = Signature::try_from("b").unwrap();
+/// assert_eq!(to_normal_form(&[7], &signature).unwrap(), [1]);
+/// ```
+///
+/// [normal form]: https://developer.gnome.org/documentation/specifications/gvariant-specification-1.0.html#normal-form
+pub fn to_normal_form(bytes: &[u8], signature: &Signature<'_>) -> Result<Vec<u8>> {
+    if !is_single_complete_type(signature) {
+        return Err(Error::SignatureMismatch(
+            signature.to_owned(),
+            "a single complete type".to_string(),
+        ));
+    }
+
+    let mut normal = Vec::with_capacity(bytes.len());
+    normalize(signature, bytes, ContainerDepths::default(), &mut normal)?;
+
+    Ok(normal)
+}
+
+// Append the normal form of `data`, a value of type `signature`, to `out`.
+//
+// Since `out` starts with the top-level value and every container is aligned at least as much as
+// its children, the alignment of the position in `out` is the alignment relative to the container.
+fn normalize(
+    signature: &Signature<'_>,
+    data: &[u8],
+    depths: ContainerDepths,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    if let Some(size) = fixed_size(signature)? {
+        if data.len() != size {
+            // Fixed-sized values of the wrong size are read as the default value, i.e all zeros.
+            out.resize(out.len() + size, 0);
+
+            return Ok(());
+        }
+    }
+
+    match signature.as_bytes()[0] as char {
+        bool::SIGNATURE_CHAR => out.push((data[0] != 0) as u8),
+        u8::SIGNATURE_CHAR
+        | i16::SIGNATURE_CHAR
+        | u16::SIGNATURE_CHAR
+        | i32::SIGNATURE_CHAR
+        | u32::SIGNATURE_CHAR
+        | i64::SIGNATURE_CHAR
+        | u64::SIGNATURE_CHAR
+        | f64::SIGNATURE_CHAR => out.extend_from_slice(data),
+        #[cfg(unix)]
+        Fd::SIGNATURE_CHAR => out.extend_from_slice(data),
+        c @ (<&str>::SIGNATURE_CHAR | ObjectPath::SIGNATURE_CHAR | Signature::SIGNATURE_CHAR) => {
+            normalize_string(c, data, out)
+        }
+        VARIANT_SIGNATURE_CHAR => normalize_variant(data, depths, out)?,
+        ARRAY_SIGNATURE_CHAR => normalize_array(signature, data, depths.inc_array()?, out)?,
+        MAYBE_SIGNATURE_CHAR => normalize_maybe(signature, data, depths.inc_maybe()?, out)?,
+        STRUCT_SIG_START_CHAR | DICT_ENTRY_SIG_START_CHAR => {
+            normalize_structure(signature, data, depths.inc_structure()?, out)?
+        }
+        c => {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Char(c),
+                &"a valid signature character",
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize_string(c: char, data: &[u8], out: &mut Vec<u8>) {
+    let valid = match data.split_last() {
+        Some((0, s)) if !s.contains(&0) => match std::str::from_utf8(s) {
+            Ok(s) if c == ObjectPath::SIGNATURE_CHAR => ObjectPath::try_from(s).is_ok(),
+            Ok(s) if c == Signature::SIGNATURE_CHAR => Signature::try_from(s).is_ok(),
+            Ok(_) => true,
+            Err(_) => false,
+        },
+        _ => false,
+    };
+
+    if valid {
+        out.extend_from_slice(data);
+    } else if c == ObjectPath::SIGNATURE_CHAR {
+        out.extend_from_slice(b"/\0");
+    } else {
+        out.push(b'\0');
+    }
+}
+
+fn normalize_variant(data: &[u8], depths: ContainerDepths, out: &mut Vec<u8>) -> Result<()> {
+    // The child value is followed by a zero byte and the signature of the child. A missing or
+    // invalid signature makes it a unit value.
+    let child = data.iter().rposition(|b| *b == 0).and_then(|separator| {
+        let signature = std::str::from_utf8(&data[separator + 1..]).ok()?;
+        let signature = Signature::try_from(signature).ok()?;
+
+        is_single_complete_type(&signature).then(|| (signature, &data[..separator]))
+    });
+    let (signature, child) =
+        child.unwrap_or_else(|| (Signature::from_static_str_unchecked("()"), &[]));
+
+    // Since the child signature comes from the data, so does its depth. Just like GLib, we read
+    // children nested too deep as unit values as well.
+    let start = out.len();
+    let signature = match depths
+        .inc_variant()
+        .and_then(|depths| normalize(&signature, child, depths, out))
+    {
+        Ok(()) => signature,
+        Err(Error::MaxDepthExceeded(_)) => {
+            out.truncate(start);
+            out.push(b'\0');
+
+            Signature::from_static_str_unchecked("()")
+        }
+        Err(e) => return Err(e),
+    };
+    out.push(b'\0');
+    out.extend_from_slice(signature.as_bytes());
+
+    Ok(())
+}
+
+fn normalize_array(
+    signature: &Signature<'_>,
+    data: &[u8],
+    depths: ContainerDepths,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let element_signature = signature.slice(1..);
+    let alignment = alignment_for_signature(&element_signature, EncodingFormat::GVariant)?;
+
+    if let Some(size) = fixed_size(&element_signature)? {
+        // A size that's not a multiple of the element size makes an empty array.
+        if data.len() % size != 0 {
+            return Ok(());
+        }
+
+        if is_numeric(&element_signature) {
+            // Any bytes are valid and there is no padding.
+            out.extend_from_slice(data);
+        } else {
+            for element in data.chunks(size) {
+                normalize(&element_signature, element, depths, out)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    // The last framing offset is the end of the last element, i.e the start of the offsets.
+    let offset_size = FramingOffsetSize::for_encoded_container(data.len());
+    let offsets_start = offset_size.read_last_offset_from_buffer(data);
+    if offsets_start > data.len() || (data.len() - offsets_start) % offset_size as usize != 0 {
+        return Ok(());
+    }
+
+    let start = out.len();
+    let mut element_start = 0;
+    let mut ends = vec![];
+    for offset in data[offsets_start..].chunks(offset_size as usize) {
+        let element_end = offset_size.read_last_offset_from_buffer(offset);
+        let element = data
+            .get(element_start..element_end)
+            .filter(|_| element_end <= offsets_start)
+            .unwrap_or_default();
+
+        pad(out, alignment);
+        normalize(&element_signature, element, depths, out)?;
+        ends.push(out.len() - start);
+
+        element_start = element_end + padding_for_n_bytes(element_end, alignment);
+    }
+
+    write_offsets(out, start, ends)
+}
+
+fn normalize_maybe(
+    signature: &Signature<'_>,
+    data: &[u8],
+    depths: ContainerDepths,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let child_signature = signature.slice(1..);
+
+    match fixed_size(&child_signature)? {
+        // Anything of a size other than the child's is `Nothing`.
+        Some(size) if data.len() == size => normalize(&child_signature, data, depths, out),
+        Some(_) => Ok(()),
+        // Variable-sized children are followed by a zero byte.
+        None => match data.split_last() {
+            Some((_, child)) => {
+                normalize(&child_signature, child, depths, out)?;
+                out.push(b'\0');
+
+                Ok(())
+            }
+            None => Ok(()),
+        },
+    }
+}
+
+fn normalize_structure(
+    signature: &Signature<'_>,
+    data: &[u8],
+    depths: ContainerDepths,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let fields = fields(signature)?;
+    let start = out.len();
+
+    if fixed_size(signature)?.is_some() {
+        // The unit type is encoded as a single zero byte.
+        if fields.is_empty() {
+            out.push(b'\0');
+
+            return Ok(());
+        }
+
+        // All fields are fixed-sized as well so their position only depends on their types.
+        let mut field_start = 0;
+        for field in &fields {
+            let alignment = alignment_for_signature(field, EncodingFormat::GVariant)?;
+            let size = fixed_size(field)?.unwrap_or_default();
+            field_start += padding_for_n_bytes(field_start, alignment);
+
+            pad(out, alignment);
+            normalize(field, &data[field_start..field_start + size], depths, out)?;
+            field_start += size;
+        }
+        pad(
+            out,
+            alignment_for_signature(signature, EncodingFormat::GVariant)?,
+        );
+
+        return Ok(());
+    }
+
+    // The end of every variable-sized field, except the last field, is stored in a framing offset
+    // at the end of the structure, in reverse order.
+    let offset_size = FramingOffsetSize::for_encoded_container(data.len());
+    let mut num_offsets = 0;
+    for field in &fields[..fields.len() - 1] {
+        if fixed_size(field)?.is_none() {
+            num_offsets += 1;
+        }
+    }
+    // If the offsets don't fit, there is no valid field.
+    let offsets_start = data.len().checked_sub(num_offsets * offset_size as usize);
+    let read_offset = |i: usize| {
+        let end = data.len() - i * offset_size as usize;
+
+        offset_size.read_last_offset_from_buffer(&data[end - offset_size as usize..end])
+    };
+
+    let mut field_start = 0;
+    let mut offset_index = 0;
+    let mut ends = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let alignment = alignment_for_signature(field, EncodingFormat::GVariant)?;
+        let field_size = fixed_size(field)?;
+        field_start += padding_for_n_bytes(field_start, alignment);
+        let field_end = match (field_size, offsets_start) {
+            (Some(size), _) => field_start + size,
+            (None, Some(offsets_start)) if i == fields.len() - 1 => offsets_start,
+            (None, Some(_)) => {
+                offset_index += 1;
+
+                read_offset(offset_index - 1)
+            }
+            (None, None) => 0,
+        };
+        let field_data = match offsets_start {
+            Some(offsets_start) if field_end <= offsets_start => {
+                data.get(field_start..field_end).unwrap_or_default()
+            }
+            _ => &[],
+        };
+
+        pad(out, alignment);
+        normalize(field, field_data, depths, out)?;
+        if field_size.is_none() && i != fields.len() - 1 {
+            ends.push(out.len() - start);
+        }
+
+        field_start = field_end;
+    }
+    ends.reverse();
+
+    write_offsets(out, start, ends)
+}
+
+// The size of the values of type `signature`, if they're fixed-sized.
+fn fixed_size(signature: &Signature<'_>) -> Result<Option<usize>> {
+    if !is_fixed_sized_signature(signature)? {
+        return Ok(None);
+    }
+
+    let size = match signature.as_bytes()[0] as char {
+        u8::SIGNATURE_CHAR | bool::SIGNATURE_CHAR => 1,
+        i16::SIGNATURE_CHAR | u16::SIGNATURE_CHAR => 2,
+        i32::SIGNATURE_CHAR | u32::SIGNATURE_CHAR => 4,
+        #[cfg(unix)]
+        Fd::SIGNATURE_CHAR => 4,
+        i64::SIGNATURE_CHAR | u64::SIGNATURE_CHAR | f64::SIGNATURE_CHAR => 8,
+        _ => {
+            let mut size = 0;
+            for field in fields(signature)? {
+                let alignment = alignment_for_signature(&field, EncodingFormat::GVariant)?;
+                size += padding_for_n_bytes(size, alignment);
+                size += fixed_size(&field)?.unwrap_or_default();
+            }
+
+            if size == 0 {
+                // Unit type.
+                1
+            } else {
+                let alignment = alignment_for_signature(signature, EncodingFormat::GVariant)?;
+
+                size + padding_for_n_bytes(size, alignment)
+            }
+        }
+    };
+
+    Ok(Some(size))
+}
+
+// The field signatures of a structure or dict-entry signature.
+fn fields<'s>(signature: &Signature<'s>) -> Result<Vec<Signature<'s>>> {
+    let mut parser = SignatureParser::new(signature.slice(1..signature.len() - 1));
+    let mut fields = vec![];
+    while !parser.done() {
+        fields.push(parser.parse_next_signature()?);
+    }
+
+    Ok(fields)
+}
+
+fn is_single_complete_type(signature: &Signature<'_>) -> bool {
+    let mut parser = SignatureParser::new(signature.clone());
+
+    parser.parse_next_signature().is_ok() && parser.done()
+}
+
+// Fixed-sized basic types, other than booleans, for which all bytes are valid.
+fn is_numeric(signature: &Signature<'_>) -> bool {
+    signature.len() == 1 && !matches!(signature.as_bytes()[0] as char, bool::SIGNATURE_CHAR)
+}
+
+fn pad(out: &mut Vec<u8>, alignment: usize) {
+    out.resize(out.len() + padding_for_n_bytes(out.len(), alignment), 0);
+}
+
+fn write_offsets(out: &mut Vec<u8>, start: usize, offsets: Vec<usize>) -> Result<()> {
+    let offset_size = FramingOffsetSize::for_bare_container(out.len() - start, offsets.len());
+    for offset in offsets {
+        offset_size.write_offset(out, offset)?;
+    }
+
+    Ok(())
+}
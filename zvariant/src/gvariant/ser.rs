@@ -1,3 +1,4 @@
+use byteorder::WriteBytesExt;
 use serde::{ser, ser::SerializeSeq, Serialize};
 use static_assertions::assert_impl_all;
 use std::{
@@ -130,7 +131,14 @@ where
     type SerializeStruct = StructSeqSerializer<'ser, 'sig, 'b, B, W>;
     type SerializeStructVariant = StructSeqSerializer<'ser, 'sig, 'b, B, W>;
 
-    serialize_basic!(serialize_bool, bool);
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        // Unlike D-Bus booleans, GVariant ones are a single byte.
+        self.0.prep_serialize_basic::<bool>()?;
+        self.0
+            .write_u8(v as u8)
+            .map_err(|e| Error::InputOutput(e.into()))
+    }
+
     serialize_basic!(serialize_i16, i16);
     serialize_basic!(serialize_i32, i32);
     serialize_basic!(serialize_i64, i64);
@@ -238,16 +246,25 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(unix), allow(unused_variables))]
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
+        #[cfg(unix)]
+        if name == OS_STRING_NAME
+            && variant == OS_STRING_VARIANTS[0]
+            && self.0.sig_parser.next_signature()? == OS_STRING_SIGNATURE
+        {
+            return value.serialize(self);
+        }
+
         self.0.prep_serialize_enum_variant(variant_index)?;
 
         value.serialize(self)
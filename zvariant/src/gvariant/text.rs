@@ -0,0 +1,976 @@
+use std::{convert::TryFrom, fmt::Write, iter::Peekable, str::CharIndices};
+
+#[cfg(unix)]
+use crate::Fd;
+use crate::{
+    signature_parser::SignatureParser, Array, Basic, Dict, Error, Maybe, ObjectPath, OwnedValue,
+    Result, Signature, Str, StructureBuilder, Type, Value, ARRAY_SIGNATURE_CHAR,
+    DICT_ENTRY_SIG_START_CHAR, MAYBE_SIGNATURE_CHAR, STRUCT_SIG_START_CHAR, VARIANT_SIGNATURE_CHAR,
+};
+
+/// Print `value` in the [GVariant text format].
+///
+/// This is equivalent to GLib's `g_variant_print` with type annotations enabled, so the result
+/// can be parsed back to the same value, without an expected signature, using [`from_text`]. It's
+/// also what [`Value::to_gvariant_text`] returns.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{gvariant::to_text, Value};
+///
+/// let mut map: HashMap<&str, Value> = HashMap::new();
+/// map.insert("a", Value::new(1i32));
+/// assert_eq!(to_text(&Value::from(map)), "{'a': <1>}");
+///
+/// let value = Value::from((42u32, "hello", vec!["world"]));
+/// assert_eq!(to_text(&value), "(uint32 42, 'hello', ['world'])");
+/// ```
+///
+/// [GVariant text format]: https://docs.gtk.org/glib/gvariant-text.html
+pub fn to_text(value: &Value<'_>) -> String {
+    let mut text = String::new();
+    print_value(value, true, &mut text);
+
+    text
+}
+
+/// Parse a value from its [GVariant text format] representation.
+///
+/// If `signature` is given, the text is parsed as a value of that type. Otherwise, the type is
+/// inferred from the text, using the same defaults as GLib's `g_variant_parse`: integers are
+/// `int32`, floating-point numbers are `double` and strings are `string`, unless annotated
+/// otherwise (e.g `uint64 5`, `objectpath '/'` or `@as []`).
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use zvariant::{gvariant::from_text, Signature};
+///
+/// let value = from_text("{'a': <int32 1>, 'b': <@as []>}", None).unwrap();
+/// assert_eq!(value.value_signature(), "a{sv}");
+///
+/// let signature = Signature::try_from("(uo)").unwrap();
+/// let value = from_text("(42, '/org/zbus')", Some(&signature)).unwrap();
+/// assert_eq!(value.value_signature(), "(uo)");
+///
+/// let value = from_text("[1, 2, 3]", None).unwrap();
+/// assert_eq!(<Vec<i32>>::try_from(value).unwrap(), vec![1, 2, 3]);
+/// ```
+///
+/// [GVariant text format]: https://docs.gtk.org/glib/gvariant-text.html
+pub fn from_text(text: &str, signature: Option<&Signature<'_>>) -> Result<OwnedValue> {
+    let mut parser = Parser {
+        text,
+        chars: text.char_indices().peekable(),
+    };
+    let ast = parser.parse_value()?;
+    parser.skip_whitespace();
+    if let Some((pos, c)) = parser.chars.peek() {
+        return Err(parse_error(
+            *pos,
+            format!("unexpected `{c}` after the value"),
+        ));
+    }
+
+    let signature = match signature {
+        Some(signature) => {
+            let mut parser = SignatureParser::new(signature.clone());
+            let signature = parser.parse_next_signature()?;
+            if !parser.done() {
+                return Err(Error::SignatureMismatch(
+                    signature.to_owned(),
+                    String::from("a single complete type"),
+                ));
+            }
+
+            signature.to_string()
+        }
+        None => infer_signature(&ast)
+            .map(|(signature, _)| signature)
+            .ok_or_else(|| parse_error(0, "unable to infer the type of the value"))?,
+    };
+
+    build_value(&ast, &signature).map(Into::into)
+}
+
+fn parse_error(pos: usize, msg: impl std::fmt::Display) -> Error {
+    Error::Message(format!(
+        "Failed to parse GVariant text at position {pos}: {msg}"
+    ))
+}
+
+/// The type annotation keywords and the signature they correspond to.
+const TYPE_KEYWORDS: &[(&str, &str)] = &[
+    ("boolean", "b"),
+    ("byte", "y"),
+    ("int16", "n"),
+    ("uint16", "q"),
+    ("int32", "i"),
+    ("uint32", "u"),
+    ("handle", "h"),
+    ("int64", "x"),
+    ("uint64", "t"),
+    ("double", "d"),
+    ("string", "s"),
+    ("objectpath", "o"),
+    ("signature", "g"),
+];
+
+// Printing
+
+fn print_value(value: &Value<'_>, annotate: bool, text: &mut String) {
+    let prefix = |keyword: &str, text: &mut String| {
+        if annotate {
+            text.push_str(keyword);
+            text.push(' ');
+        }
+    };
+
+    match value {
+        Value::U8(v) => {
+            prefix("byte", text);
+            let _ = write!(text, "0x{v:02x}");
+        }
+        Value::Bool(v) => {
+            let _ = write!(text, "{v}");
+        }
+        Value::I16(v) => {
+            prefix("int16", text);
+            let _ = write!(text, "{v}");
+        }
+        Value::U16(v) => {
+            prefix("uint16", text);
+            let _ = write!(text, "{v}");
+        }
+        Value::I32(v) => {
+            let _ = write!(text, "{v}");
+        }
+        Value::U32(v) => {
+            prefix("uint32", text);
+            let _ = write!(text, "{v}");
+        }
+        Value::I64(v) => {
+            prefix("int64", text);
+            let _ = write!(text, "{v}");
+        }
+        Value::U64(v) => {
+            prefix("uint64", text);
+            let _ = write!(text, "{v}");
+        }
+        Value::F64(v) => print_f64(*v, text),
+        Value::Str(v) => print_str(v, text),
+        Value::Signature(v) => {
+            prefix("signature", text);
+            print_str(v, text);
+        }
+        Value::ObjectPath(v) => {
+            prefix("objectpath", text);
+            print_str(v, text);
+        }
+        Value::Value(v) => {
+            text.push('<');
+            print_value(v, true, text);
+            text.push('>');
+        }
+        Value::Array(array) => {
+            if array.is_empty() {
+                if annotate {
+                    let _ = write!(text, "@{} ", array.full_signature());
+                }
+                text.push_str("[]");
+
+                return;
+            }
+
+            let bytes: Option<Vec<u8>> = array
+                .iter()
+                .map(|element| match element {
+                    Value::U8(b) => Some(*b),
+                    _ => None,
+                })
+                .collect();
+            if let Some(bytes) = bytes.filter(|bytes| is_bytestring(bytes)) {
+                print_bytestring(&bytes[..bytes.len() - 1], text);
+
+                return;
+            }
+
+            text.push('[');
+            for (i, element) in array.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(", ");
+                }
+                print_value(element, annotate && i == 0, text);
+            }
+            text.push(']');
+        }
+        Value::Dict(dict) => {
+            if dict.iter().next().is_none() {
+                if annotate {
+                    let _ = write!(text, "@{} ", dict.full_signature());
+                }
+                text.push_str("{}");
+
+                return;
+            }
+
+            text.push('{');
+            for (i, (key, value)) in dict.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(", ");
+                }
+                print_value(key, annotate && i == 0, text);
+                text.push_str(": ");
+                print_value(value, annotate && i == 0, text);
+            }
+            text.push('}');
+        }
+        Value::Structure(structure) => {
+            let fields = structure.fields();
+            text.push('(');
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(", ");
+                }
+                print_value(field, annotate, text);
+            }
+            if fields.len() == 1 {
+                text.push(',');
+            }
+            text.push(')');
+        }
+        Value::Maybe(maybe) => {
+            if annotate {
+                let _ = write!(text, "@{} ", maybe.full_signature());
+            }
+            match maybe.inner() {
+                Some(value) => {
+                    // Nested maybes need `just` to tell `just nothing` apart from `nothing`.
+                    let mut child = String::new();
+                    print_value(value, false, &mut child);
+                    if child.ends_with("nothing") {
+                        text.push_str("just ");
+                    }
+                    text.push_str(&child);
+                }
+                None => text.push_str("nothing"),
+            }
+        }
+        #[cfg(unix)]
+        Value::Fd(fd) => {
+            prefix("handle", text);
+            let _ = write!(text, "{fd}");
+        }
+    }
+}
+
+fn print_f64(v: f64, text: &mut String) {
+    if v.is_nan() {
+        text.push_str("nan");
+    } else if v.is_infinite() {
+        text.push_str(if v > 0. { "inf" } else { "-inf" });
+    } else {
+        // The `Debug` format always includes a `.` or an exponent, so it's never parsed as an
+        // integer.
+        let _ = write!(text, "{v:?}");
+    }
+}
+
+fn print_str(s: &str, text: &mut String) {
+    let quote = if s.contains('\'') { '"' } else { '\'' };
+
+    text.push(quote);
+    for c in s.chars() {
+        match c {
+            c if c == quote || c == '\\' => {
+                text.push('\\');
+                text.push(c);
+            }
+            '\u{7}' => text.push_str("\\a"),
+            '\u{8}' => text.push_str("\\b"),
+            '\u{c}' => text.push_str("\\f"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            '\u{b}' => text.push_str("\\v"),
+            c if c.is_control() => {
+                let c = c as u32;
+                if c < 0x10000 {
+                    let _ = write!(text, "\\u{c:04x}");
+                } else {
+                    let _ = write!(text, "\\U{c:08x}");
+                }
+            }
+            c => text.push(c),
+        }
+    }
+    text.push(quote);
+}
+
+// A byte array is printed as a bytestring if it's nul-terminated and contains no other nul byte.
+fn is_bytestring(bytes: &[u8]) -> bool {
+    match bytes.split_last() {
+        Some((0, rest)) => !rest.contains(&0),
+        _ => false,
+    }
+}
+
+fn print_bytestring(bytes: &[u8], text: &mut String) {
+    let quote = if bytes.contains(&b'\'') { b'"' } else { b'\'' };
+
+    text.push('b');
+    text.push(quote as char);
+    for b in bytes {
+        match *b {
+            b if b == quote || b == b'\\' => {
+                text.push('\\');
+                text.push(b as char);
+            }
+            b'\n' => text.push_str("\\n"),
+            b'\r' => text.push_str("\\r"),
+            b'\t' => text.push_str("\\t"),
+            b if b.is_ascii_graphic() || b == b' ' => text.push(b as char),
+            b => {
+                let _ = write!(text, "\\{b:03o}");
+            }
+        }
+    }
+    text.push(quote as char);
+}
+
+// Parsing
+
+/// The syntax tree of a value in text format, before its type is known.
+#[derive(Debug)]
+enum Ast {
+    Bool(bool),
+    Number {
+        pos: usize,
+        text: String,
+    },
+    Str(String),
+    ByteString(Vec<u8>),
+    Array(Vec<Ast>),
+    Dict(Vec<(Ast, Ast)>),
+    DictEntry(Box<Ast>, Box<Ast>),
+    Tuple(Vec<Ast>),
+    Variant(Box<Ast>),
+    Just(Box<Ast>),
+    Nothing,
+    Typed {
+        pos: usize,
+        signature: String,
+        value: Box<Ast>,
+    },
+}
+
+struct Parser<'t> {
+    text: &'t str,
+    chars: Peekable<CharIndices<'t>>,
+}
+
+impl<'t> Parser<'t> {
+    fn pos(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|(pos, _)| *pos)
+            .unwrap_or(self.text.len())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .peek()
+            .map(|(_, c)| c.is_whitespace())
+            .unwrap_or(false)
+        {
+            self.chars.next();
+        }
+    }
+
+    fn next_char(&mut self) -> Result<(usize, char)> {
+        let pos = self.pos();
+        self.chars
+            .next()
+            .ok_or_else(|| parse_error(pos, "unexpected end of text"))
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn expect_char(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        let (pos, c) = self.next_char()?;
+        if c != expected {
+            return Err(parse_error(
+                pos,
+                format!("expected `{expected}`, got `{c}`"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Ast> {
+        self.skip_whitespace();
+        let end = self.text.len();
+        let (pos, c) = *self
+            .chars
+            .peek()
+            .ok_or_else(|| parse_error(end, "expected a value"))?;
+
+        match c {
+            '[' => {
+                self.chars.next();
+                let elements = self.parse_list(']')?;
+
+                Ok(Ast::Array(elements))
+            }
+            '(' => {
+                self.chars.next();
+                let fields = self.parse_list(')')?;
+
+                Ok(Ast::Tuple(fields))
+            }
+            '{' => {
+                self.chars.next();
+                self.parse_dict()
+            }
+            '<' => {
+                self.chars.next();
+                let value = self.parse_value()?;
+                self.expect_char('>')?;
+
+                Ok(Ast::Variant(Box::new(value)))
+            }
+            '@' => {
+                self.chars.next();
+                let signature = self.parse_signature()?;
+                let value = self.parse_value()?;
+
+                Ok(Ast::Typed {
+                    pos,
+                    signature,
+                    value: Box::new(value),
+                })
+            }
+            '\'' | '"' => {
+                let s = self.parse_string()?;
+
+                Ok(Ast::Str(s))
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                let text = self.parse_word();
+
+                Ok(Ast::Number { pos, text })
+            }
+            c if c.is_ascii_alphabetic() => {
+                if c == 'b' && self.text[pos + 1..].starts_with(['\'', '"']) {
+                    self.chars.next();
+                    let bytes = self.parse_quoted(true)?;
+
+                    return Ok(Ast::ByteString(bytes));
+                }
+
+                let word = self.parse_word();
+                match word.as_str() {
+                    "true" => Ok(Ast::Bool(true)),
+                    "false" => Ok(Ast::Bool(false)),
+                    "nothing" => Ok(Ast::Nothing),
+                    "just" => Ok(Ast::Just(Box::new(self.parse_value()?))),
+                    "inf" | "nan" => Ok(Ast::Number { pos, text: word }),
+                    word => {
+                        let (_, signature) = TYPE_KEYWORDS
+                            .iter()
+                            .find(|(keyword, _)| *keyword == word)
+                            .ok_or_else(|| parse_error(pos, format!("unknown keyword `{word}`")))?;
+                        let value = self.parse_value()?;
+
+                        Ok(Ast::Typed {
+                            pos,
+                            signature: signature.to_string(),
+                            value: Box::new(value),
+                        })
+                    }
+                }
+            }
+            c => Err(parse_error(pos, format!("unexpected `{c}`"))),
+        }
+    }
+
+    // Parses comma-separated values, up to and including the `end` character.
+    fn parse_list(&mut self, end: char) -> Result<Vec<Ast>> {
+        let mut values = vec![];
+        loop {
+            if self.peek_char() == Some(end) {
+                self.chars.next();
+
+                return Ok(values);
+            }
+            values.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            let (pos, c) = self.next_char()?;
+            match c {
+                ',' => continue,
+                c if c == end => return Ok(values),
+                c => {
+                    return Err(parse_error(
+                        pos,
+                        format!("expected `,` or `{end}`, got `{c}`"),
+                    ))
+                }
+            }
+        }
+    }
+
+    // Parses a dictionary (`{k: v, ...}`) or a dictionary entry (`{k, v}`), after the `{`.
+    fn parse_dict(&mut self) -> Result<Ast> {
+        if self.peek_char() == Some('}') {
+            self.chars.next();
+
+            return Ok(Ast::Dict(vec![]));
+        }
+
+        let key = self.parse_value()?;
+        self.skip_whitespace();
+        let (pos, c) = self.next_char()?;
+        match c {
+            ',' => {
+                let value = self.parse_value()?;
+                self.expect_char('}')?;
+
+                Ok(Ast::DictEntry(Box::new(key), Box::new(value)))
+            }
+            ':' => {
+                let mut entries = vec![(key, self.parse_value()?)];
+                loop {
+                    self.skip_whitespace();
+                    let (pos, c) = self.next_char()?;
+                    match c {
+                        '}' => return Ok(Ast::Dict(entries)),
+                        ',' => {
+                            let key = self.parse_value()?;
+                            self.expect_char(':')?;
+                            entries.push((key, self.parse_value()?));
+                        }
+                        c => {
+                            return Err(parse_error(
+                                pos,
+                                format!("expected `,` or `}}`, got `{c}`"),
+                            ))
+                        }
+                    }
+                }
+            }
+            c => Err(parse_error(pos, format!("expected `:` or `,`, got `{c}`"))),
+        }
+    }
+
+    // Parses a single complete type, after the `@`.
+    fn parse_signature(&mut self) -> Result<String> {
+        let pos = self.pos();
+        let rest = &self.text[pos..];
+        let len = SignatureParser::new(Signature::from_str_unchecked(rest))
+            .next_signature()
+            .map_err(|e| parse_error(pos, e))?
+            .len();
+        let signature = &rest[..len];
+        Signature::try_from(signature).map_err(|e| parse_error(pos, e))?;
+        for _ in signature.chars() {
+            self.chars.next();
+        }
+
+        Ok(signature.to_string())
+    }
+
+    // Parses an identifier or a number.
+    fn parse_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_ascii_alphanumeric() && !matches!(c, '_' | '.' | '+' | '-') {
+                break;
+            }
+            word.push(*c);
+            self.chars.next();
+        }
+
+        word
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        let bytes = self.parse_quoted(false)?;
+
+        Ok(String::from_utf8(bytes).expect("strings are only made of characters"))
+    }
+
+    // Parses a quoted string as bytes. In bytestrings, octal escapes are bytes rather than
+    // characters, so that e.g `b'\377'` is a single byte.
+    fn parse_quoted(&mut self, bytestring: bool) -> Result<Vec<u8>> {
+        let (_, quote) = self.next_char()?;
+        let mut s = Vec::new();
+        let push = |s: &mut Vec<u8>, c: char| {
+            s.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        };
+        loop {
+            let (pos, c) = self.next_char()?;
+            match c {
+                c if c == quote => return Ok(s),
+                '\\' => {
+                    let (_, c) = self.next_char()?;
+                    match c {
+                        'a' => s.push(0x7),
+                        'b' => s.push(0x8),
+                        'f' => s.push(0xc),
+                        'n' => s.push(b'\n'),
+                        'r' => s.push(b'\r'),
+                        't' => s.push(b'\t'),
+                        'v' => s.push(0xb),
+                        'u' => push(&mut s, self.parse_unicode_escape(pos, 4)?),
+                        'U' => push(&mut s, self.parse_unicode_escape(pos, 8)?),
+                        '0'..='7' => {
+                            let mut code = c.to_digit(8).expect("octal digit");
+                            for _ in 0..2 {
+                                match self.chars.peek().and_then(|(_, c)| c.to_digit(8)) {
+                                    Some(digit) => {
+                                        code = code * 8 + digit;
+                                        self.chars.next();
+                                    }
+                                    None => break,
+                                }
+                            }
+                            if bytestring {
+                                let b = u8::try_from(code)
+                                    .map_err(|_| parse_error(pos, "invalid octal escape"))?;
+                                s.push(b);
+                            } else {
+                                let c = char::from_u32(code)
+                                    .ok_or_else(|| parse_error(pos, "invalid octal escape"))?;
+                                push(&mut s, c);
+                            }
+                        }
+                        c => push(&mut s, c),
+                    }
+                }
+                c => push(&mut s, c),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self, pos: usize, n_digits: usize) -> Result<char> {
+        let mut code = 0;
+        for _ in 0..n_digits {
+            let (_, c) = self.next_char()?;
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| parse_error(pos, "invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+
+        char::from_u32(code).ok_or_else(|| parse_error(pos, "invalid unicode escape"))
+    }
+}
+
+// Type inference
+
+/// Infer the signature of `ast` if possible, along with whether it was only guessed from a bare
+/// number (in which case a definite type of another element in the same container wins).
+fn infer_signature(ast: &Ast) -> Option<(String, bool)> {
+    match ast {
+        Ast::Bool(_) => Some((bool::SIGNATURE_STR.to_string(), false)),
+        Ast::Number { text, .. } => {
+            let signature = if is_float(text) {
+                f64::SIGNATURE_STR
+            } else {
+                i32::SIGNATURE_STR
+            };
+
+            Some((signature.to_string(), true))
+        }
+        Ast::Str(_) => Some((<&str>::SIGNATURE_STR.to_string(), false)),
+        Ast::ByteString(_) => Some((String::from("ay"), false)),
+        Ast::Array(elements) => {
+            let element = unify(elements.iter().map(infer_signature))?;
+
+            Some((format!("{ARRAY_SIGNATURE_CHAR}{}", element.0), element.1))
+        }
+        Ast::Dict(entries) => {
+            let key = unify(entries.iter().map(|(k, _)| infer_signature(k)))?;
+            let value = unify(entries.iter().map(|(_, v)| infer_signature(v)))?;
+
+            Some((format!("a{{{}{}}}", key.0, value.0), key.1 || value.1))
+        }
+        Ast::DictEntry(key, value) => {
+            let key = infer_signature(key)?;
+            let value = infer_signature(value)?;
+
+            Some((format!("{{{}{}}}", key.0, value.0), key.1 || value.1))
+        }
+        Ast::Tuple(fields) => {
+            let mut signature = String::from(STRUCT_SIG_START_CHAR);
+            let mut weak = false;
+            for field in fields {
+                let (field, field_weak) = infer_signature(field)?;
+                signature.push_str(&field);
+                weak |= field_weak;
+            }
+            signature.push(')');
+
+            Some((signature, weak))
+        }
+        Ast::Variant(_) => Some((VARIANT_SIGNATURE_CHAR.to_string(), false)),
+        Ast::Just(value) => {
+            let (signature, weak) = infer_signature(value)?;
+
+            Some((format!("{MAYBE_SIGNATURE_CHAR}{signature}"), weak))
+        }
+        Ast::Nothing => None,
+        Ast::Typed { signature, .. } => Some((signature.clone(), false)),
+    }
+}
+
+// The first definite signature wins, otherwise `double` wins over `int32`.
+fn unify(signatures: impl Iterator<Item = Option<(String, bool)>>) -> Option<(String, bool)> {
+    let mut weak: Option<String> = None;
+    for (signature, is_weak) in signatures.flatten() {
+        if !is_weak {
+            return Some((signature, false));
+        }
+        if weak.is_none() || signature == f64::SIGNATURE_STR {
+            weak = Some(signature);
+        }
+    }
+
+    weak.map(|signature| (signature, true))
+}
+
+fn is_float(number: &str) -> bool {
+    let digits = number.trim_start_matches(['-', '+']);
+    if digits.starts_with("0x") || digits.starts_with("0X") {
+        return false;
+    }
+
+    digits.contains(['.', 'e', 'E']) || digits == "inf" || digits == "nan"
+}
+
+// Building the value
+
+fn build_value(ast: &Ast, signature: &str) -> Result<Value<'static>> {
+    let type_mismatch = |what: &str| {
+        Error::SignatureMismatch(
+            Signature::from_str_unchecked(signature).to_owned(),
+            what.to_string(),
+        )
+    };
+    let first = signature
+        .chars()
+        .next()
+        .ok_or_else(|| type_mismatch("a non-empty signature"))?;
+
+    if let Ast::Typed {
+        pos,
+        signature: annotated,
+        value,
+    } = ast
+    {
+        if annotated != signature {
+            return Err(parse_error(
+                *pos,
+                format!("type `{annotated}` doesn't match the expected type `{signature}`"),
+            ));
+        }
+
+        return build_value(value, signature);
+    }
+
+    // Maybe values can be written without `just`.
+    if first == MAYBE_SIGNATURE_CHAR {
+        let child_signature = &signature[1..];
+        let child = match ast {
+            Ast::Nothing => None,
+            Ast::Just(value) => Some(build_value(value, child_signature)?),
+            ast => Some(build_value(ast, child_signature)?),
+        };
+        let maybe = match child {
+            Some(child) => Maybe::just_full_signature(child, signature_from_str(signature)),
+            None => Maybe::nothing_full_signature(signature_from_str(signature)),
+        };
+
+        return Ok(Value::Maybe(maybe));
+    }
+
+    let value = match (ast, first) {
+        (Ast::Bool(b), bool::SIGNATURE_CHAR) => Value::Bool(*b),
+        (Ast::Number { pos, text }, _) => build_number(*pos, text, first)?,
+        (Ast::Str(s), <&str>::SIGNATURE_CHAR) => Value::Str(Str::from(s.clone())),
+        (Ast::Str(s), ObjectPath::SIGNATURE_CHAR) => {
+            Value::ObjectPath(ObjectPath::try_from(s.clone())?)
+        }
+        (Ast::Str(s), Signature::SIGNATURE_CHAR) => {
+            Value::Signature(Signature::try_from(s.clone())?)
+        }
+        (Ast::ByteString(bytes), ARRAY_SIGNATURE_CHAR) if signature == "ay" => {
+            let mut array = Array::new(u8::signature());
+            // Bytestrings are nul-terminated.
+            for b in bytes.iter().chain(std::iter::once(&0)) {
+                array.append(Value::U8(*b))?;
+            }
+
+            Value::Array(array)
+        }
+        (Ast::Variant(value), VARIANT_SIGNATURE_CHAR) => {
+            let (child_signature, _) = infer_signature(value)
+                .ok_or_else(|| parse_error(0, "unable to infer the type of a variant's value"))?;
+
+            Value::Value(Box::new(build_value(value, &child_signature)?))
+        }
+        (Ast::Array(elements), ARRAY_SIGNATURE_CHAR) => {
+            let child_signature = &signature[1..];
+            if child_signature.starts_with(DICT_ENTRY_SIG_START_CHAR) {
+                let mut dict = Dict::new_full_signature(signature_from_str(signature));
+                for element in elements {
+                    match element {
+                        Ast::DictEntry(key, value) => {
+                            let (key, value) = build_dict_entry(key, value, signature)?;
+                            dict.append(key, value)?;
+                        }
+                        _ => return Err(type_mismatch("a dictionary entry")),
+                    }
+                }
+
+                Value::Dict(dict)
+            } else {
+                let mut array = Array::new_full_signature(signature_from_str(signature));
+                for element in elements {
+                    array.append(build_value(element, child_signature)?)?;
+                }
+
+                Value::Array(array)
+            }
+        }
+        (Ast::Dict(entries), ARRAY_SIGNATURE_CHAR) if signature[1..].starts_with('{') => {
+            let mut dict = Dict::new_full_signature(signature_from_str(signature));
+            for (key, value) in entries {
+                let (key, value) = build_dict_entry(key, value, signature)?;
+                dict.append(key, value)?;
+            }
+
+            Value::Dict(dict)
+        }
+        (Ast::Tuple(fields), STRUCT_SIG_START_CHAR) => {
+            let mut parser = SignatureParser::new(signature_from_str(signature));
+            parser.skip_char()?;
+            let mut builder = StructureBuilder::new();
+            for field in fields {
+                if parser.next_char()? == ')' {
+                    return Err(type_mismatch("fewer fields"));
+                }
+                let field_signature = parser.parse_next_signature()?;
+                builder = builder.append_field(build_value(field, &field_signature)?);
+            }
+            if parser.next_char()? != ')' {
+                return Err(type_mismatch("more fields"));
+            }
+
+            Value::Structure(builder.build_with_signature(signature_from_str(signature)))
+        }
+        (Ast::Just(_) | Ast::Nothing, _) => return Err(type_mismatch("a maybe type")),
+        (Ast::DictEntry(..), _) => return Err(type_mismatch("an array of dictionary entries")),
+        (ast, _) => {
+            let (inferred, _) = infer_signature(ast).unwrap_or_default();
+
+            return Err(type_mismatch(&format!("`{inferred}`")));
+        }
+    };
+
+    Ok(value)
+}
+
+fn build_dict_entry(
+    key: &Ast,
+    value: &Ast,
+    dict_signature: &str,
+) -> Result<(Value<'static>, Value<'static>)> {
+    // `a{` + key signature + value signature + `}`
+    let key_signature = &dict_signature[2..3];
+    let value_signature = &dict_signature[3..dict_signature.len() - 1];
+
+    Ok((
+        build_value(key, key_signature)?,
+        build_value(value, value_signature)?,
+    ))
+}
+
+fn build_number(pos: usize, text: &str, signature: char) -> Result<Value<'static>> {
+    if signature == f64::SIGNATURE_CHAR {
+        let v = text
+            .parse::<f64>()
+            .or_else(|_| parse_integer(text).map(|v| v as f64).ok_or(()))
+            .map_err(|_| parse_error(pos, format!("invalid number `{text}`")))?;
+
+        return Ok(Value::F64(v));
+    }
+
+    if is_float(text) {
+        return Err(parse_error(
+            pos,
+            format!("floating-point number `{text}` where an integer was expected"),
+        ));
+    }
+    let v =
+        parse_integer(text).ok_or_else(|| parse_error(pos, format!("invalid number `{text}`")))?;
+    let out_of_range = || {
+        parse_error(
+            pos,
+            format!("number `{text}` out of range for `{signature}`"),
+        )
+    };
+    let value = match signature {
+        u8::SIGNATURE_CHAR => Value::U8(u8::try_from(v).map_err(|_| out_of_range())?),
+        i16::SIGNATURE_CHAR => Value::I16(i16::try_from(v).map_err(|_| out_of_range())?),
+        u16::SIGNATURE_CHAR => Value::U16(u16::try_from(v).map_err(|_| out_of_range())?),
+        i32::SIGNATURE_CHAR => Value::I32(i32::try_from(v).map_err(|_| out_of_range())?),
+        u32::SIGNATURE_CHAR => Value::U32(u32::try_from(v).map_err(|_| out_of_range())?),
+        i64::SIGNATURE_CHAR => Value::I64(i64::try_from(v).map_err(|_| out_of_range())?),
+        u64::SIGNATURE_CHAR => Value::U64(u64::try_from(v).map_err(|_| out_of_range())?),
+        #[cfg(unix)]
+        Fd::SIGNATURE_CHAR => Value::Fd(Fd::from(i32::try_from(v).map_err(|_| out_of_range())?)),
+        _ => {
+            return Err(parse_error(
+                pos,
+                format!("number where a `{signature}` was expected"),
+            ))
+        }
+    };
+
+    Ok(value)
+}
+
+// Parses decimal, hexadecimal (`0x` prefix) and octal (`0` prefix) integers.
+fn parse_integer(text: &str) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let v = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i128::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i128>().ok()?
+    };
+
+    Some(if negative { -v } else { v })
+}
+
+fn signature_from_str(signature: &str) -> Signature<'static> {
+    Signature::from_string_unchecked(signature.to_string())
+}
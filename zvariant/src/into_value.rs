@@ -1,4 +1,7 @@
-use std::{collections::HashMap, hash::BuildHasher};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
 
 #[cfg(feature = "gvariant")]
 use crate::Maybe;
@@ -104,6 +107,32 @@ where
     }
 }
 
+impl<'a, 'k, 'v, K, V> From<BTreeMap<K, V>> for Value<'a>
+where
+    'k: 'a,
+    'v: 'a,
+    K: Type + Into<Value<'k>> + std::cmp::Ord,
+    V: Type + Into<Value<'v>>,
+{
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Self::Dict(value.into())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'a, 'k, 'v, K, V, H> From<indexmap::IndexMap<K, V, H>> for Value<'a>
+where
+    'k: 'a,
+    'v: 'a,
+    K: Type + Into<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    V: Type + Into<Value<'v>>,
+    H: BuildHasher + Default,
+{
+    fn from(value: indexmap::IndexMap<K, V, H>) -> Self {
+        Self::Dict(value.into())
+    }
+}
+
 impl<'v> From<&'v String> for Value<'v> {
     fn from(v: &'v String) -> Value<'v> {
         Value::Str(v.into())
@@ -0,0 +1,313 @@
+//! Conversion of values to and from JSON.
+//!
+//! The JSON representation is meant to be stable and natural to consume from other languages:
+//!
+//! * Booleans map to JSON booleans, and all numeric types, including file descriptors, to JSON
+//!   numbers. Non-finite doubles, which JSON numbers can't represent, map to the `"NaN"`,
+//!   `"Infinity"` and `"-Infinity"` strings.
+//! * Strings, object paths and signatures map to JSON strings.
+//! * Arrays and structures map to JSON arrays.
+//! * Dictionaries map to JSON objects. Keys that aren't strings are converted to their string
+//!   form (e.g `"42"` or `"true"`).
+//! * Variants map to an object with the signature of the contained value and the value itself:
+//!   `{"signature": "u", "value": 42}`. This is what makes the representation lossless, since the
+//!   types of the contained values would otherwise be lost.
+//! * Maybe types (GVariant only) map to `null` for nothing and to the contained value otherwise,
+//!   except when the contained value is itself a maybe, in which case it's wrapped in a
+//!   single-element JSON array.
+//!
+//! Since JSON doesn't carry D-Bus type information, decoding requires the signature of the
+//! expected value.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::convert::TryFrom;
+//! use std::collections::HashMap;
+//! use serde_json::json;
+//! use zvariant::{json::{from_json, to_json}, Signature, Value};
+//!
+//! let mut map: HashMap<&str, Value> = HashMap::new();
+//! map.insert("id", Value::new(42u32));
+//! let value = Value::from(("/org/zbus", map));
+//! let json = to_json(&value);
+//! assert_eq!(json, json!(["/org/zbus", {"id": {"signature": "u", "value": 42}}]));
+//!
+//! let signature = Signature::try_from("(oa{sv})").unwrap();
+//! let decoded = from_json(&json, &signature).unwrap();
+//! assert_eq!(decoded.value_signature(), "(oa{sv})");
+//! ```
+
+use serde_json::{Map, Number, Value as JsonValue};
+use std::convert::TryFrom;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+
+#[cfg(unix)]
+use crate::Fd;
+use crate::{
+    signature_parser::SignatureParser, Array, Basic, Dict, Error, ObjectPath, OwnedValue, Result,
+    Signature, Str, StructureBuilder, Value, ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_START_CHAR,
+    STRUCT_SIG_END_CHAR, STRUCT_SIG_START_CHAR, VARIANT_SIGNATURE_CHAR,
+};
+#[cfg(feature = "gvariant")]
+use crate::{Maybe, MAYBE_SIGNATURE_CHAR};
+
+const SIGNATURE_KEY: &str = "signature";
+const VALUE_KEY: &str = "value";
+
+/// Convert `value` to its JSON representation.
+///
+/// See the [module documentation](index.html) for details on the representation.
+pub fn to_json(value: &Value<'_>) -> JsonValue {
+    match value {
+        Value::U8(v) => JsonValue::from(*v),
+        Value::Bool(v) => JsonValue::from(*v),
+        Value::I16(v) => JsonValue::from(*v),
+        Value::U16(v) => JsonValue::from(*v),
+        Value::I32(v) => JsonValue::from(*v),
+        Value::U32(v) => JsonValue::from(*v),
+        Value::I64(v) => JsonValue::from(*v),
+        Value::U64(v) => JsonValue::from(*v),
+        Value::F64(v) => Number::from_f64(*v)
+            .map_or_else(|| JsonValue::from(non_finite_to_str(*v)), JsonValue::Number),
+        Value::Str(v) => JsonValue::from(v.as_str()),
+        Value::Signature(v) => JsonValue::from(v.as_str()),
+        Value::ObjectPath(v) => JsonValue::from(v.as_str()),
+        Value::Value(v) => {
+            let mut map = Map::new();
+            map.insert(
+                SIGNATURE_KEY.to_string(),
+                JsonValue::from(v.value_signature().as_str()),
+            );
+            map.insert(VALUE_KEY.to_string(), to_json(v));
+
+            JsonValue::Object(map)
+        }
+        Value::Array(array) => array.get().iter().map(to_json).collect(),
+        Value::Dict(dict) => JsonValue::Object(
+            dict.iter()
+                .map(|(key, value)| (key_to_string(key), to_json(value)))
+                .collect(),
+        ),
+        Value::Structure(structure) => structure.fields().iter().map(to_json).collect(),
+        #[cfg(feature = "gvariant")]
+        Value::Maybe(maybe) => match maybe.inner() {
+            Some(value @ Value::Maybe(_)) => JsonValue::Array(vec![to_json(value)]),
+            Some(value) => to_json(value),
+            None => JsonValue::Null,
+        },
+        #[cfg(unix)]
+        Value::Fd(fd) => JsonValue::from(fd.as_raw_fd()),
+    }
+}
+
+/// Decode a value of type `signature` from its JSON representation.
+///
+/// `signature` must be a single complete type. See the [module documentation](index.html) for
+/// details on the representation.
+pub fn from_json(json: &JsonValue, signature: &Signature<'_>) -> Result<OwnedValue> {
+    let mut parser = SignatureParser::new(signature.clone());
+    parser.parse_next_signature()?;
+    if !parser.done() {
+        return Err(Error::SignatureMismatch(
+            signature.to_owned(),
+            String::from("a single complete type"),
+        ));
+    }
+
+    value_from_json(json, signature).map(Into::into)
+}
+
+fn key_to_string(key: &Value<'_>) -> String {
+    match to_json(key) {
+        JsonValue::String(s) => s,
+        json => json.to_string(),
+    }
+}
+
+fn value_from_json(json: &JsonValue, signature: &Signature<'_>) -> Result<Value<'static>> {
+    let mismatch =
+        |expected: &str| Error::SignatureMismatch(signature.to_owned(), expected.to_string());
+    let first = signature
+        .as_bytes()
+        .first()
+        .map(|b| *b as char)
+        .ok_or_else(|| mismatch("a non-empty signature"))?;
+
+    let value = match first {
+        ARRAY_SIGNATURE_CHAR
+            if signature.as_bytes().get(1) == Some(&(DICT_ENTRY_SIG_START_CHAR as u8)) =>
+        {
+            let map = json.as_object().ok_or_else(|| mismatch("an object"))?;
+            // `a{` + key signature + value signature + `}`
+            let key_signature = signature.slice(2..3);
+            let value_signature = signature.slice(3..signature.len() - 1);
+            let mut dict = Dict::new_full_signature(signature.to_owned());
+            for (key, value) in map {
+                let key = key_from_str(key, &key_signature)?;
+                let value = value_from_json(value, &value_signature)?;
+                dict.append(key, value)?;
+            }
+
+            Value::Dict(dict)
+        }
+        ARRAY_SIGNATURE_CHAR => {
+            let elements = json.as_array().ok_or_else(|| mismatch("an array"))?;
+            let element_signature = signature.slice(1..);
+            let mut array = Array::new_full_signature(signature.to_owned());
+            for element in elements {
+                array.append(value_from_json(element, &element_signature)?)?;
+            }
+
+            Value::Array(array)
+        }
+        STRUCT_SIG_START_CHAR => {
+            let mut fields = json.as_array().ok_or_else(|| mismatch("an array"))?.iter();
+            let mut parser = SignatureParser::new(signature.clone());
+            parser.skip_char()?;
+            let mut builder = StructureBuilder::new();
+            while parser.next_char()? != STRUCT_SIG_END_CHAR {
+                let field_signature = parser.parse_next_signature()?;
+                let field = fields.next().ok_or_else(|| mismatch("more fields"))?;
+                builder = builder.append_field(value_from_json(field, &field_signature)?);
+            }
+            if fields.next().is_some() {
+                return Err(mismatch("fewer fields"));
+            }
+
+            Value::Structure(builder.build_with_signature(signature.to_owned()))
+        }
+        VARIANT_SIGNATURE_CHAR => {
+            let map = json.as_object().ok_or_else(|| mismatch("an object"))?;
+            let (child_signature, child) = match (map.get(SIGNATURE_KEY), map.get(VALUE_KEY)) {
+                (Some(JsonValue::String(s)), Some(child)) if map.len() == 2 => (s, child),
+                _ => {
+                    return Err(mismatch(&format!(
+                        "an object with `{SIGNATURE_KEY}` and `{VALUE_KEY}` fields",
+                    )))
+                }
+            };
+            let child_signature = Signature::try_from(child_signature.as_str())?;
+
+            Value::Value(Box::new(from_json(child, &child_signature)?.into()))
+        }
+        #[cfg(feature = "gvariant")]
+        MAYBE_SIGNATURE_CHAR => {
+            let child_signature = signature.slice(1..);
+            let child = match json {
+                JsonValue::Null => None,
+                JsonValue::Array(elements) if child_signature.starts_with(MAYBE_SIGNATURE_CHAR) => {
+                    match elements.as_slice() {
+                        [child] => Some(child),
+                        _ => return Err(mismatch("a single-element array")),
+                    }
+                }
+                _ if child_signature.starts_with(MAYBE_SIGNATURE_CHAR) => {
+                    return Err(mismatch("`null` or a single-element array"))
+                }
+                json => Some(json),
+            };
+            let maybe = match child {
+                Some(child) => Maybe::just_full_signature(
+                    value_from_json(child, &child_signature)?,
+                    signature.to_owned(),
+                ),
+                None => Maybe::nothing_full_signature(signature.to_owned()),
+            };
+
+            Value::Maybe(maybe)
+        }
+        bool::SIGNATURE_CHAR => Value::Bool(json.as_bool().ok_or_else(|| mismatch("a boolean"))?),
+        f64::SIGNATURE_CHAR => Value::F64(
+            json.as_f64()
+                .or_else(|| json.as_str().and_then(non_finite_from_str))
+                .ok_or_else(|| mismatch("a number"))?,
+        ),
+        <&str>::SIGNATURE_CHAR | ObjectPath::SIGNATURE_CHAR | Signature::SIGNATURE_CHAR => {
+            let s = json.as_str().ok_or_else(|| mismatch("a string"))?;
+
+            key_from_str(s, signature)?
+        }
+        _ => {
+            let v = match json {
+                JsonValue::Number(n) => n
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| n.as_u64().map(i128::from)),
+                _ => None,
+            }
+            .ok_or_else(|| mismatch("an integer"))?;
+
+            integer_value(v, first).ok_or_else(|| mismatch("an integer in range"))?
+        }
+    };
+
+    Ok(value)
+}
+
+// Decodes a basic value from a dictionary key or string.
+fn key_from_str(s: &str, signature: &Signature<'_>) -> Result<Value<'static>> {
+    let mismatch =
+        || Error::SignatureMismatch(signature.to_owned(), format!("a valid key, not `{s}`"));
+    let first = signature
+        .as_bytes()
+        .first()
+        .map(|b| *b as char)
+        .ok_or_else(mismatch)?;
+
+    let value = match first {
+        <&str>::SIGNATURE_CHAR => Value::Str(Str::from(s.to_string())),
+        ObjectPath::SIGNATURE_CHAR => Value::ObjectPath(ObjectPath::try_from(s.to_string())?),
+        Signature::SIGNATURE_CHAR => Value::Signature(Signature::try_from(s.to_string())?),
+        bool::SIGNATURE_CHAR => Value::Bool(s.parse().map_err(|_| mismatch())?),
+        f64::SIGNATURE_CHAR => Value::F64(
+            non_finite_from_str(s)
+                .or_else(|| s.parse().ok())
+                .ok_or_else(mismatch)?,
+        ),
+        c => s
+            .parse::<i128>()
+            .ok()
+            .and_then(|v| integer_value(v, c))
+            .ok_or_else(mismatch)?,
+    };
+
+    Ok(value)
+}
+
+fn non_finite_to_str(v: f64) -> &'static str {
+    if v.is_nan() {
+        "NaN"
+    } else if v.is_sign_positive() {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+fn non_finite_from_str(s: &str) -> Option<f64> {
+    match s {
+        "NaN" => Some(f64::NAN),
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+fn integer_value(v: i128, signature: char) -> Option<Value<'static>> {
+    let value = match signature {
+        u8::SIGNATURE_CHAR => Value::U8(u8::try_from(v).ok()?),
+        i16::SIGNATURE_CHAR => Value::I16(i16::try_from(v).ok()?),
+        u16::SIGNATURE_CHAR => Value::U16(u16::try_from(v).ok()?),
+        i32::SIGNATURE_CHAR => Value::I32(i32::try_from(v).ok()?),
+        u32::SIGNATURE_CHAR => Value::U32(u32::try_from(v).ok()?),
+        i64::SIGNATURE_CHAR => Value::I64(i64::try_from(v).ok()?),
+        u64::SIGNATURE_CHAR => Value::U64(u64::try_from(v).ok()?),
+        #[cfg(unix)]
+        Fd::SIGNATURE_CHAR => Value::Fd(Fd::from(i32::try_from(v).ok()?)),
+        _ => return None,
+    };
+
+    Some(value)
+}
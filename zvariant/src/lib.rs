@@ -18,12 +18,24 @@ pub use utils::*;
 mod array;
 pub use array::*;
 
+mod array_iter;
+pub use array_iter::*;
+
 mod basic;
 pub use basic::*;
 
+mod busctl;
+pub use busctl::*;
+
 mod dict;
 pub use dict::*;
 
+mod dict_entries;
+pub use dict_entries::*;
+
+mod dict_iter;
+pub use dict_iter::*;
+
 mod encoding_context;
 pub use encoding_context::*;
 
@@ -44,10 +56,15 @@ pub use de::*;
 pub mod dbus;
 #[cfg(feature = "gvariant")]
 pub mod gvariant;
+#[cfg(feature = "serde_json")]
+pub mod json;
 
 mod signature;
 pub use crate::signature::*;
 
+mod signature_type;
+pub use crate::signature_type::*;
+
 mod str;
 pub use crate::str::*;
 
@@ -95,7 +112,10 @@ mod signature_parser;
 
 mod container_depths;
 
-pub use zvariant_derive::{DeserializeDict, OwnedValue, SerializeDict, Type, TypeDict, Value};
+pub use zvariant_derive::{
+    DeserializeDict, DeserializeTagged, OwnedValue, SerializeDict, SerializeTagged, Type, TypeDict,
+    Value,
+};
 
 // Required for the macros to function within this crate.
 extern crate self as zvariant;
@@ -110,7 +130,7 @@ pub mod export {
 #[allow(clippy::disallowed_names)]
 mod tests {
     use std::{
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         convert::{TryFrom, TryInto},
         net::{IpAddr, Ipv4Addr, Ipv6Addr},
     };
@@ -134,9 +154,9 @@ mod tests {
     #[cfg(unix)]
     use crate::Fd;
     use crate::{
-        Array, Basic, DeserializeDict, DeserializeValue, Dict, EncodingContext as Context,
-        EncodingFormat, Error, ObjectPath, Result, SerializeDict, SerializeValue, Signature, Str,
-        Structure, Type, Value,
+        Array, ArrayIter, Basic, DeserializeDict, DeserializeValue, Dict, DictIter,
+        EncodingContext as Context, EncodingFormat, Error, ObjectPath, Result, SerializeDict,
+        SerializeValue, Signature, SignatureType, Str, Structure, Type, Value,
     };
 
     // Test through both generic and specific API (wrt byte order)
@@ -314,6 +334,34 @@ mod tests {
         basic_type_test!(LE, GVariant, 77_i8, 2, i8, 2);
     }
 
+    #[test]
+    fn bool_value() {
+        let encoded = basic_type_test!(LE, DBus, true, 4, bool, 4, Bool, 8);
+        assert_eq!(encoded, [1, 0, 0, 0]);
+        #[cfg(feature = "gvariant")]
+        {
+            // Unlike D-Bus booleans, GVariant ones are a single byte.
+            let encoded = basic_type_test!(LE, GVariant, true, 1, bool, 1, Bool, 3);
+            assert_eq!(encoded, [1]);
+            assert!(decode_with_gvariant::<_, bool>(encoded));
+
+            let ctxt = Context::<LE>::new_gvariant(0);
+            let encoded = to_bytes(ctxt, &vec![true, false, true]).unwrap();
+            assert_eq!(encoded, [1, 0, 1]);
+            assert_eq!(
+                decode_with_gvariant::<_, Vec<bool>>(encoded),
+                [true, false, true]
+            );
+
+            let encoded = to_bytes(ctxt, &(7u8, true)).unwrap();
+            assert_eq!(encoded, [7, 1]);
+            assert_eq!(decode_with_gvariant::<_, (u8, bool)>(encoded), (7, true));
+
+            // Only 0 and 1 are valid booleans.
+            assert!(from_slice::<_, bool>(&[2], ctxt).is_err());
+        }
+    }
+
     #[cfg(unix)]
     #[test]
     fn fd_value() {
@@ -1185,6 +1233,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dict_map_api() {
+        let mut dict = Dict::new(<&str>::signature(), Value::signature());
+        assert!(dict.is_empty());
+        assert_eq!(dict.insert("Volume", Value::from(50u8)).unwrap(), None);
+        assert_eq!(
+            dict.insert("Volume", Value::from(70u8)).unwrap(),
+            Some(Value::new(Value::from(50u8)))
+        );
+        dict.insert("Title", Value::from("Lazy")).unwrap();
+        assert_eq!(dict.len(), 2);
+        assert!(dict.contains_key("Title").unwrap());
+        assert!(!dict.contains_key("Muted").unwrap());
+        assert!(dict.contains_key(1u8).is_err());
+        assert!(dict.insert(1u8, Value::from(1u8)).is_err());
+
+        dict.entry("Muted")
+            .unwrap()
+            .or_insert(Value::from(false))
+            .unwrap();
+        dict.entry("Volume")
+            .unwrap()
+            .or_insert(Value::from(0u8))
+            .unwrap();
+        assert_eq!(dict.get::<str, u8>("Volume").unwrap(), Some(&70));
+        assert_eq!(
+            dict.remove("Title").unwrap(),
+            Some(Value::new(Value::from("Lazy")))
+        );
+        assert_eq!(dict.remove("Title").unwrap(), None);
+        let keys: Vec<_> = dict.keys().cloned().collect();
+        assert_eq!(keys, [Value::from("Volume"), Value::from("Muted")]);
+
+        // BTreeMap conversions.
+        let mut map = BTreeMap::new();
+        map.insert("b".to_string(), 2u32);
+        map.insert("a".to_string(), 1u32);
+        let dict = Dict::from(map.clone());
+        assert_eq!(dict.full_signature(), "a{su}");
+        assert_eq!(
+            dict.values().collect::<Vec<_>>(),
+            [&Value::U32(1), &Value::U32(2)]
+        );
+        assert_eq!(
+            BTreeMap::<String, u32>::try_from(dict.clone()).unwrap(),
+            map
+        );
+        assert!(BTreeMap::<String, u8>::try_from(dict).is_err());
+
+        // IndexMap conversions, which keep the order of the entries.
+        #[cfg(feature = "indexmap")]
+        {
+            use crate::OwnedValue;
+            use indexmap::IndexMap;
+
+            let mut map = IndexMap::new();
+            map.insert("b".to_string(), 2u32);
+            map.insert("a".to_string(), 1u32);
+            let dict = Dict::from(map.clone());
+            assert_eq!(dict.full_signature(), "a{su}");
+            assert_eq!(
+                dict.values().collect::<Vec<_>>(),
+                [&Value::U32(2), &Value::U32(1)]
+            );
+            let converted = IndexMap::<String, u32>::try_from(dict.clone()).unwrap();
+            assert!(converted.iter().eq(map.iter()));
+            assert!(IndexMap::<String, u8>::try_from(dict).is_err());
+
+            let converted = IndexMap::<String, u32>::try_from(Value::from(map.clone())).unwrap();
+            assert!(converted.iter().eq(map.iter()));
+            let converted =
+                IndexMap::<String, u32>::try_from(OwnedValue::from(map.clone())).unwrap();
+            assert!(converted.iter().eq(map.iter()));
+        }
+    }
+
+    #[test]
+    fn value_pointer() {
+        let mut metadata = HashMap::new();
+        metadata.insert("xesam:artist", Value::from(vec!["Foo", "Bar"]));
+        metadata.insert("a/b~c", Value::from(7u8));
+        let mut ids = HashMap::new();
+        ids.insert(3u32, "three");
+        let mut value = Value::from((metadata, ids, Value::from(true)));
+
+        assert_eq!(
+            value.pointer("/0/xesam:artist/1"),
+            Some(&Value::from("Bar"))
+        );
+        assert_eq!(value.pointer("/0/a~1b~0c"), Some(&Value::from(7u8)));
+        assert_eq!(value.pointer("/1/3"), Some(&Value::from("three")));
+        assert_eq!(value.pointer("/2"), Some(&Value::from(true)));
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("0"), None);
+        assert_eq!(value.pointer("/0/xesam:artist/01"), None);
+        assert_eq!(value.pointer("/0/xesam:artist/+1"), None);
+        assert_eq!(value.pointer("/0/xesam:artist/2"), None);
+        assert_eq!(value.pointer("/1/three"), None);
+        assert_eq!(value.pointer("/2/0"), None);
+
+        assert_eq!(value.get_as::<u8>("/0/a~1b~0c").unwrap(), Some(7));
+        assert_eq!(
+            value.get_as::<Vec<String>>("/0/xesam:artist").unwrap(),
+            Some(vec!["Foo".to_string(), "Bar".to_string()])
+        );
+        assert_eq!(value.get_as::<u8>("/0/missing").unwrap(), None);
+        assert!(value.get_as::<bool>("/1/3").is_err());
+
+        value
+            .replace_pointer("/0/xesam:artist/0", Value::from("Baz"))
+            .unwrap();
+        assert_eq!(
+            value.pointer("/0/xesam:artist/0"),
+            Some(&Value::from("Baz"))
+        );
+
+        // Variants can be replaced by any type, other values only by the same type.
+        assert_eq!(
+            value.replace_pointer("/2", Value::from("yes")).unwrap(),
+            Value::new(Value::from(true))
+        );
+        assert_eq!(value.pointer("/2"), Some(&Value::from("yes")));
+        assert_eq!(
+            value.replace_pointer("/1/3", Value::from("drei")).unwrap(),
+            Value::from("three")
+        );
+        assert!(value.replace_pointer("/1/3", Value::from(3u8)).is_err());
+        assert!(value.replace_pointer("/1/4", Value::from("vier")).is_err());
+        assert!(value
+            .replace_pointer("/0/xesam:artist/0", Value::from(1u8))
+            .is_err());
+
+        let encoded = to_bytes(Context::<LE>::new_dbus(0), &value).unwrap();
+        let decoded: Value<'_> = from_slice(&encoded, Context::<LE>::new_dbus(0)).unwrap();
+        assert_eq!(decoded.pointer("/1/3"), Some(&Value::from("drei")));
+    }
+
     #[test]
     fn value_value() {
         let ctxt = Context::<BE>::new_dbus(0);
@@ -1327,8 +1512,12 @@ mod tests {
                 (Context::<BE>::new_gvariant(4), 12),
             ],
         ];
-        // TODO: Provide convenience API to create complex signatures
-        let signature = "(u(yu))".try_into().unwrap();
+        let signature = SignatureType::Struct(vec![
+            SignatureType::U32,
+            SignatureType::Struct(vec![SignatureType::U8, SignatureType::U32]),
+        ])
+        .to_signature()
+        .unwrap();
         for ctxts_n_expected_len in ctxts_n_expected_lens {
             for (ctxt, expected_len) in ctxts_n_expected_len {
                 let encoded =
@@ -1763,6 +1952,226 @@ mod tests {
         let _: ZVStruct<'_> = from_slice_for_signature(&encoded, ctxt, &signature).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "gvariant")]
+    fn gvariant_text() {
+        use crate::gvariant::{from_text, to_text};
+
+        let v = from_text("{'a': <int32 1>, 'b': <@as []>}", None).unwrap();
+        assert_eq!(v.value_signature(), "a{sv}");
+        assert_eq!(to_text(&v), "{'a': <1>, 'b': <@as []>}");
+
+        let v = from_text("[1, 2.5]", None).unwrap();
+        assert_eq!(v.value_signature(), "ad");
+        let v = from_text("b'hello'", None).unwrap();
+        assert_eq!(v.value_signature(), "ay");
+        assert_eq!(to_text(&v), "b'hello'");
+        // Octal escapes in bytestrings are bytes, not characters.
+        let v = from_text("b'\\377'", None).unwrap();
+        assert_eq!(*v, Value::from(vec![0xffu8, 0]));
+        let value = Value::from(vec![0x80u8, b'a', 0xff, 0]);
+        let text = to_text(&value);
+        assert_eq!(text, "b'\\200a\\377'");
+        assert_eq!(*from_text(&text, None).unwrap(), value);
+
+        let v = from_text("just 5", None).unwrap();
+        assert_eq!(to_text(&v), "@mi 5");
+        let v = from_text("@mmi just nothing", None).unwrap();
+        assert_eq!(to_text(&v), "@mmi just nothing");
+
+        let byte_sig = Signature::from_static_str_unchecked("y");
+        let v = from_text("0x10", Some(&byte_sig)).unwrap();
+        assert_eq!(*v, Value::U8(0x10));
+        assert!(from_text("300", Some(&byte_sig)).is_err());
+
+        let path_sig = Signature::from_static_str_unchecked("o");
+        let v = from_text("'/a'", Some(&path_sig)).unwrap();
+        assert_eq!(*v, Value::ObjectPath(ObjectPath::try_from("/a").unwrap()));
+
+        let u32_sig = Signature::from_static_str_unchecked("u");
+        assert!(from_text("int32 4", Some(&u32_sig)).is_err());
+        assert!(from_text("[]", None).is_err());
+
+        // Round-trip through the printer.
+        let value = Value::from((42u8, Value::from(vec![1i64, 2]), "Hello!"));
+        let text = to_text(&value);
+        assert_eq!(text, "(byte 0x2a, <[int64 1, 2]>, 'Hello!')");
+        assert_eq!(*from_text(&text, None).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(feature = "gvariant")]
+    fn gvariant_normal_form() {
+        use crate::gvariant::{is_normal_form, to_normal_form};
+
+        // Whatever we encode is in normal form.
+        let ctxt = Context::<LE>::new_gvariant(0);
+        let mut map = HashMap::new();
+        map.insert("key", Value::from((7u8, true, "value")));
+        let value = (
+            42u32,
+            true,
+            "hello",
+            vec![false, true],
+            vec!["a", "bc"],
+            map,
+        );
+        let encoded = to_bytes(ctxt, &value).unwrap();
+        let signature = Signature::try_from("(ubsabasa{sv})").unwrap();
+        assert!(is_normal_form(&encoded, &signature).unwrap());
+        let normalized = to_normal_form(&encoded, &signature).unwrap();
+        assert_eq!(normalized, &*encoded);
+        let decoded: (
+            u32,
+            bool,
+            &str,
+            Vec<bool>,
+            Vec<&str>,
+            HashMap<&str, Value<'_>>,
+        ) = from_slice(&normalized, ctxt).unwrap();
+        assert_eq!(decoded, value);
+
+        // Non-canonical data and its normal form.
+        let cases: Vec<(&str, &[u8], &[u8])> = vec![
+            // Booleans other than 0 and 1.
+            ("b", b"\x02", b"\x01"),
+            ("(by)", b"\x02\x03", b"\x01\x03"),
+            // Non-zero padding.
+            (
+                "(yu)",
+                b"\x01\xff\xff\xff\x02\0\0\0",
+                b"\x01\0\0\0\x02\0\0\0",
+            ),
+            // Overlapping framing offsets.
+            ("as", b"a\0b\0\x04\x02\x04", b"\0\0b\0\x01\x02\x04"),
+            // Trailing bytes.
+            ("u", b"\x01\0\0\0\0", b"\0\0\0\0"),
+            ("au", b"\x01\0\0\0\0", b""),
+            ("mu", b"\x01\0\0", b""),
+            // Invalid strings.
+            ("s", b"\xff\0", b"\0"),
+            ("s", b"a\0b\0", b"\0"),
+            ("s", b"ab", b"\0"),
+            ("o", b"a\0", b"/\0"),
+            // Invalid variant signature.
+            ("v", b"\x01\0zz", b"\0\0()"),
+            // Framing offsets out of bounds.
+            ("(sy)", b"a\0\x01\x09", b"\0\0\x01"),
+        ];
+        for (signature, bytes, normal) in cases {
+            let signature = Signature::try_from(signature).unwrap();
+            assert!(!is_normal_form(bytes, &signature).unwrap());
+            let normalized = to_normal_form(bytes, &signature).unwrap();
+            assert_eq!(normalized, normal, "{}", signature);
+            assert!(is_normal_form(&normalized, &signature).unwrap());
+        }
+
+        // Variants nested too deep are read as unit values.
+        let mut encoded = vec![1, 0, b'y'];
+        for _ in 0..100 {
+            encoded.extend_from_slice(b"\0v");
+        }
+        let signature = Signature::try_from("v").unwrap();
+        let mut normal = b"\0\0()".to_vec();
+        for _ in 0..64 {
+            normal.extend_from_slice(b"\0v");
+        }
+        assert_eq!(to_normal_form(&encoded, &signature).unwrap(), normal);
+
+        let signature = Signature::try_from("uu").unwrap();
+        assert!(to_normal_form(b"", &signature).is_err());
+    }
+
+    #[test]
+    fn busctl_args() {
+        use crate::{from_busctl_args, to_busctl_args};
+
+        let signature = Signature::try_from("(sib)adv").unwrap();
+        let args = ["x", "-0x3", "yes", "2", "1.5", "2", "a(ii)", "1", "2", "3"];
+        let values = from_busctl_args(&signature, args).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(*values[0], Value::from(("x", -3i32, true)));
+        assert_eq!(*values[1], Value::from(vec![1.5f64, 2.]));
+        assert_eq!(*values[2], Value::new(Value::from(vec![(2i32, 3i32)])));
+
+        let values: Vec<Value<'_>> = values.into_iter().map(Into::into).collect();
+        assert_eq!(
+            to_busctl_args(&values),
+            ["x", "-3", "true", "2", "1.5", "2", "a(ii)", "1", "2", "3"]
+        );
+
+        let signature = Signature::try_from("y").unwrap();
+        assert!(from_busctl_args(&signature, ["256"]).is_err());
+        assert!(from_busctl_args(&signature, ["1", "2"]).is_err());
+        assert!(from_busctl_args(&signature, [""; 0]).is_err());
+        let signature = Signature::try_from("v").unwrap();
+        assert!(from_busctl_args(&signature, ["ii", "1", "2"]).is_err());
+
+        #[cfg(unix)]
+        {
+            let signature = Signature::try_from("ah").unwrap();
+            let values = from_busctl_args(&signature, ["1", "4"]).unwrap();
+            assert_eq!(*values[0], Value::from(vec![Fd::from(4)]));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn json_bridge() {
+        use crate::json::{from_json, to_json};
+        use serde_json::json;
+
+        let mut map: HashMap<u32, Value<'_>> = HashMap::new();
+        map.insert(1, Value::new(ObjectPath::try_from("/a").unwrap()));
+        let value = Value::from((-5i64, map, vec![1.5f64]));
+        let encoded = to_json(&value);
+        assert_eq!(
+            encoded,
+            json!([-5, {"1": {"signature": "o", "value": "/a"}}, [1.5]])
+        );
+        let signature = Signature::try_from("(xa{uv}ad)").unwrap();
+        assert_eq!(*from_json(&encoded, &signature).unwrap(), value);
+
+        // Type information comes from the signature.
+        let signature = Signature::try_from("(ta{yv}ad)").unwrap();
+        let decoded = from_json(
+            &json!([5, {"1": {"signature": "s", "value": "x"}}, [2]]),
+            &signature,
+        )
+        .unwrap();
+        assert_eq!(decoded.value_signature(), "(ta{yv}ad)");
+        assert!(from_json(&encoded, &signature).is_err());
+
+        let signature = Signature::try_from("y").unwrap();
+        assert!(from_json(&json!(256), &signature).is_err());
+        assert!(from_json(&json!("1"), &signature).is_err());
+        let signature = Signature::try_from("v").unwrap();
+        assert!(from_json(&json!(1), &signature).is_err());
+
+        // Non-finite doubles.
+        let value = Value::from(vec![f64::INFINITY, f64::NEG_INFINITY, 0.5]);
+        let encoded = to_json(&value);
+        assert_eq!(encoded, json!(["Infinity", "-Infinity", 0.5]));
+        let signature = Signature::try_from("ad").unwrap();
+        assert_eq!(*from_json(&encoded, &signature).unwrap(), value);
+        let encoded = to_json(&Value::from(f64::NAN));
+        assert_eq!(encoded, json!("NaN"));
+        let signature = Signature::try_from("d").unwrap();
+        match *from_json(&encoded, &signature).unwrap() {
+            Value::F64(v) => assert!(v.is_nan()),
+            _ => panic!("expected a double"),
+        }
+        assert!(from_json(&json!("1.5"), &signature).is_err());
+
+        #[cfg(feature = "gvariant")]
+        {
+            let signature = Signature::try_from("amms").unwrap();
+            let encoded = json!([null, [null], ["x"]]);
+            let decoded = from_json(&encoded, &signature).unwrap();
+            assert_eq!(to_json(&decoded), encoded);
+        }
+    }
+
     #[test]
     fn ip_addr() {
         let localhost_v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
@@ -1778,6 +2187,103 @@ mod tests {
         assert_eq!(localhost_v6, decoded);
     }
 
+    #[test]
+    fn std_types() {
+        std_types_test(Context::<LE>::new_dbus(0));
+        #[cfg(feature = "gvariant")]
+        std_types_test(Context::<LE>::new_gvariant(0));
+    }
+
+    fn std_types_test(ctxt: Context<LE>) {
+        use std::{
+            collections::{BTreeSet, HashSet, VecDeque},
+            net::{SocketAddr, SocketAddrV6},
+            num::{NonZeroI64, NonZeroU16, Wrapping},
+            time::Duration,
+        };
+
+        fn round_trip<T>(ctxt: Context<LE>, value: T, signature: &str)
+        where
+            T: Serialize + serde::de::DeserializeOwned + Type + PartialEq + std::fmt::Debug,
+        {
+            assert_eq!(T::signature(), signature);
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            let decoded: T = from_slice(&encoded, ctxt).unwrap();
+            assert_eq!(decoded, value);
+        }
+
+        round_trip(ctxt, NonZeroU16::new(7).unwrap(), "q");
+        round_trip(ctxt, NonZeroI64::new(-7).unwrap(), "x");
+        let encoded = to_bytes(ctxt, &0u16).unwrap();
+        assert!(from_slice::<_, NonZeroU16>(&encoded, ctxt).is_err());
+        round_trip(ctxt, Wrapping(42u32), "u");
+        round_trip(ctxt, Duration::new(42, 123_456_789), "(tu)");
+        round_trip(ctxt, 1u32..5, "(uu)");
+        round_trip(ctxt, -1i16..=1, "(nn)");
+        round_trip(ctxt, SocketAddr::from(([127, 0, 0, 1], 8080)), "(u(ayq))");
+        round_trip(
+            ctxt,
+            SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0)),
+            "(u(ayq))",
+        );
+        round_trip(
+            ctxt,
+            ["a", "b"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<HashSet<_>>(),
+            "as",
+        );
+        round_trip(
+            ctxt,
+            vec![3u8, 1, 2].into_iter().collect::<BTreeSet<_>>(),
+            "ay",
+        );
+        round_trip(ctxt, VecDeque::from(vec![1i32, -1]), "ai");
+        round_trip(
+            ctxt,
+            (Duration::from_millis(1), vec![Wrapping(1u8)]),
+            "((tu)ay)",
+        );
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+            // Not valid UTF-8.
+            let os_string = OsString::from_vec(vec![b'f', 0xff, b'o']);
+            round_trip(ctxt, os_string.clone(), "ay");
+            round_trip(ctxt, (os_string, 7u8), "(ayy)");
+            let encoded = to_bytes(ctxt, &OsString::from("foo")).unwrap();
+            let decoded: Vec<u8> = from_slice(&encoded, ctxt).unwrap();
+            assert_eq!(decoded, b"foo");
+
+            // Other enums with the same name are encoded like any other enum.
+            mod other {
+                #[derive(serde::Serialize, serde::Deserialize, crate::Type, PartialEq, Debug)]
+                pub enum OsString {
+                    Unix(Vec<u8>),
+                    Windows(Vec<u8>),
+                }
+            }
+            round_trip(ctxt, other::OsString::Unix(b"foo".to_vec()), "(uay)");
+        }
+
+        #[cfg(feature = "bytes")]
+        round_trip(ctxt, bytes::Bytes::from_static(b"bytes"), "ay");
+        #[cfg(feature = "smallvec")]
+        round_trip(ctxt, smallvec::SmallVec::from([1u32, 2]), "au");
+        #[cfg(feature = "heapless")]
+        {
+            round_trip(
+                ctxt,
+                heapless::Vec::<u64, 4>::from_slice(&[1, 2]).unwrap(),
+                "at",
+            );
+            round_trip(ctxt, heapless::String::<8>::from("hi"), "s");
+        }
+    }
+
     #[cfg(feature = "ostree-tests")]
     #[test]
     fn ostree_de() {
@@ -1835,6 +2341,108 @@ mod tests {
         assert_eq!(date, decoded);
     }
 
+    #[test]
+    fn array_and_dict_iter() {
+        let ctxt = Context::<LE>::new_dbus(0);
+        array_and_dict_iter_test(ctxt);
+        let ctxt = Context::<BE>::new_dbus(3);
+        array_and_dict_iter_test(ctxt);
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::<LE>::new_gvariant(0);
+            array_and_dict_iter_test(ctxt);
+            let ctxt = Context::<LE>::new_gvariant(5);
+            array_and_dict_iter_test(ctxt);
+        }
+    }
+
+    fn array_and_dict_iter_test<B: ByteOrder>(ctxt: Context<B>) {
+        // Non-fixed-sized elements.
+        let names: Vec<_> = (0..1000u32).map(|i| (i, format!("name{i}"))).collect();
+        let encoded = to_bytes(ctxt, &(names.clone(), 42u8)).unwrap();
+        let (iter, tail): (ArrayIter<'_, (u32, &str)>, u8) = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(tail, 42);
+        assert!(!iter.is_empty());
+        let decoded: Vec<_> = iter.map(|e| e.unwrap()).collect();
+        assert_eq!(decoded.len(), names.len());
+        assert_eq!(decoded[999], (999, "name999"));
+
+        // Fixed-sized elements.
+        let numbers: Vec<u64> = (0..100).collect();
+        let encoded = to_bytes(ctxt, &(7u8, &numbers)).unwrap();
+        let (_, iter): (u8, ArrayIter<'_, u64>) = from_slice(&encoded, ctxt).unwrap();
+        assert_eq!(iter.map(|e| e.unwrap()).collect::<Vec<_>>(), numbers);
+
+        // Empty & nested arrays.
+        let nested = vec![vec!["x", "yy"], vec![], vec!["zzz"]];
+        let encoded = to_bytes(ctxt, &nested).unwrap();
+        let iter: ArrayIter<'_, ArrayIter<'_, &str>> = from_slice(&encoded, ctxt).unwrap();
+        let lens: Vec<_> = iter.map(|e| e.unwrap().count()).collect();
+        assert_eq!(lens, [2, 0, 1]);
+
+        // Dictionaries.
+        let mut dict = HashMap::new();
+        dict.insert("Volume", Value::from(50u8));
+        dict.insert("Title", Value::from("Lazy"));
+        let encoded = to_bytes(ctxt, &dict).unwrap();
+        let iter: DictIter<'_, &str, Value<'_>> = from_slice(&encoded, ctxt).unwrap();
+        let decoded: HashMap<_, _> = iter.map(|e| e.unwrap()).collect();
+        assert_eq!(decoded, dict);
+
+        // Iteration stops at the first invalid element.
+        let encoded = to_bytes(ctxt, &vec!["/a", "b", "/c"]).unwrap();
+        let signature = Signature::try_from("as").unwrap();
+        let iter: ArrayIter<'_, ObjectPath<'_>> =
+            from_slice_for_signature(&encoded, ctxt, &signature).unwrap();
+        let decoded: Vec<_> = iter.collect();
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[1].is_err());
+    }
+
+    #[test]
+    fn error_context() {
+        let mut dict = HashMap::new();
+        dict.insert(7u32, vec!["/a", "b"]);
+        let value = (42u32, "hello", dict);
+        let signature = Signature::try_from("(usa{uas})").unwrap();
+
+        let ctxt = Context::<LE>::new_dbus(0);
+        let encoded = to_bytes(ctxt, &value).unwrap();
+        let err = from_slice_for_signature::<_, (u32, &str, HashMap<u32, Vec<ObjectPath<'_>>>)>(
+            &encoded, ctxt, &signature,
+        )
+        .unwrap_err();
+        let context = err.context().unwrap();
+        assert_eq!(context.path(), "[2].a{uas}[7].as[1]");
+        assert_eq!(context.offset(), 40);
+        assert!(matches!(err.root_cause(), Error::Message(_)));
+        assert_eq!(
+            err.prefix_path("body").context().unwrap().path(),
+            "body[2].a{uas}[7].as[1]"
+        );
+
+        // Keys are reported by their index.
+        let err = from_slice_for_signature::<_, (u32, &str, HashMap<bool, Vec<&str>>)>(
+            &encoded, ctxt, &signature,
+        )
+        .unwrap_err();
+        let context = err.context().unwrap();
+        assert_eq!(context.path(), "[2].a{uas}[0]");
+        assert_eq!(context.offset(), 24);
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::<LE>::new_gvariant(0);
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            let err =
+                from_slice_for_signature::<_, (u32, &str, HashMap<u32, Vec<ObjectPath<'_>>>)>(
+                    &encoded, ctxt, &signature,
+                )
+                .unwrap_err();
+            assert_eq!(err.context().unwrap().path(), "[2].a{uas}[7].as[1]");
+        }
+    }
+
     #[test]
     fn recursion_limits() {
         let ctxt = Context::<LE>::new_dbus(0);
@@ -24,6 +24,11 @@ impl<'a> Maybe<'a> {
         &self.value
     }
 
+    /// Get a mutable reference to underlying value. Its signature must not be changed.
+    pub(crate) fn inner_mut(&mut self) -> &mut Option<Value<'a>> {
+        &mut self.value
+    }
+
     /// Create a new Just (Some) `Maybe`.
     pub fn just(value: Value<'a>) -> Self {
         let value_signature = value.value_signature().to_owned();
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Deserializer, Serialize};
 use static_assertions::assert_impl_all;
-use std::{collections::HashMap, convert::TryFrom, hash::BuildHasher};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    hash::BuildHasher,
+};
 
 use crate::{
     Array, Dict, ObjectPath, OwnedObjectPath, OwnedSignature, Signature, Str, Structure, Type,
@@ -13,7 +17,10 @@ use crate::Fd;
 #[cfg(feature = "gvariant")]
 use crate::Maybe;
 
-// FIXME: Replace with a generic impl<T: TryFrom<Value>> TryFrom<OwnedValue> for T?
+// A generic `impl<T: TryFrom<Value>> TryFrom<OwnedValue> for T` would conflict with the blanket
+// `impl<T, U: Into<T>> TryFrom<U> for T` from core, so `TryFrom<OwnedValue>` is implemented for
+// each type (or type constructor) that implements `TryFrom<Value>`. For any other such type,
+// `OwnedValue::downcast` provides the conversion.
 // https://gitlab.freedesktop.org/dbus/zbus/-/issues/138
 
 /// Owned [`Value`](enum.Value.html)
@@ -30,6 +37,28 @@ impl OwnedValue {
     pub(crate) fn inner(&self) -> &Value<'_> {
         &self.0
     }
+
+    /// Try to get the underlying type `T`.
+    ///
+    /// This is the same as [`Value::downcast`] and works for any type that implements
+    /// `TryFrom<Value>`, even the ones that don't implement `TryFrom<OwnedValue>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{OwnedValue, Value};
+    ///
+    /// let v = OwnedValue::from(Value::new(Value::from("hello")));
+    /// assert_eq!(v.downcast::<Value<'_>>(), Some(Value::from("hello")));
+    /// let v = OwnedValue::from(Value::from(vec!["hello", "world"]));
+    /// assert_eq!(v.downcast::<Vec<String>>(), Some(vec!["hello".into(), "world".into()]));
+    /// ```
+    pub fn downcast<T>(self) -> Option<T>
+    where
+        T: TryFrom<Value<'static>>,
+    {
+        self.0.downcast()
+    }
 }
 
 macro_rules! ov_try_from {
@@ -148,6 +177,44 @@ where
     }
 }
 
+impl<'k, 'v, K, V> TryFrom<OwnedValue> for BTreeMap<K, V>
+where
+    K: crate::Basic + TryFrom<Value<'k>> + std::cmp::Ord,
+    V: TryFrom<Value<'v>>,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = crate::Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        if let Value::Dict(v) = value.0 {
+            Self::try_from(v)
+        } else {
+            Err(crate::Error::IncorrectType)
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'k, 'v, K, V, H> TryFrom<OwnedValue> for indexmap::IndexMap<K, V, H>
+where
+    K: crate::Basic + TryFrom<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    V: TryFrom<Value<'v>>,
+    H: BuildHasher + Default,
+    K::Error: Into<crate::Error>,
+    V::Error: Into<crate::Error>,
+{
+    type Error = crate::Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        if let Value::Dict(v) = value.0 {
+            Self::try_from(v)
+        } else {
+            Err(crate::Error::IncorrectType)
+        }
+    }
+}
+
 impl<K, V, H> From<HashMap<K, V, H>> for OwnedValue
 where
     K: Type + Into<Value<'static>> + std::hash::Hash + std::cmp::Eq,
@@ -159,6 +226,28 @@ where
     }
 }
 
+impl<K, V> From<BTreeMap<K, V>> for OwnedValue
+where
+    K: Type + Into<Value<'static>> + std::cmp::Ord,
+    V: Type + Into<Value<'static>>,
+{
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Self(value.into())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V, H> From<indexmap::IndexMap<K, V, H>> for OwnedValue
+where
+    K: Type + Into<Value<'static>> + std::hash::Hash + std::cmp::Eq,
+    V: Type + Into<Value<'static>>,
+    H: BuildHasher + Default,
+{
+    fn from(value: indexmap::IndexMap<K, V, H>) -> Self {
+        Self(value.into())
+    }
+}
+
 // tuple conversions in `structure` module for avoiding code-duplication.
 
 impl<'a> From<Value<'a>> for OwnedValue {
@@ -236,7 +325,12 @@ impl<'de> Deserialize<'de> for OwnedValue {
 #[cfg(test)]
 mod tests {
     use byteorder::LE;
-    use std::{collections::HashMap, convert::TryFrom, error::Error, result::Result};
+    use std::{
+        collections::{BTreeMap, HashMap},
+        convert::TryFrom,
+        error::Error,
+        result::Result,
+    };
 
     use crate::{from_slice, to_bytes, EncodingContext, OwnedValue, Value};
 
@@ -287,4 +381,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn btree_map_conversion() -> Result<(), Box<dyn Error>> {
+        let mut map = BTreeMap::<u32, Vec<String>>::new();
+        map.insert(1, vec!["one".to_string()]);
+        map.insert(2, vec!["two".to_string(), "deux".to_string()]);
+        let value = OwnedValue::from(map.clone());
+        assert_eq!(value.value_signature(), "a{uas}");
+        assert_eq!(<BTreeMap<u32, Vec<String>>>::try_from(value.clone())?, map);
+        assert_eq!(
+            <BTreeMap<u32, Vec<String>>>::try_from(Value::from(value.clone()))?,
+            map
+        );
+        assert!(<BTreeMap<u32, String>>::try_from(value.clone()).is_err());
+        assert_eq!(value.downcast::<BTreeMap<u32, Vec<String>>>(), Some(map));
+
+        Ok(())
+    }
 }
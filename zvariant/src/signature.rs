@@ -271,6 +271,53 @@ impl<'a> Signature<'a> {
 
         clone
     }
+
+    /// Iterate over the top-level complete types in `self`.
+    ///
+    /// Use [`SignatureType`] if you need to inspect the individual types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::convert::TryFrom;
+    /// use zvariant::Signature;
+    ///
+    /// let signature = Signature::try_from("sa{sv}(ii)").unwrap();
+    /// let types: Vec<_> = signature.complete_types().collect();
+    /// assert_eq!(types, ["s", "a{sv}", "(ii)"]);
+    /// ```
+    ///
+    /// [`SignatureType`]: enum.SignatureType.html
+    pub fn complete_types(&self) -> CompleteTypes<'a> {
+        CompleteTypes(SignatureParser::new(self.clone()))
+    }
+}
+
+/// Iterator over the top-level complete types of a [`Signature`].
+///
+/// Created by [`Signature::complete_types`]. Iteration stops at the first invalid type, which can
+/// only happen if the signature was created through an unchecked constructor.
+#[derive(Debug, Clone)]
+pub struct CompleteTypes<'a>(SignatureParser<'a>);
+
+impl<'a> Iterator for CompleteTypes<'a> {
+    type Item = Signature<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.done() {
+            return None;
+        }
+
+        match self.0.parse_next_signature() {
+            Ok(signature) => Some(signature),
+            Err(_) => {
+                // Make sure we don't try again.
+                self.0 = SignatureParser::new(Signature::from_str_unchecked(""));
+
+                None
+            }
+        }
+    }
 }
 
 impl<'a> Debug for Signature<'a> {
@@ -0,0 +1,478 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter, Write},
+    str::FromStr,
+};
+
+#[cfg(feature = "gvariant")]
+use crate::MAYBE_SIGNATURE_CHAR;
+use crate::{
+    signature_parser::SignatureParser, utils::*, Basic, EncodingFormat, Error, ObjectPath, Result,
+    Signature, Type,
+};
+
+// `Fd::SIGNATURE_CHAR`, as `Fd` is only available on Unix.
+const FD_SIGNATURE_CHAR: char = 'h';
+
+/// The structure of a single complete type signature.
+///
+/// While [`Signature`] is a validated string, `SignatureType` is its parsed form, which allows
+/// inspecting and creating signatures without any string manipulation.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use zvariant::{Signature, SignatureType};
+///
+/// let signature = Signature::try_from("a{sv}").unwrap();
+/// let parsed = SignatureType::try_from(&signature).unwrap();
+/// assert_eq!(
+///     parsed,
+///     SignatureType::Dict {
+///         key: Box::new(SignatureType::Str),
+///         value: Box::new(SignatureType::Variant),
+///     },
+/// );
+/// assert_eq!(parsed.to_string(), "a{sv}");
+///
+/// let created = SignatureType::Struct(vec![
+///     SignatureType::U32,
+///     SignatureType::Array(Box::new(SignatureType::ObjectPath)),
+/// ]);
+/// assert_eq!(created.to_signature().unwrap(), "(uao)");
+///
+/// // Only single complete types can be parsed.
+/// assert!("ss".parse::<SignatureType>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignatureType {
+    /// `y`
+    U8,
+    /// `b`
+    Bool,
+    /// `n`
+    I16,
+    /// `q`
+    U16,
+    /// `i`
+    I32,
+    /// `u`
+    U32,
+    /// `x`
+    I64,
+    /// `t`
+    U64,
+    /// `d`
+    F64,
+    /// `s`
+    Str,
+    /// `o`
+    ObjectPath,
+    /// `g`
+    Signature,
+    /// `v`
+    Variant,
+    /// `h`, a file descriptor.
+    ///
+    /// Signatures containing it are only valid on Unix.
+    Fd,
+    /// An array of the given element type (`a` followed by the element type).
+    Array(Box<SignatureType>),
+    /// A dictionary, i-e an array of dict entries (`a{`, followed by the key and value types and
+    /// `}`). The key must be a basic type.
+    Dict {
+        /// The type of the keys.
+        key: Box<SignatureType>,
+        /// The type of the values.
+        value: Box<SignatureType>,
+    },
+    /// A structure with the given fields (`(`, followed by the field types and `)`). There must
+    /// be at least one field.
+    Struct(Vec<SignatureType>),
+    /// A maybe type (GVariant only) of the given type (`m` followed by the child type).
+    #[cfg(feature = "gvariant")]
+    Maybe(Box<SignatureType>),
+}
+
+impl SignatureType {
+    /// Create the signature string for `self`.
+    ///
+    /// This fails if `self` isn't valid, i-e it contains empty structures or the resulting
+    /// signature is too long.
+    pub fn to_signature(&self) -> Result<Signature<'static>> {
+        Signature::try_from(self.to_string())
+    }
+
+    /// Whether `self` is a [basic type].
+    ///
+    /// [basic type]: https://dbus.freedesktop.org/doc/dbus-specification.html#basic-types
+    pub fn is_basic(&self) -> bool {
+        !matches!(
+            self,
+            Self::Variant | Self::Array(_) | Self::Dict { .. } | Self::Struct(_)
+        ) && !self.is_maybe()
+    }
+
+    /// The alignment of values of this type in the given encoding format.
+    #[cfg_attr(not(feature = "gvariant"), allow(unused_variables))]
+    pub fn alignment(&self, format: EncodingFormat) -> usize {
+        match self {
+            Self::U8 => u8::alignment(format),
+            Self::Bool => bool::alignment(format),
+            Self::I16 => i16::alignment(format),
+            Self::U16 => u16::alignment(format),
+            Self::I32 => i32::alignment(format),
+            Self::U32 => u32::alignment(format),
+            Self::I64 => i64::alignment(format),
+            Self::U64 => u64::alignment(format),
+            Self::F64 => f64::alignment(format),
+            Self::Str => <&str>::alignment(format),
+            Self::ObjectPath => ObjectPath::alignment(format),
+            Self::Signature => Signature::alignment(format),
+            Self::Fd => u32::alignment(format),
+            Self::Variant => match format {
+                EncodingFormat::DBus => VARIANT_ALIGNMENT_DBUS,
+                #[cfg(feature = "gvariant")]
+                EncodingFormat::GVariant => VARIANT_ALIGNMENT_GVARIANT,
+            },
+            Self::Array(element) => match format {
+                EncodingFormat::DBus => ARRAY_ALIGNMENT_DBUS,
+                #[cfg(feature = "gvariant")]
+                EncodingFormat::GVariant => element.alignment(format),
+            },
+            Self::Dict { key, value } => match format {
+                EncodingFormat::DBus => ARRAY_ALIGNMENT_DBUS,
+                #[cfg(feature = "gvariant")]
+                EncodingFormat::GVariant => key.alignment(format).max(value.alignment(format)),
+            },
+            Self::Struct(fields) => match format {
+                EncodingFormat::DBus => STRUCT_ALIGNMENT_DBUS,
+                #[cfg(feature = "gvariant")]
+                EncodingFormat::GVariant => fields
+                    .iter()
+                    .map(|field| field.alignment(format))
+                    .max()
+                    .unwrap_or(1),
+            },
+            #[cfg(feature = "gvariant")]
+            Self::Maybe(child) => match format {
+                EncodingFormat::DBus => 1,
+                EncodingFormat::GVariant => child.alignment(format),
+            },
+        }
+    }
+
+    fn is_maybe(&self) -> bool {
+        #[cfg(feature = "gvariant")]
+        if let Self::Maybe(_) = self {
+            return true;
+        }
+
+        false
+    }
+
+    // `signature` must be **one** complete and correct signature.
+    fn from_complete_signature(signature: &Signature<'_>) -> Result<Self> {
+        let bytes = signature.as_bytes();
+        let c = bytes
+            .first()
+            .map(|b| *b as char)
+            .ok_or_else(|| -> Error { serde::de::Error::invalid_length(0, &">= 1 character") })?;
+
+        let parsed = match c {
+            u8::SIGNATURE_CHAR => Self::U8,
+            bool::SIGNATURE_CHAR => Self::Bool,
+            i16::SIGNATURE_CHAR => Self::I16,
+            u16::SIGNATURE_CHAR => Self::U16,
+            i32::SIGNATURE_CHAR => Self::I32,
+            u32::SIGNATURE_CHAR => Self::U32,
+            i64::SIGNATURE_CHAR => Self::I64,
+            u64::SIGNATURE_CHAR => Self::U64,
+            f64::SIGNATURE_CHAR => Self::F64,
+            <&str>::SIGNATURE_CHAR => Self::Str,
+            ObjectPath::SIGNATURE_CHAR => Self::ObjectPath,
+            Signature::SIGNATURE_CHAR => Self::Signature,
+            VARIANT_SIGNATURE_CHAR => Self::Variant,
+            FD_SIGNATURE_CHAR => Self::Fd,
+            ARRAY_SIGNATURE_CHAR if bytes.get(1) == Some(&(DICT_ENTRY_SIG_START_CHAR as u8)) => {
+                // `a{` + key signature + value signature + `}`
+                let key = Self::from_complete_signature(&signature.slice(2..3))?;
+                if !key.is_basic() {
+                    return Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Str(signature),
+                        &"a dictionary with a basic key type",
+                    ));
+                }
+                let value =
+                    Self::from_complete_signature(&signature.slice(3..signature.len() - 1))?;
+
+                Self::Dict {
+                    key: Box::new(key),
+                    value: Box::new(value),
+                }
+            }
+            ARRAY_SIGNATURE_CHAR => Self::Array(Box::new(Self::from_complete_signature(
+                &signature.slice(1..),
+            )?)),
+            STRUCT_SIG_START_CHAR => {
+                let mut parser = SignatureParser::new(signature.slice(1..signature.len() - 1));
+                let mut fields = vec![];
+                while !parser.done() {
+                    fields.push(Self::from_complete_signature(
+                        &parser.parse_next_signature()?,
+                    )?);
+                }
+
+                Self::Struct(fields)
+            }
+            #[cfg(feature = "gvariant")]
+            MAYBE_SIGNATURE_CHAR => Self::Maybe(Box::new(Self::from_complete_signature(
+                &signature.slice(1..),
+            )?)),
+            _ => {
+                return Err(serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Str(signature),
+                    &"a single complete type, outside of a dict entry",
+                ))
+            }
+        };
+
+        Ok(parsed)
+    }
+}
+
+impl Display for SignatureType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Self::U8 => u8::SIGNATURE_CHAR,
+            Self::Bool => bool::SIGNATURE_CHAR,
+            Self::I16 => i16::SIGNATURE_CHAR,
+            Self::U16 => u16::SIGNATURE_CHAR,
+            Self::I32 => i32::SIGNATURE_CHAR,
+            Self::U32 => u32::SIGNATURE_CHAR,
+            Self::I64 => i64::SIGNATURE_CHAR,
+            Self::U64 => u64::SIGNATURE_CHAR,
+            Self::F64 => f64::SIGNATURE_CHAR,
+            Self::Str => <&str>::SIGNATURE_CHAR,
+            Self::ObjectPath => ObjectPath::SIGNATURE_CHAR,
+            Self::Signature => Signature::SIGNATURE_CHAR,
+            Self::Variant => VARIANT_SIGNATURE_CHAR,
+            Self::Fd => FD_SIGNATURE_CHAR,
+            Self::Array(element) => {
+                f.write_char(ARRAY_SIGNATURE_CHAR)?;
+
+                return element.fmt(f);
+            }
+            Self::Dict { key, value } => {
+                f.write_char(ARRAY_SIGNATURE_CHAR)?;
+                f.write_char(DICT_ENTRY_SIG_START_CHAR)?;
+                key.fmt(f)?;
+                value.fmt(f)?;
+
+                return f.write_char(DICT_ENTRY_SIG_END_CHAR);
+            }
+            Self::Struct(fields) => {
+                f.write_char(STRUCT_SIG_START_CHAR)?;
+                for field in fields {
+                    field.fmt(f)?;
+                }
+
+                return f.write_char(STRUCT_SIG_END_CHAR);
+            }
+            #[cfg(feature = "gvariant")]
+            Self::Maybe(child) => {
+                f.write_char(MAYBE_SIGNATURE_CHAR)?;
+
+                return child.fmt(f);
+            }
+        };
+
+        f.write_char(c)
+    }
+}
+
+impl<'a> TryFrom<&Signature<'a>> for SignatureType {
+    type Error = Error;
+
+    fn try_from(signature: &Signature<'a>) -> Result<Self> {
+        let mut parser = SignatureParser::new(signature.clone());
+        let complete = parser.parse_next_signature()?;
+        if !parser.done() {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(signature),
+                &"a single complete type",
+            ));
+        }
+
+        Self::from_complete_signature(&complete)
+    }
+}
+
+impl<'a> TryFrom<Signature<'a>> for SignatureType {
+    type Error = Error;
+
+    fn try_from(signature: Signature<'a>) -> Result<Self> {
+        Self::try_from(&signature)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for SignatureType {
+    type Error = Error;
+
+    fn try_from(signature: &'a str) -> Result<Self> {
+        Self::try_from(Signature::try_from(signature)?)
+    }
+}
+
+impl FromStr for SignatureType {
+    type Err = Error;
+
+    fn from_str(signature: &str) -> Result<Self> {
+        Self::try_from(signature)
+    }
+}
+
+/// Use this to build a signature out of any number of complete types.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{SignatureBuilder, SignatureType, Value};
+///
+/// // The signature of a method call body.
+/// let signature = SignatureBuilder::new()
+///     .add::<&str>()
+///     .add::<HashMap<&str, Value<'_>>>()
+///     .append(SignatureType::Array(Box::new(SignatureType::U8)))
+///     .build()
+///     .unwrap();
+/// assert_eq!(signature, "sa{sv}ay");
+///
+/// // Or a structure.
+/// let structure = SignatureBuilder::new().add::<u32>().add::<(u8, u64)>().build_struct();
+/// assert_eq!(structure.to_signature().unwrap(), "(u(yt))");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SignatureBuilder(Vec<SignatureType>);
+
+impl SignatureBuilder {
+    /// Create a new `SignatureBuilder`.
+    ///
+    /// Same as `SignatureBuilder::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the signature of `T` to `self`.
+    ///
+    /// # Panics
+    ///
+    /// If the signature of `T` is invalid.
+    #[must_use]
+    pub fn add<T>(mut self) -> Self
+    where
+        T: Type + ?Sized,
+    {
+        let signature = T::signature();
+        for complete in signature.complete_types() {
+            let parsed = SignatureType::from_complete_signature(&complete)
+                .unwrap_or_else(|e| panic!("Invalid signature `{}`: {}", signature, e));
+            self.0.push(parsed);
+        }
+
+        self
+    }
+
+    /// Append `signature_type` to `self`.
+    #[must_use]
+    pub fn append(mut self, signature_type: SignatureType) -> Self {
+        self.0.push(signature_type);
+
+        self
+    }
+
+    /// Append `signature_type` to `self`.
+    ///
+    /// Identical to `append`, except it makes changes in-place.
+    pub fn push(&mut self, signature_type: SignatureType) {
+        self.0.push(signature_type);
+    }
+
+    /// The complete types appended so far.
+    pub fn types(&self) -> &[SignatureType] {
+        &self.0
+    }
+
+    /// Build the signature, consisting of all the appended complete types.
+    pub fn build(self) -> Result<Signature<'static>> {
+        let mut signature = String::new();
+        for signature_type in &self.0 {
+            // Writing to a `String` can't fail.
+            let _ = write!(signature, "{signature_type}");
+        }
+
+        Signature::try_from(signature)
+    }
+
+    /// Build a structure type, with all the appended complete types as its fields.
+    pub fn build_struct(self) -> SignatureType {
+        SignatureType::Struct(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::{SignatureBuilder, SignatureType};
+    use crate::{EncodingFormat, Signature};
+
+    #[test]
+    fn signature_type() {
+        let signature = Signature::try_from("(ya{s(ux)}avaah)").unwrap();
+        let parsed = SignatureType::try_from(&signature).unwrap();
+        let expected = SignatureType::Struct(vec![
+            SignatureType::U8,
+            SignatureType::Dict {
+                key: Box::new(SignatureType::Str),
+                value: Box::new(SignatureType::Struct(vec![
+                    SignatureType::U32,
+                    SignatureType::I64,
+                ])),
+            },
+            SignatureType::Array(Box::new(SignatureType::Variant)),
+            SignatureType::Array(Box::new(SignatureType::Array(Box::new(SignatureType::Fd)))),
+        ]);
+        assert_eq!(parsed, expected);
+        assert_eq!(expected.to_signature().unwrap(), signature);
+        assert!(!parsed.is_basic());
+        assert_eq!(parsed.alignment(EncodingFormat::DBus), 8);
+
+        for invalid in ["", "ss", "{sv}", "a{vs}", "(s"] {
+            assert!(
+                SignatureType::try_from(invalid).is_err(),
+                "`{}` parsed",
+                invalid
+            );
+        }
+        assert!(SignatureType::Struct(vec![]).to_signature().is_err());
+
+        let signature = SignatureBuilder::new()
+            .add::<(u8, &str)>()
+            .add::<()>()
+            .append(parsed)
+            .build()
+            .unwrap();
+        assert_eq!(signature.complete_types().count(), 2);
+
+        #[cfg(feature = "gvariant")]
+        {
+            let parsed = SignatureType::try_from("(ymt)").unwrap();
+            assert_eq!(parsed.alignment(EncodingFormat::GVariant), 8);
+            let parsed = SignatureType::try_from("a(yq)").unwrap();
+            assert_eq!(parsed.alignment(EncodingFormat::GVariant), 2);
+        }
+    }
+}
@@ -169,6 +169,11 @@ impl<'a> Structure<'a> {
         &self.fields
     }
 
+    /// Get a mutable reference to all the fields. The signature of the fields must not be changed.
+    pub(crate) fn fields_mut(&mut self) -> &mut [Value<'a>] {
+        &mut self.fields
+    }
+
     /// Converts `self` to a `Vec` containing all its fields.
     pub fn into_fields(self) -> Vec<Value<'a>> {
         self.fields
@@ -3,7 +3,12 @@ use serde::de::{Deserialize, DeserializeSeed};
 use std::{
     convert::TryInto,
     marker::PhantomData,
-    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8, Wrapping,
+    },
+    ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
     rc::Rc,
     sync::{Arc, Mutex, RwLock},
@@ -156,6 +161,21 @@ macro_rules! array_type {
 
 array_type!([T]);
 array_type!(Vec<T>);
+array_type!(VecDeque<T>);
+array_type!(LinkedList<T>);
+array_type!(BinaryHeap<T>);
+array_type!(BTreeSet<T>);
+
+impl<T, H> Type for HashSet<T, H>
+where
+    T: Type,
+    H: BuildHasher,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
 
 #[cfg(feature = "arrayvec")]
 impl<T, const CAP: usize> Type for arrayvec::ArrayVec<T, CAP>
@@ -206,6 +226,17 @@ deref_impl!(T, <T: ?Sized + Type> Type for Mutex<T>);
 deref_impl!(T, <T: ?Sized + Type> Type for RwLock<T>);
 deref_impl!(T, <T: ?Sized + Type> Type for Box<T>);
 deref_impl!(T, <T: ?Sized + Type> Type for Rc<T>);
+deref_impl!(T, <T: Type> Type for Wrapping<T>);
+
+// Serde serializes non-zero integers as their primitive counterparts.
+deref_impl!(u8, <> Type for NonZeroU8);
+deref_impl!(u16, <> Type for NonZeroU16);
+deref_impl!(u32, <> Type for NonZeroU32);
+deref_impl!(u64, <> Type for NonZeroU64);
+deref_impl!(i8, <> Type for NonZeroI8);
+deref_impl!(i16, <> Type for NonZeroI16);
+deref_impl!(i32, <> Type for NonZeroI32);
+deref_impl!(i64, <> Type for NonZeroI64);
 
 #[cfg(feature = "gvariant")]
 impl<T> Type for Option<T>
@@ -289,11 +320,15 @@ where
 
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    ffi::{OsStr, OsString},
     hash::{BuildHasher, Hash},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+
 macro_rules! map_impl {
     ($ty:ident < K $(: $kbound1:ident $(+ $kbound2:ident)*)*, V $(, $typaram:ident : $bound:ident)* >) => {
         impl<K, V $(, $typaram)*> Type for $ty<K, V $(, $typaram)*>
@@ -312,6 +347,8 @@ macro_rules! map_impl {
 
 map_impl!(BTreeMap<K: Ord, V>);
 map_impl!(HashMap<K: Eq + Hash, V, H: BuildHasher>);
+#[cfg(feature = "indexmap")]
+map_impl!(IndexMap<K: Eq + Hash, V, H: BuildHasher>);
 
 impl Type for SystemTime {
     #[inline]
@@ -325,6 +362,39 @@ impl Type for SystemTime {
     }
 }
 
+impl Type for Duration {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(
+            // seconds
+            u64,
+            // nano
+            u32,
+        )>::signature()
+    }
+}
+
+// Serialized as a struct of `start` and `end`.
+impl<Idx> Type for Range<Idx>
+where
+    Idx: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(Idx, Idx)>::signature()
+    }
+}
+
+impl<Idx> Type for RangeInclusive<Idx>
+where
+    Idx: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(Idx, Idx)>::signature()
+    }
+}
+
 impl Type for Ipv4Addr {
     #[inline]
     fn signature() -> Signature<'static> {
@@ -346,6 +416,29 @@ impl Type for IpAddr {
     }
 }
 
+// The IP address and the port. The flow info and scope ID of IPv6 socket addresses are not
+// serialized by serde.
+impl Type for SocketAddrV4 {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(&[u8], u16)>::signature()
+    }
+}
+
+impl Type for SocketAddrV6 {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(&[u8], u16)>::signature()
+    }
+}
+
+impl Type for SocketAddr {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <(u32, (&[u8], u16))>::signature()
+    }
+}
+
 // BitFlags
 #[cfg(feature = "enumflags2")]
 impl<F> Type for enumflags2::BitFlags<F>
@@ -358,6 +451,51 @@ where
     }
 }
 
+#[cfg(feature = "bytes")]
+impl Type for bytes::Bytes {
+    fn signature() -> Signature<'static> {
+        Signature::from_static_str_unchecked("ay")
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Type for bytes::BytesMut {
+    fn signature() -> Signature<'static> {
+        Signature::from_static_str_unchecked("ay")
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A> Type for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[A::Item]>::signature()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> Type for heapless::Vec<T, N>
+where
+    T: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Type for heapless::String<N> {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <&str>::signature()
+    }
+}
+
 #[cfg(feature = "serde_bytes")]
 impl Type for serde_bytes::Bytes {
     fn signature() -> Signature<'static> {
@@ -386,6 +524,23 @@ macro_rules! static_str_type {
 static_str_type!(Path);
 static_str_type!(PathBuf);
 
+// Serde encodes OS strings as an enum, with a single `Unix` variant on Unix. The serializers
+// special-case it to encode the bytes of the string directly, so paths and other OS strings that
+// aren't valid UTF-8 can be sent as well.
+#[cfg(unix)]
+impl Type for OsStr {
+    fn signature() -> Signature<'static> {
+        Signature::from_static_str_unchecked("ay")
+    }
+}
+
+#[cfg(unix)]
+impl Type for OsString {
+    fn signature() -> Signature<'static> {
+        Signature::from_static_str_unchecked("ay")
+    }
+}
+
 #[cfg(feature = "uuid")]
 impl Type for uuid::Uuid {
     fn signature() -> Signature<'static> {
@@ -522,5 +677,3 @@ impl<Tz: chrono::TimeZone> Type for chrono::DateTime<Tz> {
 static_str_type!(chrono::NaiveDateTime);
 #[cfg(feature = "chrono")]
 static_str_type!(chrono::NaiveTime);
-
-// TODO: Blanket implementation for more types: https://github.com/serde-rs/serde/blob/master/serde/src/ser/impls.rs
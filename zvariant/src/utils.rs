@@ -46,6 +46,16 @@ pub const MAYBE_SIGNATURE_CHAR: char = 'm';
 #[cfg(feature = "gvariant")]
 pub const MAYBE_SIGNATURE_STR: &str = "m";
 
+// The name and variants of the enum serde (de)serializes `OsStr` and `OsString` as. On Unix, the
+// `Unix` variant is encoded as just the bytes of the string, i.e `ay`, which is the signature
+// `OsString` has there. Other enums named `OsString` are encoded like any other enum.
+#[cfg(unix)]
+pub(crate) const OS_STRING_NAME: &str = "OsString";
+#[cfg(unix)]
+pub(crate) const OS_STRING_VARIANTS: &[&str] = &["Unix", "Windows"];
+#[cfg(unix)]
+pub(crate) const OS_STRING_SIGNATURE: &str = "ay";
+
 pub(crate) fn padding_for_n_bytes(value: usize, align: usize) -> usize {
     let len_rounded_up = value.wrapping_add(align).wrapping_sub(1) & !align.wrapping_sub(1);
 
@@ -1,5 +1,5 @@
 use core::str;
-use std::{convert::TryFrom, marker::PhantomData};
+use std::{borrow::Cow, convert::TryFrom, marker::PhantomData};
 
 use serde::{
     de::{
@@ -224,6 +224,27 @@ impl<'a> Value<'a> {
         }
     }
 
+    /// Print the value in the [GVariant text format].
+    ///
+    /// The value is printed with enough type annotations for it to be parsed back, using
+    /// [`gvariant::from_text`], as a value of the same type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::Value;
+    ///
+    /// let value = Value::from((42u8, Value::from(vec![1i64, 2]), "Hello!"));
+    /// assert_eq!(value.to_gvariant_text(), "(byte 0x2a, <[int64 1, 2]>, 'Hello!')");
+    /// ```
+    ///
+    /// [GVariant text format]: https://docs.gtk.org/glib/gvariant-text.html
+    /// [`gvariant::from_text`]: gvariant/fn.from_text.html
+    #[cfg(feature = "gvariant")]
+    pub fn to_gvariant_text(&self) -> String {
+        crate::gvariant::to_text(self)
+    }
+
     pub(crate) fn serialize_value_as_struct_field<S>(
         &self,
         name: &'static str,
@@ -364,6 +385,214 @@ impl<'a> Value<'a> {
             <&T>::try_from(self).ok()
         }
     }
+
+    /// Look up a value by a [JSON Pointer].
+    ///
+    /// Each reference token of the pointer selects an element of an [`Array`] or a field of a
+    /// [`Structure`] by its index, or the value of a [`Dict`] entry by its key (formatted as a
+    /// string). Variants (and maybe values) are looked through, as if they weren't there. `~1` and
+    /// `~0` escape `/` and `~` in tokens, respectively.
+    ///
+    /// Returns `None` if `pointer` is not a valid JSON Pointer or there is no value at the
+    /// location it refers to. The returned value is never a variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let mut metadata = HashMap::new();
+    /// metadata.insert("xesam:title", Value::from("Moon"));
+    /// metadata.insert("xesam:artist", Value::from(vec!["Foo", "Bar"]));
+    /// let mut properties = HashMap::new();
+    /// properties.insert("Metadata", Value::from(metadata));
+    /// properties.insert("Position", Value::from((42u64, true)));
+    /// let value = Value::from(properties);
+    ///
+    /// assert_eq!(value.pointer("/Metadata/xesam:artist/1"), Some(&Value::from("Bar")));
+    /// assert_eq!(value.pointer("/Position/0"), Some(&Value::from(42u64)));
+    /// assert_eq!(value.pointer("/Metadata/xesam:album"), None);
+    /// assert_eq!(value.pointer(""), Some(&value));
+    /// ```
+    ///
+    /// [JSON Pointer]: https://www.rfc-editor.org/rfc/rfc6901
+    pub fn pointer(&self, pointer: &str) -> Option<&Value<'a>> {
+        pointer_tokens(pointer)?
+            .try_fold(self, |value, token| value.child(&token))
+            .map(|mut value| {
+                while let Value::Value(v) = value {
+                    value = v;
+                }
+
+                value
+            })
+    }
+
+    /// Replace the value at `pointer` with `value`, returning the replaced value.
+    ///
+    /// See [`Value::pointer`] for details on the pointer. If the replaced value is a variant,
+    /// `value` can be of any type and is wrapped in a variant (unless it already is one), otherwise
+    /// it must have the same signature as the replaced value.
+    ///
+    /// # Errors
+    ///
+    /// If there is no value at `pointer` or the signature of `value` doesn't match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let mut metadata = HashMap::new();
+    /// metadata.insert("xesam:title", Value::from("Moon"));
+    /// let mut value = Value::from((metadata, 42u32));
+    ///
+    /// value.replace_pointer("/0/xesam:title", Value::from(7u8)).unwrap();
+    /// assert_eq!(value.pointer("/0/xesam:title"), Some(&Value::from(7u8)));
+    /// value.replace_pointer("/1", Value::from(43u32)).unwrap();
+    /// assert!(value.replace_pointer("/1", Value::from("43")).is_err());
+    /// assert!(value.replace_pointer("/2", Value::from(43u32)).is_err());
+    /// ```
+    pub fn replace_pointer(
+        &mut self,
+        pointer: &str,
+        value: Value<'a>,
+    ) -> std::result::Result<Value<'a>, crate::Error> {
+        let target = match pointer_tokens(pointer) {
+            Some(mut tokens) => tokens.try_fold(self, |value, token| value.child_mut(&token)),
+            None => None,
+        }
+        .ok_or_else(|| crate::Error::Message(format!("no value at pointer `{pointer}`")))?;
+
+        let value = match (&*target, value) {
+            (Value::Value(_), value @ Value::Value(_)) => value,
+            (Value::Value(_), value) => Value::Value(Box::new(value)),
+            (target, value) => {
+                let (expected, signature) = (target.value_signature(), value.value_signature());
+                if expected != signature {
+                    return Err(crate::Error::SignatureMismatch(
+                        signature.to_owned(),
+                        format!("`{expected}`"),
+                    ));
+                }
+
+                value
+            }
+        };
+
+        Ok(std::mem::replace(target, value))
+    }
+
+    /// Look up a value by a [JSON Pointer] and convert it to `T`.
+    ///
+    /// See [`Value::pointer`] for details on the pointer.
+    ///
+    /// # Errors
+    ///
+    /// If the value can't be converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let mut metadata = HashMap::new();
+    /// metadata.insert("xesam:artist", Value::from(vec!["Foo", "Bar"]));
+    /// let value = Value::from(metadata);
+    ///
+    /// assert_eq!(value.get_as::<String>("/xesam:artist/0").unwrap(), Some("Foo".to_string()));
+    /// assert_eq!(value.get_as::<String>("/xesam:title").unwrap(), None);
+    /// assert!(value.get_as::<u32>("/xesam:artist/0").is_err());
+    /// ```
+    ///
+    /// [JSON Pointer]: https://www.rfc-editor.org/rfc/rfc6901
+    pub fn get_as<T>(&self, pointer: &str) -> std::result::Result<Option<T>, crate::Error>
+    where
+        T: TryFrom<Value<'a>>,
+        T::Error: Into<crate::Error>,
+    {
+        match self.pointer(pointer) {
+            Some(value) => T::try_from(value.clone()).map(Some).map_err(Into::into),
+            None => Ok(None),
+        }
+    }
+
+    // The child of `self` for the given pointer token, looking through variants.
+    fn child(&self, token: &str) -> Option<&Value<'a>> {
+        match self {
+            Value::Value(v) => v.child(token),
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(m) => m.inner().as_ref()?.child(token),
+            Value::Array(a) => a.get().get(pointer_index(token)?),
+            Value::Structure(s) => s.fields().get(pointer_index(token)?),
+            Value::Dict(d) => d
+                .iter()
+                .find(|(key, _)| pointer_key_matches(key, token))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn child_mut(&mut self, token: &str) -> Option<&mut Value<'a>> {
+        match self {
+            Value::Value(v) => v.child_mut(token),
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(m) => m.inner_mut().as_mut()?.child_mut(token),
+            Value::Array(a) => a.get_mut().get_mut(pointer_index(token)?),
+            Value::Structure(s) => s.fields_mut().get_mut(pointer_index(token)?),
+            Value::Dict(d) => d
+                .iter_mut()
+                .find(|(key, _)| pointer_key_matches(key, token))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+// Split a JSON Pointer into its unescaped reference tokens.
+fn pointer_tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+
+    Some(pointer.split('/').skip(1).map(|token| {
+        if token.contains('~') {
+            Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+        } else {
+            Cow::Borrowed(token)
+        }
+    }))
+}
+
+// Same rules as JSON Pointer array indices: no sign and no leading zeros.
+fn pointer_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() > 1) {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
+fn pointer_key_matches(key: &Value<'_>, token: &str) -> bool {
+    match key {
+        Value::U8(k) => token.parse() == Ok(*k),
+        Value::Bool(k) => token.parse() == Ok(*k),
+        Value::I16(k) => token.parse() == Ok(*k),
+        Value::U16(k) => token.parse() == Ok(*k),
+        Value::I32(k) => token.parse() == Ok(*k),
+        Value::U32(k) => token.parse() == Ok(*k),
+        Value::I64(k) => token.parse() == Ok(*k),
+        Value::U64(k) => token.parse() == Ok(*k),
+        Value::F64(k) => token.parse() == Ok(*k),
+        Value::Str(k) => k.as_str() == token,
+        Value::Signature(k) => k.as_str() == token,
+        Value::ObjectPath(k) => k.as_str() == token,
+        Value::Value(k) => pointer_key_matches(k, token),
+        _ => false,
+    }
 }
 
 impl<'a> Serialize for Value<'a> {
@@ -502,9 +731,9 @@ impl<'de, T> From<ValueSeed<'de, T>> for SignatureSeed<'de> {
     }
 }
 
-struct ValueSeed<'de, T> {
-    signature: Signature<'de>,
-    phantom: PhantomData<T>,
+pub(crate) struct ValueSeed<'de, T> {
+    pub(crate) signature: Signature<'de>,
+    pub(crate) phantom: PhantomData<T>,
 }
 
 impl<'de, T> ValueSeed<'de, T>
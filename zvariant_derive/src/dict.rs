@@ -1,5 +1,5 @@
 use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote};
 use syn::{punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Field};
 use zvariant_utils::{case, macros};
 
@@ -51,35 +51,81 @@ fn dict_name_for_field(
     }
 }
 
+// Whether the dictionary values of a struct with the given `signature` attribute are variants
+// (`a{sv}`), as opposed to being encoded as is (e.g `a{sa{sv}}`).
+fn values_are_variants(signature: Option<&str>, input: &DeriveInput) -> Result<bool, Error> {
+    match signature {
+        None | Some("dict") => Ok(true),
+        Some(s) if s.starts_with("a{") && s.ends_with('}') && s.len() > 4 => {
+            Ok(&s[3..s.len() - 1] == "v")
+        }
+        Some(s) => Err(Error::new(
+            input.span(),
+            format!("invalid signature `{s}` for a dictionary"),
+        )),
+    }
+}
+
 pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let StructAttributes {
+        rename_all,
+        signature,
+        ..
+    } = StructAttributes::parse(&input.attrs)?;
+    let variants = values_are_variants(signature.as_deref(), &input)?;
     let (name, data) = match input.data {
         Data::Struct(data) => (input.ident, data),
         _ => return Err(Error::new(input.span(), "only structs supported")),
     };
 
-    let StructAttributes { rename_all, .. } = StructAttributes::parse(&input.attrs)?;
-
     let zv = zvariant_path();
     let mut entries = quote! {};
     let mut num_entries: usize = 0;
+    let mut flattened = false;
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes {
+            rename, flatten, ..
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
+        if flatten {
+            if !variants {
+                return Err(Error::new(
+                    f.span(),
+                    "`flatten` attribute is only supported in `a{sv}` dictionaries",
+                ));
+            }
+            entries.extend(quote! {
+                #zv::SerializeDictEntries::serialize_dict_entries(&self.#name, map)?;
+            });
+            flattened = true;
+
+            continue;
+        }
         let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
 
         let is_option = macros::ty_is_option(&f.ty);
+        let value = if is_option {
+            quote!(self.#name.as_ref().unwrap())
+        } else {
+            quote!(&self.#name)
+        };
+        let value = if variants {
+            quote!(&#zv::SerializeValue(#value))
+        } else {
+            value
+        };
 
         let e = if is_option {
             quote! {
                 if self.#name.is_some() {
-                    map.serialize_entry(#dict_name, &#zv::SerializeValue(self.#name.as_ref().unwrap()))?;
+                    map.serialize_entry(#dict_name, #value)?;
                 }
             }
         } else {
             quote! {
-                map.serialize_entry(#dict_name, &#zv::SerializeValue(&self.#name))?;
+                map.serialize_entry(#dict_name, #value)?;
             }
         };
 
@@ -90,7 +136,41 @@ pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error>
     let generics = input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let num_entries = num_entries.to_token_stream();
+    // The number of entries of flattened fields is only known at runtime.
+    let num_entries = if flattened {
+        quote!(::std::option::Option::None)
+    } else {
+        quote!(::std::option::Option::Some(#num_entries))
+    };
+    // `a{sv}` structs can be flattened into other dictionaries.
+    let (entries, entries_impl) = if variants {
+        (
+            quote! {
+                #zv::SerializeDictEntries::serialize_dict_entries(self, &mut map)?;
+            },
+            quote! {
+                #[allow(deprecated)]
+                impl #impl_generics #zv::SerializeDictEntries for #name #ty_generics
+                #where_clause
+                {
+                    fn serialize_dict_entries<M>(
+                        &self,
+                        map: &mut M,
+                    ) -> ::std::result::Result<(), M::Error>
+                    where
+                        M: #zv::export::serde::ser::SerializeMap,
+                    {
+                        #entries
+
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            },
+        )
+    } else {
+        (entries, quote!())
+    };
+
     Ok(quote! {
         #[allow(deprecated)]
         impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
@@ -103,57 +183,150 @@ pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error>
                 use #zv::export::serde::ser::SerializeMap;
 
                 // zbus doesn't care about number of entries (it would need bytes instead)
-                let mut map = serializer.serialize_map(::std::option::Option::Some(#num_entries))?;
+                let mut map = serializer.serialize_map(#num_entries)?;
                 #entries
                 map.end()
             }
         }
+
+        #entries_impl
     })
 }
 
 pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
-    let (name, data) = match input.data {
-        Data::Struct(data) => (input.ident, data),
-        _ => return Err(Error::new(input.span(), "only structs supported")),
-    };
-
     let StructAttributes {
         rename_all,
         deny_unknown_fields,
+        strict,
+        signature,
         ..
     } = StructAttributes::parse(&input.attrs)?;
+    let variants = values_are_variants(signature.as_deref(), &input)?;
+    let (name, data) = match input.data {
+        Data::Struct(data) => (input.ident, data),
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
 
     let visitor = format_ident!("{}Visitor", name);
     let zv = zvariant_path();
     let mut fields = Vec::new();
-    let mut req_fields = Vec::new();
+    let mut entry_fields = Vec::new();
     let mut dict_names = Vec::new();
     let mut entries = Vec::new();
+    let mut finalizers = Vec::new();
+    let mut flattened = Vec::new();
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes {
+            rename,
+            required,
+            default,
+            flatten,
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
-        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
+        fields.push(name);
+
+        if flatten {
+            if rename.is_some() || required || default.is_some() {
+                return Err(Error::new(
+                    f.span(),
+                    "`flatten` attribute can't be combined with other attributes",
+                ));
+            }
+            if deny_unknown_fields {
+                return Err(Error::new(
+                    f.span(),
+                    "`flatten` attribute can't be combined with `deny_unknown_fields`",
+                ));
+            }
+            if !variants {
+                return Err(Error::new(
+                    f.span(),
+                    "`flatten` attribute is only supported in `a{sv}` dictionaries",
+                ));
+            }
+            flattened.push((name, &f.ty));
+
+            continue;
+        }
+        entry_fields.push(name);
 
+        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
         let is_option = macros::ty_is_option(&f.ty);
 
+        let value = if variants && !strict {
+            // Values of the wrong type are skipped, as if the entry was missing.
+            quote!(access.next_value::<#zv::DeserializeValueOpt<_>>()?.0)
+        } else if variants {
+            quote!(::std::option::Option::Some(
+                access.next_value::<#zv::DeserializeValue<_>>()?.0,
+            ))
+        } else {
+            quote!(::std::option::Option::Some(access.next_value()?))
+        };
         entries.push(quote! {
             #dict_name => {
-                // FIXME: add an option about strict parsing (instead of silently skipping the field)
-                #name = access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+                #name = #value;
             }
         });
 
-        dict_names.push(dict_name);
-        fields.push(name);
+        let missing = quote! {
+            return ::std::result::Result::Err(
+                <M::Error as #zv::export::serde::de::Error>::missing_field(#dict_name),
+            )
+        };
+        let finalizer = match default {
+            Some(_) if required => {
+                return Err(Error::new(
+                    f.span(),
+                    "`required` and `default` attributes can't be combined",
+                ))
+            }
+            Some(default) => {
+                let default: syn::Expr = syn::parse_str(&default).map_err(|e| {
+                    Error::new(f.span(), format!("invalid `default` expression: {e}"))
+                })?;
+
+                if is_option {
+                    quote! {
+                        let #name = if #name.is_some() { #name } else { #default };
+                    }
+                } else {
+                    quote! {
+                        let #name = match #name {
+                            ::std::option::Option::Some(val) => val,
+                            ::std::option::Option::None => #default,
+                        };
+                    }
+                }
+            }
+            None if is_option && !required => quote!(),
+            None if is_option => quote! {
+                if #name.is_none() {
+                    #missing;
+                }
+            },
+            None => quote! {
+                let #name = match #name {
+                    ::std::option::Option::Some(val) => val,
+                    ::std::option::Option::None => #missing,
+                };
+            },
+        };
+        finalizers.push(finalizer);
 
-        if !is_option {
-            req_fields.push(name);
-        }
+        dict_names.push(dict_name);
     }
 
-    let fallback = if deny_unknown_fields {
+    let fallback = if !flattened.is_empty() {
+        quote! {
+            unknown => {
+                let value = access.next_value::<#zv::OwnedValue>()?;
+                unknown_entries.insert(::std::string::String::from(unknown), value);
+            }
+        }
+    } else if deny_unknown_fields {
         quote! {
             field => {
                 return ::std::result::Result::Err(
@@ -166,13 +339,56 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
         }
     } else {
         quote! {
-            unknown => {
-                let _ = access.next_value::<#zv::Value>();
+            _ => {
+                access.next_value::<#zv::Value>()?;
             }
         }
     };
     entries.push(fallback);
 
+    // Flattened fields are built in order, each one removing the unknown entries it uses.
+    let unknown_entries = if flattened.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            let mut unknown_entries = ::std::collections::HashMap::new();
+        }
+    };
+    for (name, _) in &flattened {
+        finalizers.push(quote! {
+            let #name = #zv::DeserializeDictEntries::deserialize_dict_entries(&mut unknown_entries)
+                .map_err(<M::Error as #zv::export::serde::de::Error>::custom)?;
+        });
+    }
+
+    // Only owned `a{sv}` structs can be built from (owned) entries.
+    let entries_impl = if variants && input.generics.params.is_empty() {
+        let flattened_tys = flattened.iter().map(|(_, ty)| ty);
+
+        quote! {
+            #[allow(deprecated)]
+            impl #zv::DeserializeDictEntries for #name {
+                fn deserialize_dict_entries(
+                    entries: &mut ::std::collections::HashMap<::std::string::String, #zv::OwnedValue>,
+                ) -> #zv::Result<Self> {
+                    let value = #zv::from_dict_entries(entries)?;
+                    <Self as #zv::DeserializeDictEntries>::remove_dict_entries(entries);
+
+                    ::std::result::Result::Ok(value)
+                }
+
+                fn remove_dict_entries(
+                    entries: &mut ::std::collections::HashMap<::std::string::String, #zv::OwnedValue>,
+                ) {
+                    #( entries.remove(#dict_names); )*
+                    #( <#flattened_tys as #zv::DeserializeDictEntries>::remove_dict_entries(entries); )*
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
     let (_, ty_generics, _) = input.generics.split_for_impl();
     let mut generics = input.generics.clone();
     let def = syn::LifetimeDef {
@@ -213,7 +429,8 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                     where
                         M: #zv::export::serde::de::MapAccess<'de>,
                     {
-                        #( let mut #fields = ::std::default::Default::default(); )*
+                        #( let mut #entry_fields = ::std::default::Default::default(); )*
+                        #unknown_entries
 
                         // does not check duplicated fields, since those shouldn't exist in stream
                         while let ::std::option::Option::Some(key) = access.next_key::<&str>()? {
@@ -222,15 +439,7 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             }
                         }
 
-                        #(let #req_fields = if let ::std::option::Option::Some(val) = #req_fields {
-                            val
-                        } else {
-                            return ::std::result::Result::Err(
-                                <M::Error as #zv::export::serde::de::Error>::missing_field(
-                                    ::std::stringify!(#req_fields),
-                                ),
-                            );
-                        };)*
+                        #(#finalizers)*
 
                         ::std::result::Result::Ok(#name { #(#fields),* })
                     }
@@ -240,5 +449,7 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                 deserializer.deserialize_map(#visitor(::std::marker::PhantomData))
             }
         }
+
+        #entries_impl
     })
 }
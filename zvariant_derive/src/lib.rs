@@ -14,6 +14,7 @@ use proc_macro::TokenStream;
 use syn::{self, DeriveInput};
 
 mod dict;
+mod tagged;
 mod r#type;
 mod utils;
 mod value;
@@ -158,7 +159,15 @@ mod value;
 /// assert_eq!(decoded, StrEnum::Variant2);
 /// ```
 ///
+/// # Tagged enums
+///
+/// Enums with data-carrying variants of different types can be encoded as a structure of the
+/// variant tag and a variant holding the data, through the `tagged` attribute. By default the
+/// variant is identified by its name (signature `(sv)`) but `#[zvariant(tagged, signature =
+/// "(uv)")]` identifies it by its index instead. See [`SerializeTagged`] for details and an example.
+///
 /// [`Type`]: https://docs.rs/zvariant/2.10.0/zvariant/trait.Type.html
+/// [`SerializeTagged`]: derive.SerializeTagged.html
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
 /// [serde_repr]: https://crates.io/crates/serde_repr
@@ -243,7 +252,88 @@ pub fn type_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Optional fields
+///
+/// `Option` fields are only added to the dictionary when they're `Some`.
+///
+/// # Nested dictionaries and flattening
+///
+/// Fields can be other dictionary structs. By default, they're stored in a variant like all other
+/// values but dictionaries of dictionaries, like the `a{sa{sv}}` settings of NetworkManager, can be
+/// modeled by specifying the signature of the outer dictionary, in which case the values are stored
+/// as is:
+///
+/// ```
+/// use zvariant::{DeserializeDict, SerializeDict, Type};
+///
+/// #[derive(DeserializeDict, SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+///##[allow(unused)]
+/// struct Connection {
+///     id: String,
+///     autoconnect: Option<bool>,
+/// }
+///
+/// #[derive(DeserializeDict, SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+///##[allow(unused)]
+/// struct Ipv4 {
+///     method: String,
+/// }
+///
+/// #[derive(DeserializeDict, SerializeDict, Type)]
+/// #[zvariant(signature = "a{sa{sv}}")]
+///##[allow(unused)]
+/// struct Settings {
+///     connection: Connection,
+///     ipv4: Option<Ipv4>,
+/// }
+///
+/// assert_eq!(Settings::signature(), "a{sa{sv}}");
+/// ```
+///
+/// The fields of another `a{sv}` dictionary struct (or the entries of a `HashMap<String,
+/// OwnedValue>`) can also be merged into the dictionary through the `#[zvariant(flatten)]`
+/// attribute:
+///
+/// ```
+/// use zvariant::{DeserializeDict, SerializeDict, Type};
+///
+/// #[derive(DeserializeDict, SerializeDict, Type, Debug, PartialEq)]
+/// #[zvariant(signature = "a{sv}")]
+/// struct Common {
+///     title: String,
+/// }
+///
+/// #[derive(DeserializeDict, SerializeDict, Type, Debug, PartialEq)]
+/// #[zvariant(signature = "a{sv}", rename_all = "camelCase")]
+/// struct Track {
+///     #[zvariant(flatten)]
+///     common: Common,
+///     track_number: u32,
+/// }
+///
+/// // Encoded as `{"title": <"Song">, "trackNumber": <7>}`.
+/// let track = Track {
+///     common: Common { title: "Song".into() },
+///     track_number: 7,
+/// };
+/// let ctxt = zvariant::EncodingContext::<byteorder::LE>::new_dbus(0);
+/// let encoded = zvariant::to_bytes(ctxt, &track).unwrap();
+/// let decoded: Track = zvariant::from_slice(&encoded, ctxt).unwrap();
+/// assert_eq!(decoded, track);
+/// ```
+///
+/// Flattened fields must implement [`SerializeDictEntries`] (and [`DeserializeDictEntries`] for
+/// [`DeserializeDict`]), which this macro implements for `a{sv}` structs. On deserialization,
+/// flattened fields are built in order from the entries that don't match other fields of the
+/// struct, each one leaving out the entries used by the previous ones. A flattened map therefore
+/// only gets the entries no other field uses.
+///
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`SerializeDictEntries`]: ../zvariant/trait.SerializeDictEntries.html
+/// [`DeserializeDictEntries`]: ../zvariant/trait.DeserializeDictEntries.html
+/// [`DeserializeDict`]: derive.DeserializeDict.html
 #[proc_macro_derive(SerializeDict, attributes(zvariant))]
 pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -311,7 +401,40 @@ pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Missing, unknown and invalid entries
+///
+/// A missing entry for a non-`Option` field results in an error, unless the field has a
+/// `#[zvariant(default = "expression")]` attribute, in which case the field is set to the value of
+/// the expression. `Option` fields are set to `None` when their entry is missing, unless they're
+/// marked `#[zvariant(required)]`, in which case a missing entry is an error too.
+///
+/// Unknown entries are skipped by default. Use the `deny_unknown_fields` attribute on the struct to
+/// turn them into an error or mark a `HashMap<String, OwnedValue>` field with
+/// `#[zvariant(flatten)]` to collect them. [`SerializeDict`] serializes the entries of such a field
+/// back into the dictionary. See [`SerializeDict`] for flattening other dictionary structs.
+///
+/// In `a{sv}` dictionaries, an entry whose value doesn't match the type of its field is skipped, as
+/// if it were missing. Use the `strict` attribute on the struct to turn it into an error instead.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{DeserializeDict, OwnedValue, SerializeDict, Type};
+///
+/// #[derive(DeserializeDict, SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+///##[allow(unused)]
+/// struct Settings {
+///     #[zvariant(required)]
+///     id: Option<String>,
+///     #[zvariant(default = "1500")]
+///     mtu: u32,
+///     #[zvariant(flatten)]
+///     extensions: HashMap<String, OwnedValue>,
+/// }
+/// ```
+///
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+/// [`SerializeDict`]: derive.SerializeDict.html
 #[proc_macro_derive(DeserializeDict, attributes(zvariant))]
 pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -320,6 +443,76 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Adds [`Serialize`] implementation to enums tagged with the `tagged` attribute.
+///
+/// Each variant is serialized as a structure of two fields: the tag (the variant name or index)
+/// and a variant holding the data of the enum variant. Newtype variants hold their field, unit
+/// variants a `0u8` and all other variants a structure of their fields.
+///
+/// The variant names can be customized through the `rename` attribute on the variants or the
+/// `rename_all` attribute on the enum, which takes the same values as the one of
+/// [`SerializeDict`].
+///
+/// # Example
+///
+/// ```
+/// use zvariant::{
+///     from_slice, to_bytes, DeserializeTagged, EncodingContext, SerializeTagged, Type,
+/// };
+/// use byteorder::LE;
+///
+/// #[derive(DeserializeTagged, SerializeTagged, Type, PartialEq, Debug)]
+/// #[zvariant(tagged, rename_all = "snake_case")]
+/// enum Shape {
+///     Empty,
+///     Circle(f64),
+///     Rectangle { width: u32, height: u32 },
+///     #[zvariant(rename = "tri")]
+///     Triangle(u32, u32, u32),
+/// }
+///
+/// assert_eq!(Shape::signature(), "(sv)");
+/// let ctxt = EncodingContext::<LE>::new_dbus(0);
+/// let shapes = vec![
+///     Shape::Empty,
+///     Shape::Circle(2.5),
+///     Shape::Rectangle { width: 2, height: 3 },
+///     Shape::Triangle(3, 4, 5),
+/// ];
+/// let encoded = to_bytes(ctxt, &shapes).unwrap();
+/// let decoded: Vec<Shape> = from_slice(&encoded, ctxt).unwrap();
+/// assert_eq!(decoded, shapes);
+///
+/// // The same, built by hand.
+/// let encoded = to_bytes(ctxt, &("rectangle", zvariant::Value::from((2u32, 3u32)))).unwrap();
+/// let decoded: Shape = from_slice(&encoded, ctxt).unwrap();
+/// assert_eq!(decoded, Shape::Rectangle { width: 2, height: 3 });
+/// ```
+///
+/// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`SerializeDict`]: derive.SerializeDict.html
+#[proc_macro_derive(SerializeTagged, attributes(zvariant))]
+pub fn serialize_tagged_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    tagged::expand_serialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Adds [`Deserialize`] implementation to enums tagged with the `tagged` attribute.
+///
+/// See [`SerializeTagged`] for the encoding and an example. An unknown tag results in an error.
+///
+/// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+/// [`SerializeTagged`]: derive.SerializeTagged.html
+#[proc_macro_derive(DeserializeTagged, attributes(zvariant))]
+pub fn deserialize_tagged_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    tagged::expand_deserialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Implements conversions for your type to/from [`Value`].
 ///
 /// Implements `TryFrom<Value>` and `Into<Value>` for your type.
@@ -422,6 +615,53 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// assert_eq!(e, Enum::Variant2);
 /// ```
 ///
+/// Enums with data-carrying variants are supported if they're [tagged]:
+///
+/// ```
+///# use std::convert::TryFrom;
+///# use zvariant::{OwnedValue, Value};
+///#
+/// #[derive(Debug, PartialEq, Value, OwnedValue)]
+/// #[zvariant(tagged, signature = "(uv)")]
+/// enum Event {
+///     Started,
+///     Progress(u8),
+///     Finished { code: i32, message: String },
+/// }
+///
+/// let value = Value::from(Event::Progress(50));
+/// assert_eq!(value.value_signature(), "(uv)");
+/// let e = Event::try_from(value).unwrap();
+/// assert_eq!(e, Event::Progress(50));
+/// let value = OwnedValue::from(Event::Finished { code: 0, message: "done".into() });
+/// let e = Event::try_from(value).unwrap();
+/// assert_eq!(e, Event::Finished { code: 0, message: "done".into() });
+/// ```
+///
+/// Generic types are supported as well, as long as their type parameters can be converted from/to
+/// [`Value`]:
+///
+/// ```
+///# use std::convert::TryFrom;
+///# use zvariant::{OwnedValue, Type, Value};
+///#
+/// #[derive(Debug, PartialEq, Type, Value, OwnedValue)]
+/// struct Wrapper<T>(T);
+///
+/// #[derive(Debug, PartialEq, Type, Value, OwnedValue)]
+/// struct Pair<K, V> {
+///     key: K,
+///     value: V,
+/// }
+///
+/// let value = OwnedValue::from(Wrapper(42u32));
+/// assert_eq!(Wrapper::<u32>::try_from(value).unwrap(), Wrapper(42));
+/// let value = Value::from(Pair { key: "answer", value: 42u8 });
+/// assert_eq!(value.value_signature(), "(sy)");
+/// let pair = Pair::<String, u8>::try_from(OwnedValue::from(value)).unwrap();
+/// assert_eq!(pair.key, "answer");
+/// ```
+///
 /// # Dictionary encoding
 ///
 /// For treating your type as a dictionary, you can use the `signature = "dict"` attribute. See
@@ -430,7 +670,8 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 ///
 /// [`Value`]: https://docs.rs/zvariant/2.10.0/zvariant/enum.Value.html
 /// [`Type`]: derive.Type.html#custom-types
-#[proc_macro_derive(Value)]
+/// [tagged]: derive.Type.html#tagged-enums
+#[proc_macro_derive(Value, attributes(zvariant))]
 pub fn value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::Value)
@@ -445,7 +686,7 @@ pub fn value_macro_derive(input: TokenStream) -> TokenStream {
 /// See [`Value`] documentation for examples.
 ///
 /// [`OwnedValue`]: https://docs.rs/zvariant/2.10.0/zvariant/struct.OwnedValue.html
-#[proc_macro_derive(OwnedValue)]
+#[proc_macro_derive(OwnedValue, attributes(zvariant))]
 pub fn owned_value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::OwnedValue)
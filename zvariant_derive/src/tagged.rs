@@ -0,0 +1,378 @@
+use std::convert::TryFrom;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, Ident,
+    Variant,
+};
+use zvariant_utils::case;
+
+use crate::utils::*;
+
+/// How the variant of a tagged enum is identified.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TagKind {
+    /// By its name, encoded as `(sv)`.
+    Name,
+    /// By its index, encoded as `(uv)`.
+    Index,
+}
+
+impl TagKind {
+    pub fn signature(self) -> &'static str {
+        match self {
+            TagKind::Name => "(sv)",
+            TagKind::Index => "(uv)",
+        }
+    }
+}
+
+/// A variant of a tagged enum.
+pub struct TaggedVariant<'a> {
+    pub variant: &'a Variant,
+    /// The tag literal (a string or an `u32`).
+    pub tag: TokenStream,
+    /// The pattern matching the variant, binding its fields to `bindings`.
+    pub pattern: TokenStream,
+    /// The expression constructing the variant from `bindings`.
+    pub constructor: TokenStream,
+    pub bindings: Vec<Ident>,
+}
+
+/// The tag kind of `input` if it's a tagged enum, or `None` if it isn't tagged.
+pub fn tag_kind(input: &DeriveInput) -> Result<Option<TagKind>, Error> {
+    let StructAttributes {
+        signature, tagged, ..
+    } = StructAttributes::parse(&input.attrs)?;
+    if !tagged {
+        return Ok(None);
+    }
+    if !matches!(input.data, Data::Enum(_)) {
+        return Err(Error::new(
+            input.span(),
+            "`tagged` attribute is only supported on enums",
+        ));
+    }
+
+    match signature.as_deref() {
+        None | Some("(sv)") => Ok(Some(TagKind::Name)),
+        Some("(uv)") => Ok(Some(TagKind::Index)),
+        Some(other) => Err(Error::new(
+            input.span(),
+            format!("invalid signature `{other}` for a tagged enum, expected `(sv)` or `(uv)`"),
+        )),
+    }
+}
+
+pub fn tagged_variants<'a>(
+    name: &Ident,
+    data: &'a DataEnum,
+    kind: TagKind,
+    rename_all: Option<&str>,
+) -> Result<Vec<TaggedVariant<'a>>, Error> {
+    data.variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let VariantAttributes { rename } = VariantAttributes::parse(&variant.attrs)?;
+            let tag = match kind {
+                TagKind::Name => {
+                    let tag = match rename {
+                        Some(rename) => rename,
+                        None => tag_name(variant, rename_all)?,
+                    };
+
+                    quote!(#tag)
+                }
+                TagKind::Index => {
+                    if rename.is_some() {
+                        return Err(Error::new(
+                            variant.span(),
+                            "`rename` attribute is only supported with `(sv)` signature",
+                        ));
+                    }
+                    let index = u32::try_from(i)
+                        .map_err(|_| Error::new(variant.span(), "too many variants"))?;
+
+                    quote!(#index)
+                }
+            };
+
+            let ident = &variant.ident;
+            let (pattern, constructor, bindings) = match &variant.fields {
+                Fields::Unit => (quote!(#name::#ident), quote!(#name::#ident), vec![]),
+                Fields::Unnamed(fields) => {
+                    let bindings: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| format_ident!("__field{}", i))
+                        .collect();
+
+                    (
+                        quote!(#name::#ident(#(#bindings),*)),
+                        quote!(#name::#ident(#(#bindings),*)),
+                        bindings,
+                    )
+                }
+                Fields::Named(fields) => {
+                    let bindings: Vec<_> = fields
+                        .named
+                        .iter()
+                        .map(|f| f.ident.clone().unwrap())
+                        .collect();
+
+                    (
+                        quote!(#name::#ident { #(#bindings),* }),
+                        quote!(#name::#ident { #(#bindings),* }),
+                        bindings,
+                    )
+                }
+            };
+
+            Ok(TaggedVariant {
+                variant,
+                tag,
+                pattern,
+                constructor,
+                bindings,
+            })
+        })
+        .collect()
+}
+
+fn tag_name(variant: &Variant, rename_all: Option<&str>) -> Result<String, Error> {
+    let ident = variant.ident.to_string();
+
+    match rename_all {
+        Some("lowercase") => Ok(ident.to_ascii_lowercase()),
+        Some("UPPERCASE") => Ok(ident.to_ascii_uppercase()),
+        Some("PascalCase") => Ok(case::pascal_or_camel_case(&ident, true)),
+        Some("camelCase") => Ok(case::pascal_or_camel_case(&ident, false)),
+        Some("snake_case") => Ok(case::snake_case(&ident)),
+        None => Ok(ident),
+        Some(other) => Err(Error::new(
+            variant.span(),
+            format!("invalid `rename_all` attribute value {other}"),
+        )),
+    }
+}
+
+// The payload of a variant: unit variants carry a `0u8` (like empty structures), newtype variants
+// their field and all others a structure of their fields.
+fn payload_type(variant: &TaggedVariant<'_>) -> TokenStream {
+    let tys = variant.variant.fields.iter().map(|f| &f.ty);
+
+    match &variant.variant.fields {
+        Fields::Unit => quote!(u8),
+        Fields::Unnamed(f) if f.unnamed.len() == 1 => quote!(#(#tys)*),
+        _ => quote!((#(#tys,)*)),
+    }
+}
+
+fn parse_input(input: &DeriveInput) -> Result<(&DataEnum, TagKind, Option<String>), Error> {
+    let kind = tag_kind(input)?.ok_or_else(|| {
+        Error::new(
+            input.span(),
+            "expected an enum with `#[zvariant(tagged)]` attribute",
+        )
+    })?;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => unreachable!("`tag_kind` only accepts enums"),
+    };
+    let StructAttributes { rename_all, .. } = StructAttributes::parse(&input.attrs)?;
+
+    Ok((data, kind, rename_all))
+}
+
+pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let (data, kind, rename_all) = parse_input(&input)?;
+    let name = &input.ident;
+    let zv = zvariant_path();
+
+    let arms = tagged_variants(name, data, kind, rename_all.as_deref())?
+        .into_iter()
+        .map(|variant| {
+            let TaggedVariant {
+                tag,
+                pattern,
+                bindings,
+                ..
+            } = &variant;
+            let payload = match &variant.variant.fields {
+                Fields::Unit => quote!(&0u8),
+                Fields::Unnamed(f) if f.unnamed.len() == 1 => quote!(#(#bindings)*),
+                _ => quote!(&(#(#bindings,)*)),
+            };
+
+            quote! {
+                #pattern => {
+                    structure.serialize_field("tag", &#tag)?;
+                    structure.serialize_field("value", &#zv::SerializeValue(#payload))?;
+                }
+            }
+        });
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(#zv::export::serde::ser::Serialize));
+        param.bounds.push(syn::parse_quote!(#zv::Type));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
+        #where_clause
+        {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #zv::export::serde::ser::Serializer,
+            {
+                use #zv::export::serde::ser::SerializeStruct;
+
+                let mut structure = serializer.serialize_struct(::std::stringify!(#name), 2)?;
+                match self {
+                    #(#arms)*
+                }
+                structure.end()
+            }
+        }
+    })
+}
+
+pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let (data, kind, rename_all) = parse_input(&input)?;
+    let name = &input.ident;
+    let visitor = format_ident!("{}Visitor", name);
+    let zv = zvariant_path();
+
+    let variants = tagged_variants(name, data, kind, rename_all.as_deref())?;
+    let tags: Vec<_> = variants.iter().map(|v| v.tag.clone()).collect();
+    let arms = variants.iter().map(|variant| {
+        let TaggedVariant {
+            tag,
+            constructor,
+            bindings,
+            ..
+        } = variant;
+        let payload_type = payload_type(variant);
+        let bindings = match &variant.variant.fields {
+            Fields::Unit => quote!(_),
+            Fields::Unnamed(f) if f.unnamed.len() == 1 => quote!(#(#bindings)*),
+            _ => quote!((#(#bindings,)*)),
+        };
+
+        // Unit variants accept any payload.
+        let payload = if variant.variant.fields.is_empty() {
+            quote!(seq.next_element::<#zv::Value<'_>>()?.map(|_| ()))
+        } else {
+            quote!(seq.next_element::<#zv::DeserializeValue<'de, #payload_type>>()?.map(|v| v.0))
+        };
+
+        quote! {
+            #tag => {
+                let #bindings = #payload.ok_or_else(|| {
+                    <A::Error as #zv::export::serde::de::Error>::invalid_length(1, &self)
+                })?;
+
+                ::std::result::Result::Ok(#constructor)
+            }
+        }
+    });
+    let (tag_type, unknown_tag) = match kind {
+        TagKind::Name => (
+            quote!(&str),
+            quote! {
+                <A::Error as #zv::export::serde::de::Error>::unknown_variant(tag, &[#(#tags),*])
+            },
+        ),
+        TagKind::Index => {
+            let expected = format!("variant index 0 <= i < {}", tags.len());
+
+            (
+                quote!(u32),
+                quote! {
+                    <A::Error as #zv::export::serde::de::Error>::invalid_value(
+                        #zv::export::serde::de::Unexpected::Unsigned(tag.into()),
+                        &#expected,
+                    )
+                },
+            )
+        }
+    };
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    let lifetimes: Punctuated<_, _> = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    let def = syn::LifetimeDef {
+        attrs: Vec::new(),
+        lifetime: syn::Lifetime::new("'de", Span::call_site()),
+        colon_token: None,
+        bounds: lifetimes.clone(),
+    };
+    // `DeserializeValue<'de, T>` requires `T: 'de` so borrowed payloads must live exactly as long
+    // as the input.
+    let where_clause = generics.make_where_clause();
+    for lifetime in lifetimes {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#lifetime: 'de));
+    }
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(#zv::export::serde::de::Deserialize<'de>));
+        param.bounds.push(syn::parse_quote!(#zv::Type));
+    }
+    generics.params = Some(syn::GenericParam::Lifetime(def))
+        .into_iter()
+        .chain(generics.params)
+        .collect::<Punctuated<_, _>>();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::de::Deserialize<'de> for #name #ty_generics
+        #where_clause
+        {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: #zv::export::serde::de::Deserializer<'de>,
+            {
+                struct #visitor #ty_generics(::std::marker::PhantomData<#name #ty_generics>);
+
+                impl #impl_generics #zv::export::serde::de::Visitor<'de> for #visitor #ty_generics
+                #where_clause
+                {
+                    type Value = #name #ty_generics;
+
+                    fn expecting(&self, formatter: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        formatter.write_str(::std::concat!("enum ", ::std::stringify!(#name)))
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::std::result::Result<Self::Value, A::Error>
+                    where
+                        A: #zv::export::serde::de::SeqAccess<'de>,
+                    {
+                        let tag = seq.next_element::<#tag_type>()?.ok_or_else(|| {
+                            <A::Error as #zv::export::serde::de::Error>::invalid_length(0, &self)
+                        })?;
+
+                        match tag {
+                            #(#arms)*
+                            _ => ::std::result::Result::Err(#unknown_tag),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_struct(
+                    ::std::stringify!(#name),
+                    &["tag", "value"],
+                    #visitor(::std::marker::PhantomData),
+                )
+            }
+        }
+    })
+}
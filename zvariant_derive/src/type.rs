@@ -4,12 +4,16 @@ use syn::{
     self, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Fields, Generics, Ident,
 };
 
-use crate::utils::*;
+use crate::{tagged, utils::*};
 
 pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
     let StructAttributes { signature, .. } = StructAttributes::parse(&ast.attrs)?;
 
     let zv = zvariant_path();
+    let signature = match tagged::tag_kind(&ast)? {
+        Some(kind) => Some(kind.signature().to_string()),
+        None => signature,
+    };
     if let Some(signature) = signature {
         let signature = match signature.as_str() {
             "dict" => "a{sv}".to_string(),
@@ -64,6 +68,10 @@ fn impl_struct(
     fields: Fields,
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
+    let mut generics = generics;
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(#zv::Type));
+    }
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let signature = signature_for_struct(&fields, zv, false);
 
@@ -19,7 +19,11 @@ def_attrs! {
     crate zvariant;
 
     /// Attributes defined on structures.
-    pub StructAttributes("struct") { signature str, rename_all str, deny_unknown_fields none };
+    pub StructAttributes("struct") {
+        signature str, rename_all str, deny_unknown_fields none, strict none, tagged none
+    };
     /// Attributes defined on fields.
-    pub FieldAttributes("field") { rename str };
+    pub FieldAttributes("field") { rename str, required none, default str, flatten none };
+    /// Attributes defined on enum variants.
+    pub VariantAttributes("variant") { rename str };
 }
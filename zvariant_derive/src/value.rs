@@ -1,11 +1,14 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
-    self, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Expr, Fields, Generics,
-    Ident, Lifetime, LifetimeDef,
+    self, parse_quote, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Expr,
+    Fields, Generics, Ident, Lifetime, WhereClause,
 };
 
-use crate::utils::*;
+use crate::{
+    tagged::{self, TaggedVariant},
+    utils::*,
+};
 
 pub enum ValueType {
     Value,
@@ -35,7 +38,10 @@ pub fn expand_derive(ast: DeriveInput, value_type: ValueType) -> Result<TokenStr
             }
             Fields::Unit => Err(Error::new(ast.span(), "Unit structures not supported")),
         },
-        Data::Enum(data) => impl_enum(value_type, ast.ident, ast.generics, ast.attrs, data, &zv),
+        Data::Enum(data) => match tagged::tag_kind(&ast)? {
+            Some(kind) => impl_tagged_enum(value_type, &ast, kind, data, &zv),
+            None => impl_enum(value_type, ast.ident, ast.generics, ast.attrs, data, &zv),
+        },
         _ => Err(Error::new(
             ast.span(),
             "only structs and enums are supported",
@@ -51,45 +57,34 @@ fn impl_struct(
     signature: Option<String>,
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
-    let statc_lifetime = LifetimeDef::new(Lifetime::new("'static", Span::call_site()));
-    let (value_type, value_lifetime) = match value_type {
+    let owned = matches!(value_type, ValueType::OwnedValue);
+    let lifetime = value_lifetime(&value_type, &generics)?;
+    let is_newtype = matches!(fields, Fields::Unnamed(_));
+    let (value_type, from_value, into_value) = match value_type {
         ValueType::Value => {
-            let mut lifetimes = generics.lifetimes();
-            let value_lifetime = lifetimes
-                .next()
-                .cloned()
-                .unwrap_or_else(|| statc_lifetime.clone());
-            if lifetimes.next().is_some() {
-                return Err(Error::new(
-                    name.span(),
-                    "Type with more than 1 lifetime not supported",
-                ));
-            }
+            let value = quote! { #zv::Value<#lifetime> };
 
-            (quote! { #zv::Value<#value_lifetime> }, value_lifetime)
+            (value.clone(), value.clone(), value)
         }
-        ValueType::OwnedValue => (quote! { #zv::OwnedValue }, statc_lifetime),
-    };
+        // Newtypes are converted through the conversions of their field from/to `OwnedValue`.
+        ValueType::OwnedValue if is_newtype => {
+            let value = quote! { #zv::OwnedValue };
 
-    let type_params = generics.type_params().cloned().collect::<Vec<_>>();
-    let (from_value_where_clause, into_value_where_clause) = if !type_params.is_empty() {
-        (
-            Some(quote! {
-                where
-                #(
-                    #type_params: ::std::convert::TryFrom<#zv::Value<#value_lifetime>> + #zv::Type
-                ),*
-            }),
-            Some(quote! {
-                where
-                #(
-                    #type_params: ::std::convert::Into<#zv::Value<#value_lifetime>> + #zv::Type
-                ),*
-            }),
-        )
+            (value.clone(), value.clone(), value)
+        }
+        ValueType::OwnedValue => (
+            quote! { #zv::OwnedValue },
+            quote! { #zv::Value<'static> },
+            quote! { #zv::Value<#lifetime> },
+        ),
+    };
+    let into_value_bound = if is_newtype {
+        quote! { ::std::convert::Into<#into_value> }
     } else {
-        (None, None)
+        quote! { ::std::convert::Into<#into_value> + #zv::Type }
     };
+    let from_value_where_clause = from_value_where_clause(&generics, &from_value, owned, zv);
+    let into_value_where_clause = into_value_where_clause(&generics, &into_value_bound);
     let (impl_generics, ty_generics, _) = generics.split_for_impl();
     match fields {
         Fields::Named(_) => {
@@ -118,7 +113,10 @@ fn impl_struct(
                     quote! {
                         let mut fields = ::std::collections::HashMap::new();
                         #(
-                            fields.insert(stringify!(#field_names), #zv::Value::from(s.#field_names));
+                            fields.insert(
+                                stringify!(#field_names),
+                                ::std::convert::Into::<#zv::Value>::into(s.#field_names),
+                            );
                         )*
 
                         #zv::Value::from(fields).into()
@@ -180,7 +178,9 @@ fn impl_struct(
 
                     #[inline]
                     fn try_from(value: #value_type) -> #zv::Result<Self> {
-                        ::std::convert::TryInto::try_into(value).map(Self)
+                        ::std::convert::TryInto::try_into(value)
+                            .map(Self)
+                            .map_err(::std::convert::Into::into)
                     }
                 }
 
@@ -199,6 +199,68 @@ fn impl_struct(
     }
 }
 
+// The lifetime of the `Value` the type is converted from/to: its only lifetime, if any.
+fn value_lifetime(value_type: &ValueType, generics: &Generics) -> Result<Lifetime, Error> {
+    let mut lifetimes = generics.lifetimes();
+    let lifetime = lifetimes
+        .next()
+        .map(|l| l.lifetime.clone())
+        .unwrap_or_else(|| Lifetime::new("'static", Span::call_site()));
+    if let (ValueType::Value, Some(l)) = (value_type, lifetimes.next()) {
+        return Err(Error::new(
+            l.span(),
+            "Type with more than 1 lifetime not supported",
+        ));
+    }
+
+    Ok(lifetime)
+}
+
+// The where clause of the `TryFrom` impl: the type's own where clause, extended with the bounds on
+// its type parameters for converting from `value`. Conversions from `OwnedValue` can only produce
+// values of `'static` lifetimes.
+fn from_value_where_clause(
+    generics: &Generics,
+    value: &TokenStream,
+    static_lifetimes: bool,
+    zv: &TokenStream,
+) -> Option<WhereClause> {
+    let mut generics = generics.clone();
+    let lifetimes: Vec<_> = generics.lifetimes().map(|l| l.lifetime.clone()).collect();
+    let type_params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    if static_lifetimes {
+        for lifetime in lifetimes {
+            where_clause
+                .predicates
+                .push(parse_quote!(#lifetime: 'static));
+        }
+    }
+    for param in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::std::convert::TryFrom<#value>));
+        where_clause.predicates.push(parse_quote!(
+            <#param as ::std::convert::TryFrom<#value>>::Error: ::std::convert::Into<#zv::Error>
+        ));
+    }
+
+    generics.where_clause.filter(|w| !w.predicates.is_empty())
+}
+
+// The where clause of the `From` impl: the type's own where clause, extended with `bound` on its
+// type parameters.
+fn into_value_where_clause(generics: &Generics, bound: &TokenStream) -> Option<WhereClause> {
+    let mut generics = generics.clone();
+    let type_params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    generics.where_clause.filter(|w| !w.predicates.is_empty())
+}
+
 fn impl_enum(
     value_type: ValueType,
     name: Ident,
@@ -275,3 +337,156 @@ fn impl_enum(
         }
     })
 }
+
+fn impl_tagged_enum(
+    value_type: ValueType,
+    ast: &DeriveInput,
+    kind: tagged::TagKind,
+    data: &DataEnum,
+    zv: &TokenStream,
+) -> Result<TokenStream, Error> {
+    let name = &ast.ident;
+    let owned = matches!(value_type, ValueType::OwnedValue);
+    let lifetime = value_lifetime(&value_type, &ast.generics)?;
+    let StructAttributes { rename_all, .. } = StructAttributes::parse(&ast.attrs)?;
+    let variants = tagged::tagged_variants(name, data, kind, rename_all.as_deref())?;
+
+    let mut from_value_arms = vec![];
+    let mut into_value_arms = vec![];
+    for variant in &variants {
+        let TaggedVariant {
+            tag,
+            pattern,
+            constructor,
+            bindings,
+            ..
+        } = variant;
+        let (from_payload, into_payload) = match &variant.variant.fields {
+            Fields::Unit => (quote!(), quote!(#zv::Value::from(0u8))),
+            Fields::Unnamed(f) if f.unnamed.len() == 1 => (
+                quote! {
+                    let #(#bindings)* = ::std::convert::TryInto::try_into(payload)
+                        .map_err(::std::convert::Into::<#zv::Error>::into)?;
+                },
+                quote!(::std::convert::Into::<#zv::Value>::into(#(#bindings)*)),
+            ),
+            fields => {
+                let len = fields.len();
+
+                (
+                    quote! {
+                        let mut fields = #zv::Structure::try_from(payload)?.into_fields();
+                        if fields.len() != #len {
+                            return ::std::result::Result::Err(#zv::Error::IncorrectType);
+                        }
+                        #(
+                            let #bindings = fields
+                                .remove(0)
+                                .downcast()
+                                .ok_or_else(|| #zv::Error::IncorrectType)?;
+                        )*
+                    },
+                    quote! {
+                        #zv::Value::from(
+                            #zv::StructureBuilder::new()
+                            #(
+                                .add_field(#bindings)
+                            )*
+                            .build()
+                        )
+                    },
+                )
+            }
+        };
+
+        from_value_arms.push(quote! {
+            #tag => {
+                #from_payload
+
+                #constructor
+            }
+        });
+        into_value_arms.push(quote! {
+            #pattern => (#zv::Value::from(#tag), #into_payload)
+        });
+    }
+
+    let (value_type, to_value, from_value) = match value_type {
+        ValueType::Value => (
+            quote! { #zv::Value<#lifetime> },
+            quote!(value),
+            quote! { #zv::Value<#lifetime> },
+        ),
+        ValueType::OwnedValue => (
+            quote! { #zv::OwnedValue },
+            quote!(<#zv::Value<'static> as ::std::convert::From<_>>::from(value)),
+            quote! { #zv::Value<'static> },
+        ),
+    };
+    let from_value_where_clause = from_value_where_clause(&ast.generics, &from_value, owned, zv);
+    let into_value_where_clause = into_value_where_clause(
+        &ast.generics,
+        &quote! { ::std::convert::Into<#zv::Value<#lifetime>> + #zv::Type },
+    );
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let tag = match kind {
+        tagged::TagKind::Name => quote! {
+            match tag {
+                #zv::Value::Str(tag) => tag,
+                _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+            }
+            .as_str()
+        },
+        tagged::TagKind::Index => quote! {
+            match tag {
+                #zv::Value::U32(tag) => tag,
+                _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::convert::TryFrom<#value_type> for #name #ty_generics
+            #from_value_where_clause
+        {
+            type Error = #zv::Error;
+
+            #[inline]
+            fn try_from(value: #value_type) -> #zv::Result<Self> {
+                let mut fields = #zv::Structure::try_from(#to_value)?.into_fields();
+                if fields.len() != 2 {
+                    return ::std::result::Result::Err(#zv::Error::IncorrectType);
+                }
+                let payload = match fields.remove(1) {
+                    #zv::Value::Value(payload) => *payload,
+                    _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+                };
+                let tag = fields.remove(0);
+
+                ::std::result::Result::Ok(match #tag {
+                    #(#from_value_arms),*
+                    _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+                })
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for #value_type
+            #into_value_where_clause
+        {
+            #[inline]
+            fn from(e: #name #ty_generics) -> Self {
+                let (tag, payload) = match e {
+                    #(#into_value_arms),*
+                };
+
+                <#zv::Value as ::std::convert::From<_>>::from(
+                    #zv::StructureBuilder::new()
+                        .append_field(tag)
+                        .append_field(#zv::Value::Value(::std::boxed::Box::new(payload)))
+                        .build(),
+                )
+                .into()
+            }
+        }
+    })
+}
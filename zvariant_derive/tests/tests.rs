@@ -2,8 +2,10 @@
 
 use byteorder::LE;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use zvariant::{
-    DeserializeDict, EncodingContext, EncodingFormat, OwnedValue, SerializeDict, Type, Value,
+    DeserializeDict, DeserializeTagged, EncodingContext, EncodingFormat, OwnedValue, SerializeDict,
+    SerializeTagged, Str, Structure, Type, Value,
 };
 
 #[test]
@@ -74,3 +76,341 @@ fn derive_dict() {
 
     assert_eq!(Test::signature(), "a{sv}")
 }
+
+#[test]
+fn derive_tagged_enum() {
+    #[derive(
+        SerializeTagged, DeserializeTagged, Type, Value, OwnedValue, Debug, Clone, PartialEq,
+    )]
+    #[zvariant(tagged, rename_all = "snake_case")]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rectangle {
+            width: u32,
+            height: u32,
+        },
+        #[zvariant(rename = "tri")]
+        Triangle(u32, u32, String),
+    }
+
+    assert_eq!(Shape::signature(), "(sv)");
+
+    let shapes = vec![
+        Shape::Empty,
+        Shape::Circle(1.5),
+        Shape::Rectangle {
+            width: 2,
+            height: 3,
+        },
+        Shape::Triangle(3, 4, "right".to_string()),
+    ];
+    for format in [EncodingFormat::DBus, EncodingFormat::GVariant] {
+        let ctxt = EncodingContext::<LE>::new(format, 0);
+        let serialized = zvariant::to_bytes(ctxt, &shapes).unwrap();
+        let deserialized: Vec<(String, OwnedValue)> =
+            zvariant::from_slice(&serialized, ctxt).unwrap();
+        assert_eq!(
+            deserialized[0],
+            ("empty".to_string(), Value::from(0u8).into())
+        );
+        assert_eq!(
+            deserialized[1],
+            ("circle".to_string(), Value::from(1.5).into())
+        );
+        assert_eq!(
+            deserialized[2],
+            ("rectangle".to_string(), Value::from((2u32, 3u32)).into())
+        );
+        assert_eq!(deserialized[3].0, "tri");
+
+        let deserialized: Vec<Shape> = zvariant::from_slice(&serialized, ctxt).unwrap();
+        assert_eq!(deserialized, shapes);
+
+        let unknown = zvariant::to_bytes(ctxt, &("square", Value::from(2u32))).unwrap();
+        assert!(zvariant::from_slice::<_, Shape>(&unknown, ctxt).is_err());
+        let mismatch = zvariant::to_bytes(ctxt, &("circle", Value::from(2u32))).unwrap();
+        assert!(zvariant::from_slice::<_, Shape>(&mismatch, ctxt).is_err());
+    }
+
+    for shape in shapes {
+        let value = Value::from(shape.clone());
+        assert_eq!(value.value_signature(), "(sv)");
+        assert_eq!(Shape::try_from(value).unwrap(), shape);
+        let value = OwnedValue::from(shape.clone());
+        assert_eq!(Shape::try_from(value).unwrap(), shape);
+    }
+    let value = Value::from(Shape::Circle(1.5));
+    let fields = Structure::try_from(value).unwrap().into_fields();
+    assert_eq!(fields[0], Value::from("circle"));
+    assert_eq!(fields[1], Value::new(Value::from(1.5)));
+}
+
+#[test]
+fn derive_index_tagged_enum() {
+    #[derive(SerializeTagged, DeserializeTagged, Type, Value, Debug, PartialEq)]
+    #[zvariant(tagged, signature = "(uv)")]
+    enum Event<'a> {
+        Started,
+        Progress(u8),
+        Finished { code: i32, message: Str<'a> },
+    }
+
+    assert_eq!(Event::signature(), "(uv)");
+
+    let ctxt = EncodingContext::<LE>::new(EncodingFormat::DBus, 0);
+    let event = Event::Finished {
+        code: 1,
+        message: Str::from("failed"),
+    };
+    let serialized = zvariant::to_bytes(ctxt, &event).unwrap();
+    let (index, _): (u32, Value<'_>) = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(index, 2);
+    let deserialized: Event<'_> = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized, event);
+
+    let unknown = zvariant::to_bytes(ctxt, &(3u32, Value::from(0u8))).unwrap();
+    assert!(zvariant::from_slice::<_, Event<'_>>(&unknown, ctxt).is_err());
+}
+
+#[test]
+fn derive_dict_defaults_and_unknown_entries() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "dict")]
+    struct Settings {
+        #[zvariant(required)]
+        id: Option<String>,
+        #[zvariant(default = "1500")]
+        mtu: u32,
+        #[zvariant(default = "Some(vec![1, 2])")]
+        list: Option<Vec<u8>>,
+        #[zvariant(flatten)]
+        extensions: HashMap<String, OwnedValue>,
+    }
+
+    #[derive(DeserializeDict, Type, Debug)]
+    #[zvariant(signature = "dict", deny_unknown_fields)]
+    struct Strict {
+        id: String,
+    }
+
+    #[derive(DeserializeDict, Type, Debug)]
+    #[zvariant(signature = "dict", strict)]
+    #[allow(unused)]
+    struct StrictTypes {
+        id: String,
+        #[zvariant(default = "1500")]
+        mtu: u32,
+    }
+
+    let ctxt = EncodingContext::<LE>::new(EncodingFormat::DBus, 0);
+    let mut dict = HashMap::new();
+    dict.insert("id", Value::from("eth0"));
+    dict.insert("vendor-x", Value::from(42u32));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let settings: Settings = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(settings.id.as_deref(), Some("eth0"));
+    assert_eq!(settings.mtu, 1500);
+    assert_eq!(settings.list, Some(vec![1, 2]));
+    assert_eq!(settings.extensions.len(), 1);
+    assert_eq!(settings.extensions["vendor-x"], Value::from(42u32).into());
+    assert!(zvariant::from_slice::<_, Strict>(&serialized, ctxt).is_err());
+
+    // Unknown entries are written back.
+    let serialized = zvariant::to_bytes(ctxt, &settings).unwrap();
+    let deserialized: HashMap<String, OwnedValue> =
+        zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized.len(), 4);
+    assert_eq!(deserialized["vendor-x"], Value::from(42u32).into());
+    let deserialized: Settings = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized, settings);
+
+    // Missing required entry.
+    dict.remove("id");
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    assert!(zvariant::from_slice::<_, Settings>(&serialized, ctxt).is_err());
+
+    // Entries of the wrong type are skipped, unless strict.
+    dict.insert("id", Value::from("eth0"));
+    dict.insert("mtu", Value::from("big"));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let settings: Settings = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(settings.id.as_deref(), Some("eth0"));
+    assert_eq!(settings.mtu, 1500);
+    assert!(zvariant::from_slice::<_, StrictTypes>(&serialized, ctxt).is_err());
+}
+
+#[test]
+fn derive_nested_and_flattened_dicts() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sv}")]
+    struct Connection {
+        id: String,
+        autoconnect: Option<bool>,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sv}")]
+    struct Ipv4 {
+        method: String,
+        #[zvariant(flatten)]
+        rest: HashMap<String, OwnedValue>,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sa{sv}}")]
+    struct Settings {
+        connection: Connection,
+        ipv4: Option<Ipv4>,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sv}")]
+    struct Extended {
+        #[zvariant(flatten)]
+        connection: Connection,
+        #[zvariant(flatten)]
+        extra: HashMap<String, OwnedValue>,
+        nested: Option<Connection>,
+    }
+
+    assert_eq!(Settings::signature(), "a{sa{sv}}");
+
+    let ctxt = EncodingContext::<LE>::new(EncodingFormat::DBus, 0);
+    let settings = Settings {
+        connection: Connection {
+            id: "wired".to_string(),
+            autoconnect: None,
+        },
+        ipv4: None,
+    };
+    let serialized = zvariant::to_bytes(ctxt, &settings).unwrap();
+    let deserialized: HashMap<String, HashMap<String, OwnedValue>> =
+        zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized.len(), 1);
+    assert_eq!(deserialized["connection"].len(), 1);
+    assert_eq!(
+        deserialized["connection"]["id"],
+        Value::from("wired").into()
+    );
+    let deserialized: Settings = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized, settings);
+
+    let mut rest = HashMap::new();
+    rest.insert("dns".to_string(), Value::from(vec![1u32]).into());
+    let settings = Settings {
+        connection: Connection {
+            id: "wired".to_string(),
+            autoconnect: Some(true),
+        },
+        ipv4: Some(Ipv4 {
+            method: "auto".to_string(),
+            rest,
+        }),
+    };
+    let serialized = zvariant::to_bytes(ctxt, &settings).unwrap();
+    let deserialized: Settings = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized, settings);
+
+    let mut extra = HashMap::new();
+    extra.insert("mtu".to_string(), Value::from(1500u32).into());
+    let extended = Extended {
+        connection: Connection {
+            id: "wifi".to_string(),
+            autoconnect: Some(false),
+        },
+        extra,
+        nested: Some(Connection {
+            id: "nested".to_string(),
+            autoconnect: None,
+        }),
+    };
+    let serialized = zvariant::to_bytes(ctxt, &extended).unwrap();
+    let deserialized: HashMap<String, OwnedValue> =
+        zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized.len(), 4);
+    assert_eq!(deserialized["id"], Value::from("wifi").into());
+    assert_eq!(deserialized["autoconnect"], Value::from(false).into());
+    assert_eq!(deserialized["mtu"], Value::from(1500u32).into());
+    assert_eq!(deserialized["nested"].value_signature(), "a{sv}");
+    // The map only gets the entries not used by `connection`.
+    let deserialized: Extended = zvariant::from_slice(&serialized, ctxt).unwrap();
+    assert_eq!(deserialized, extended);
+}
+
+#[test]
+fn derive_generic_value() {
+    #[derive(Type, Value, OwnedValue, Debug, PartialEq)]
+    struct Wrapper<T>(T);
+
+    #[derive(Type, Value, OwnedValue, Debug, PartialEq)]
+    struct Pair<'a, T>
+    where
+        T: Clone,
+    {
+        name: Str<'a>,
+        value: T,
+    }
+
+    #[derive(Type, Value, OwnedValue, Debug, PartialEq)]
+    #[zvariant(signature = "dict")]
+    struct Props<T> {
+        value: T,
+        values: Vec<T>,
+    }
+
+    #[derive(Type, Value, OwnedValue, Debug, PartialEq)]
+    #[zvariant(tagged)]
+    enum Either<L, R> {
+        Left(L),
+        Right { value: R, count: u32 },
+    }
+
+    let value = Value::from(Wrapper(42u32));
+    assert_eq!(value, Value::U32(42));
+    assert_eq!(Wrapper::<u32>::try_from(value).unwrap(), Wrapper(42));
+    let value = OwnedValue::from(Wrapper(42u32));
+    assert_eq!(Wrapper::<u32>::try_from(value).unwrap(), Wrapper(42));
+    assert!(Wrapper::<String>::try_from(OwnedValue::from(42u32)).is_err());
+
+    let pair = Pair {
+        name: Str::from("answer"),
+        value: 42u8,
+    };
+    let value = Value::from(Pair {
+        name: pair.name.clone(),
+        value: 42u8,
+    });
+    assert_eq!(value.value_signature(), "(sy)");
+    assert_eq!(Pair::try_from(value).unwrap(), pair);
+    let value = OwnedValue::from(Pair {
+        name: pair.name.clone(),
+        value: 42u8,
+    });
+    assert_eq!(Pair::try_from(value).unwrap(), pair);
+
+    let props = Props {
+        value: "a".to_string(),
+        values: vec!["b".to_string(), "c".to_string()],
+    };
+    let value = OwnedValue::from(Props {
+        value: props.value.clone(),
+        values: props.values.clone(),
+    });
+    assert_eq!(value.value_signature(), "a{sv}");
+    assert_eq!(Props::<String>::try_from(value).unwrap(), props);
+
+    let left = Either::<u8, String>::Left(7);
+    let value = Value::from(Either::<u8, String>::Left(7));
+    assert_eq!(value.value_signature(), "(sv)");
+    assert_eq!(Either::try_from(value).unwrap(), left);
+    let right = Either::<u8, String>::Right {
+        value: "r".to_string(),
+        count: 2,
+    };
+    let value = OwnedValue::from(Either::<u8, String>::Right {
+        value: "r".to_string(),
+        count: 2,
+    });
+    assert_eq!(Either::try_from(value).unwrap(), right);
+}